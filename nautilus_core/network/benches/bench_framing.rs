@@ -0,0 +1,138 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::hint::black_box;
+
+use bytes::BytesMut;
+use criterion::{criterion_group, criterion_main, Criterion};
+use nautilus_network::framing::{FrameDecoder, Framing, SuffixFinder};
+
+const READ_SIZE: usize = 4096;
+
+/// Builds a stream of JSON-like lines resembling a busy market data feed.
+fn build_stream(suffix: &[u8]) -> Vec<u8> {
+    (0..10_000)
+        .flat_map(|i| {
+            let mut line = format!(r#"{{"type":"quote","seq":{i},"bid":"100.25","ask":"100.50"}}"#)
+                .into_bytes();
+            line.extend_from_slice(suffix);
+            line
+        })
+        .collect()
+}
+
+/// Splits the stream the way the read task did before using `SuffixFinder`.
+fn split_windows(stream: &[u8], suffix: &[u8]) -> usize {
+    let mut buf = Vec::new();
+    let mut count = 0;
+    for chunk in stream.chunks(READ_SIZE) {
+        buf.extend_from_slice(chunk);
+        while let Some((i, _)) = buf
+            .windows(suffix.len())
+            .enumerate()
+            .find(|(_, pair)| pair.eq(&suffix))
+        {
+            let data: Vec<u8> = buf.drain(0..i + suffix.len()).collect();
+            black_box(data);
+            count += 1;
+        }
+    }
+    count
+}
+
+fn split_finder(stream: &[u8], suffix: &[u8]) -> usize {
+    let mut finder = SuffixFinder::new(suffix);
+    let mut buf = Vec::new();
+    let mut count = 0;
+    for chunk in stream.chunks(READ_SIZE) {
+        buf.extend_from_slice(chunk);
+        while let Some(i) = finder.find(&buf) {
+            let consumed = i + finder.suffix_len();
+            let data: Vec<u8> = buf.drain(0..consumed).collect();
+            finder.consume(consumed);
+            black_box(data);
+            count += 1;
+        }
+    }
+    count
+}
+
+fn split_decoder(stream: &[u8], suffix: &[u8]) -> usize {
+    let mut decoder = FrameDecoder::new(&Framing::Delimiter(suffix.to_vec()));
+    let mut count = 0;
+    for chunk in stream.chunks(READ_SIZE) {
+        decoder.read_buf().extend_from_slice(chunk);
+        while let Ok(Some(data)) = decoder.decode() {
+            black_box(data);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Decodes a short burst per connection, as seen by a feed which reconnects often.
+fn decode_connections(stream: &[u8], framing: &Framing, reuse: bool) -> usize {
+    let mut pooled = Some(BytesMut::new());
+    let mut count = 0;
+    for connection in stream.chunks(READ_SIZE * 4) {
+        let mut decoder = FrameDecoder::new(framing);
+        if reuse {
+            decoder = decoder.with_buffer(pooled.take().unwrap_or_default());
+        }
+        for chunk in connection.chunks(READ_SIZE) {
+            decoder.read_buf().extend_from_slice(chunk);
+            while let Ok(Some(data)) = decoder.decode() {
+                black_box(data);
+                count += 1;
+            }
+        }
+        if reuse {
+            pooled = Some(decoder.take_buffer());
+        }
+    }
+    count
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for (name, suffix) in [("lf", b"\n".as_slice()), ("crlf", b"\r\n".as_slice())] {
+        let stream = build_stream(suffix);
+        let mut group = c.benchmark_group(format!("split_{name}"));
+
+        group.bench_function("windows", |b| {
+            b.iter(|| split_windows(black_box(&stream), suffix));
+        });
+        group.bench_function("suffix_finder", |b| {
+            b.iter(|| split_finder(black_box(&stream), suffix));
+        });
+        group.bench_function("frame_decoder", |b| {
+            b.iter(|| split_decoder(black_box(&stream), suffix));
+        });
+        group.finish();
+    }
+
+    let stream = build_stream(b"\n");
+    let framing = Framing::Delimiter(b"\n".to_vec());
+    let mut group = c.benchmark_group("reconnect_read_buffer");
+    group.bench_function("fresh", |b| {
+        b.iter(|| decode_connections(black_box(&stream), &framing, false));
+    });
+    group.bench_function("reused", |b| {
+        b.iter(|| decode_connections(black_box(&stream), &framing, true));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
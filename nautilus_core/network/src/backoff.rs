@@ -0,0 +1,350 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Exponential backoff with optional jitter, and a circuit breaker, for reconnection attempts.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+/// Policy describing how the delay between reconnection attempts grows.
+///
+/// The delay starts at `delay_initial` and is multiplied by `factor` after each
+/// consecutive failure, capped at `delay_max`. When `jitter` is set, each delay is
+/// randomized by up to that fraction in either direction so that many clients
+/// reconnecting at once do not synchronize.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackoffPolicy {
+    /// The delay before the first retry.
+    pub delay_initial: Duration,
+    /// The maximum delay between retries.
+    pub delay_max: Duration,
+    /// The multiplier applied to the delay after each failure.
+    pub factor: f64,
+    /// The optional jitter fraction in the range [0, 1].
+    pub jitter: Option<f64>,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            delay_initial: Duration::from_millis(1_000),
+            delay_max: Duration::from_secs(30),
+            factor: 2.0,
+            jitter: Some(0.1),
+        }
+    }
+}
+
+/// Stateful exponential backoff driven by a [`BackoffPolicy`].
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    policy: BackoffPolicy,
+    delay_current: Duration,
+}
+
+impl ExponentialBackoff {
+    /// Creates a new [`ExponentialBackoff`] instance.
+    #[must_use]
+    pub fn new(policy: BackoffPolicy) -> Self {
+        Self {
+            policy,
+            delay_current: policy.delay_initial,
+        }
+    }
+
+    /// Returns the delay to wait before the next attempt and advances the backoff.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.delay_current;
+        let next = Duration::try_from_secs_f64(delay.as_secs_f64() * self.policy.factor.max(1.0))
+            .unwrap_or(self.policy.delay_max);
+        self.delay_current = next.min(self.policy.delay_max);
+        self.apply_jitter(delay)
+    }
+
+    /// Resets the delay to its initial value (called after a successful reconnect).
+    pub fn reset(&mut self) {
+        self.delay_current = self.policy.delay_initial;
+    }
+
+    /// Returns the current un-jittered delay.
+    #[must_use]
+    pub const fn current_delay(&self) -> Duration {
+        self.delay_current
+    }
+
+    fn apply_jitter(&self, delay: Duration) -> Duration {
+        match self.policy.jitter {
+            Some(jitter) if jitter > 0.0 => {
+                let jitter = jitter.min(1.0);
+                let scale = rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+                Duration::try_from_secs_f64(delay.as_secs_f64() * scale).unwrap_or(delay)
+            }
+            _ => delay,
+        }
+    }
+}
+
+/// Policy suspending reconnection attempts after repeated failures.
+///
+/// When `failure_threshold` consecutive attempts fail within `window` the breaker opens
+/// and no attempt is made for `cooldown`. A single trial attempt is then allowed, which
+/// closes the breaker if it succeeds or opens it for another `cooldown` if it fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    /// The number of consecutive failed attempts which opens the breaker.
+    pub failure_threshold: u32,
+    /// The window within which the failed attempts must occur.
+    pub window: Duration,
+    /// The time attempts are suspended for while the breaker is open.
+    pub cooldown: Duration,
+}
+
+/// The state of a [`CircuitBreaker`].
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub enum CircuitState {
+    /// Attempts are made as usual.
+    #[default]
+    Closed = 0,
+    /// Attempts are suspended until the cooldown elapses.
+    Open = 1,
+    /// A single trial attempt is allowed after the cooldown.
+    HalfOpen = 2,
+}
+
+impl From<u8> for CircuitState {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Closed,
+            1 => Self::Open,
+            2 => Self::HalfOpen,
+            _ => panic!("Invalid `CircuitState` value, was {value}"),
+        }
+    }
+}
+
+/// Stateful circuit breaker driven by a [`CircuitBreakerPolicy`].
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    state: CircuitState,
+    failures: VecDeque<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Creates a new [`CircuitBreaker`] instance.
+    #[must_use]
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: CircuitState::Closed,
+            failures: VecDeque::new(),
+        }
+    }
+
+    /// Returns the current state.
+    #[must_use]
+    pub const fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Records a failed attempt made at `now`.
+    ///
+    /// Returns the cooldown to wait before the next attempt if the breaker opened.
+    pub fn record_failure(&mut self, now: Instant) -> Option<Duration> {
+        if self.state != CircuitState::HalfOpen {
+            self.failures.push_back(now);
+            while self
+                .failures
+                .front()
+                .is_some_and(|&failed| now.duration_since(failed) > self.policy.window)
+            {
+                self.failures.pop_front();
+            }
+            if self.failures.len() < self.policy.failure_threshold as usize {
+                return None;
+            }
+        }
+        self.state = CircuitState::Open;
+        self.failures.clear();
+        Some(self.policy.cooldown)
+    }
+
+    /// Records a successful attempt, closing the breaker.
+    pub fn record_success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.failures.clear();
+    }
+
+    /// Allows a single trial attempt once the cooldown has elapsed.
+    pub fn half_open(&mut self) {
+        if self.state == CircuitState::Open {
+            self.state = CircuitState::HalfOpen;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn policy(jitter: Option<f64>) -> BackoffPolicy {
+        BackoffPolicy {
+            delay_initial: Duration::from_millis(100),
+            delay_max: Duration::from_millis(1_000),
+            factor: 2.0,
+            jitter,
+        }
+    }
+
+    #[rstest]
+    fn test_delay_sequence_grows_to_cap() {
+        let mut backoff = ExponentialBackoff::new(policy(None));
+        let delays: Vec<u64> = (0..7)
+            .map(|_| backoff.next_delay().as_millis() as u64)
+            .collect();
+
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_000, 1_000, 1_000]);
+    }
+
+    #[rstest]
+    fn test_reset_after_success() {
+        let mut backoff = ExponentialBackoff::new(policy(None));
+        backoff.next_delay();
+        backoff.next_delay();
+        assert_eq!(backoff.current_delay(), Duration::from_millis(400));
+
+        backoff.reset();
+
+        assert_eq!(backoff.current_delay(), Duration::from_millis(100));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+    }
+
+    #[rstest]
+    fn test_delay_overflow_saturates_at_cap() {
+        let mut backoff = ExponentialBackoff::new(BackoffPolicy {
+            delay_initial: Duration::from_secs(1),
+            delay_max: Duration::MAX,
+            factor: 1e300,
+            jitter: Some(1.0),
+        });
+
+        backoff.next_delay();
+        backoff.next_delay();
+
+        assert_eq!(backoff.current_delay(), Duration::MAX);
+    }
+
+    #[rstest]
+    fn test_jitter_within_bounds() {
+        let mut backoff = ExponentialBackoff::new(policy(Some(0.5)));
+
+        for expected in [100, 200, 400, 800, 1_000] {
+            let delay = backoff.next_delay().as_millis() as u64;
+            assert!(
+                delay >= expected / 2 && delay <= expected * 3 / 2,
+                "Delay {delay}ms outside jitter bounds for {expected}ms"
+            );
+        }
+    }
+
+    fn breaker_policy() -> CircuitBreakerPolicy {
+        CircuitBreakerPolicy {
+            failure_threshold: 3,
+            window: Duration::from_secs(10),
+            cooldown: Duration::from_secs(5),
+        }
+    }
+
+    #[rstest]
+    fn test_circuit_breaker_opens_at_threshold() {
+        let mut breaker = CircuitBreaker::new(breaker_policy());
+        let now = Instant::now();
+
+        assert_eq!(breaker.record_failure(now), None);
+        assert_eq!(breaker.record_failure(now), None);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.record_failure(now), Some(Duration::from_secs(5)));
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[rstest]
+    fn test_circuit_breaker_ignores_failures_outside_window() {
+        let mut breaker = CircuitBreaker::new(breaker_policy());
+        let start = Instant::now();
+
+        assert_eq!(breaker.record_failure(start), None);
+        assert_eq!(breaker.record_failure(start), None);
+        let later = start + Duration::from_secs(11);
+        assert_eq!(breaker.record_failure(later), None);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[rstest]
+    fn test_circuit_breaker_success_resets_failures() {
+        let mut breaker = CircuitBreaker::new(breaker_policy());
+        let now = Instant::now();
+
+        breaker.record_failure(now);
+        breaker.record_failure(now);
+        breaker.record_success();
+
+        assert_eq!(breaker.record_failure(now), None);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[rstest]
+    fn test_circuit_breaker_half_open_trial() {
+        let mut breaker = CircuitBreaker::new(breaker_policy());
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure(now);
+        }
+
+        breaker.half_open();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        // A failed trial opens the breaker again straight away
+        assert_eq!(breaker.record_failure(now), Some(Duration::from_secs(5)));
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        breaker.half_open();
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[rstest]
+    fn test_factor_below_one_does_not_shrink() {
+        let mut backoff = ExponentialBackoff::new(BackoffPolicy {
+            factor: 0.5,
+            ..policy(None)
+        });
+
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,191 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Blocking socket client for use outside of an async runtime.
+
+use tokio::runtime::Runtime;
+
+use crate::socket::{SocketClient, SocketConfig, SocketError, SocketStats};
+
+/// The number of worker threads driving the client tasks of a [`BlockingSocketClient`].
+const WORKER_THREADS: usize = 2;
+
+/// Socket client exposing a blocking API over a [`SocketClient`].
+///
+/// The client owns a dedicated multi-thread runtime which drives the read, write,
+/// heartbeat and controller tasks in the background, so the handler keeps being
+/// called between blocking calls. Each blocking call waits on the runtime for the
+/// async equivalent to complete.
+///
+/// The client is `Send` and `Sync`, so blocking calls may be made from several threads.
+/// It must not be used, nor dropped, from within an async runtime since blocking there
+/// panics. Dropping the client shuts down its runtime and releases the worker threads.
+pub struct BlockingSocketClient {
+    client: SocketClient,
+    runtime: Runtime,
+}
+
+impl std::fmt::Debug for BlockingSocketClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(BlockingSocketClient))
+            .field("active", &self.is_active())
+            .finish_non_exhaustive()
+    }
+}
+
+impl BlockingSocketClient {
+    /// Connects a new [`BlockingSocketClient`] with the given `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be built, or under the same conditions
+    /// as [`SocketClient::connect`].
+    pub fn connect(config: SocketConfig) -> Result<Self, SocketError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(WORKER_THREADS)
+            .thread_name("socket-client")
+            .enable_all()
+            .build()?;
+        let client = runtime.block_on(SocketClient::connect(config, None, None, None))?;
+        Ok(Self { client, runtime })
+    }
+
+    /// Sends `data` as a frame, blocking until it is queued for writing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`SocketClient::send_bytes`].
+    pub fn send_bytes(&self, data: &[u8]) -> Result<(), SocketError> {
+        self.runtime.block_on(self.client.send_bytes(data))
+    }
+
+    /// Sends each of `frames` as a frame, contiguously on the wire.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`SocketClient::send_many`].
+    pub fn send_many(&self, frames: &[&[u8]]) -> Result<(), SocketError> {
+        self.runtime.block_on(self.client.send_many(frames))
+    }
+
+    /// Blocks until all frames sent before this call are written and flushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`SocketClient::flush`].
+    pub fn flush(&self) -> Result<(), SocketError> {
+        self.runtime.block_on(self.client.flush())
+    }
+
+    /// Closes the client, blocking until it is closed.
+    pub fn close(&self) {
+        self.runtime.block_on(self.client.close());
+    }
+
+    /// Returns `true` if the client is active.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.client.is_active()
+    }
+
+    /// Returns `true` if the client is reconnecting.
+    #[must_use]
+    pub fn is_reconnecting(&self) -> bool {
+        self.client.is_reconnecting()
+    }
+
+    /// Returns `true` if the client is disconnecting.
+    #[must_use]
+    pub fn is_disconnecting(&self) -> bool {
+        self.client.is_disconnecting()
+    }
+
+    /// Returns `true` if the client is closed.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.client.is_closed()
+    }
+
+    /// Returns a snapshot of the client statistics.
+    #[must_use]
+    pub fn stats(&self) -> SocketStats {
+        self.client.stats()
+    }
+
+    /// Returns the underlying [`SocketClient`].
+    #[must_use]
+    pub const fn client(&self) -> &SocketClient {
+        &self.client
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_blocking_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            while let Ok(n) = socket.read(&mut buf) {
+                if n == 0 || socket.write_all(&buf[..n]).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let config = SocketConfig {
+            url: format!("127.0.0.1:{port}"),
+            handler: Arc::new(move |data: &[u8]| {
+                received_clone.lock().unwrap().push(data.to_vec());
+            }),
+            max_reconnection_tries: Some(0),
+            ..SocketConfig::default()
+        };
+        let client = BlockingSocketClient::connect(config).unwrap();
+        assert!(client.is_active());
+
+        client.send_bytes(b"hello").unwrap();
+
+        let start = Instant::now();
+        while received.lock().unwrap().is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(2), "Timed out");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(*received.lock().unwrap(), vec![b"hello".to_vec()]);
+
+        client.close();
+        assert!(client.is_closed());
+        drop(client);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Socket client exchanging typed messages through a `tokio_util` codec.
+
+use std::sync::{Arc, Mutex};
+
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    framing::Framing,
+    socket::{MessageHandler, SocketClient, SocketConfig, SocketError},
+};
+
+/// Decodes the raw bytes of each connection into items passed to a typed handler.
+///
+/// The decoder and any partially received item are discarded whenever a new
+/// connection starts, so a reconnect always begins decoding from a clean state.
+struct CodecHandler<C, H> {
+    state: Mutex<DecoderState<C>>,
+    handler: H,
+}
+
+struct DecoderState<C> {
+    codec: C,
+    decoder: C,
+    buf: BytesMut,
+}
+
+impl<C: Clone> DecoderState<C> {
+    fn new(codec: C) -> Self {
+        Self {
+            decoder: codec.clone(),
+            codec,
+            buf: BytesMut::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.decoder = self.codec.clone();
+        self.buf.clear();
+    }
+}
+
+impl<C, H> MessageHandler for CodecHandler<C, H>
+where
+    C: Decoder + Clone + Send,
+    C::Error: std::fmt::Display,
+    H: Fn(C::Item) + Send + Sync,
+{
+    fn handle(&self, data: &[u8]) {
+        let mut state = self.state.lock().unwrap();
+        let DecoderState { decoder, buf, .. } = &mut *state;
+        buf.extend_from_slice(data);
+        loop {
+            match decoder.decode(buf) {
+                Ok(Some(item)) => (self.handler)(item),
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Discarding {} buffered bytes: {e}", buf.len());
+                    state.reset();
+                    break;
+                }
+            }
+        }
+    }
+
+    fn reset(&self) {
+        self.state.lock().unwrap().reset();
+    }
+}
+
+/// Socket client exchanging typed messages through a `tokio_util` codec.
+///
+/// The underlying [`SocketClient`] uses raw framing and its connection, heartbeat and
+/// reconnect machinery is unchanged, while received bytes are decoded and sent items are
+/// encoded with the codec. This gives access to codecs such as `LinesCodec` and
+/// `LengthDelimitedCodec`, as well as custom ones.
+pub struct CodecClient<C> {
+    client: SocketClient,
+    encoder: Mutex<C>,
+}
+
+impl<C> CodecClient<C>
+where
+    C: Decoder + Clone + Send + 'static,
+    C::Error: std::fmt::Display,
+{
+    /// Connects a client calling `handler` with each item decoded by `codec`.
+    ///
+    /// The config `framing`, `send_framing` and `handler` are replaced by the codec, and the
+    /// decoded items are handled by a single worker so the stream is decoded in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config is invalid or the connection fails.
+    pub async fn connect<H>(config: SocketConfig, codec: C, handler: H) -> Result<Self, SocketError>
+    where
+        H: Fn(C::Item) + Send + Sync + 'static,
+    {
+        let handler = CodecHandler {
+            state: Mutex::new(DecoderState::new(codec.clone())),
+            handler,
+        };
+        let config = SocketConfig {
+            framing: Framing::Raw,
+            handler_concurrency: 1,
+            send_framing: None,
+            handler: Arc::new(handler),
+            ..config
+        };
+        let client = SocketClient::connect(config, None, None, None).await?;
+
+        Ok(Self {
+            client,
+            encoder: Mutex::new(codec),
+        })
+    }
+
+    /// Encodes `item` with the codec and sends it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item cannot be encoded, or under the same conditions
+    /// as [`SocketClient::send_bytes`].
+    pub async fn send<I>(&self, item: I) -> Result<(), SocketError>
+    where
+        C: Encoder<I>,
+        <C as Encoder<I>>::Error: std::fmt::Display,
+    {
+        let mut buf = BytesMut::new();
+        self.encoder
+            .lock()
+            .unwrap()
+            .encode(item, &mut buf)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+        self.client.send_bytes(&buf).await
+    }
+
+    /// Returns the underlying [`SocketClient`].
+    #[must_use]
+    pub const fn client(&self) -> &SocketClient {
+        &self.client
+    }
+
+    /// Closes the underlying [`SocketClient`].
+    pub async fn close(&self) {
+        self.client.close().await;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task,
+        time::sleep,
+    };
+    use tokio_util::codec::LinesCodec;
+
+    use super::*;
+
+    type ReceivedLines = Arc<std::sync::Mutex<Vec<String>>>;
+
+    fn lines_handler() -> (CodecHandler<LinesCodec, impl Fn(String)>, ReceivedLines) {
+        let received = ReceivedLines::default();
+        let received_clone = received.clone();
+        let handler = CodecHandler {
+            state: Mutex::new(DecoderState::new(LinesCodec::new())),
+            handler: move |line: String| received_clone.lock().unwrap().push(line),
+        };
+        (handler, received)
+    }
+
+    #[rstest]
+    fn test_codec_handler_decodes_items_across_chunks() {
+        let (handler, received) = lines_handler();
+
+        handler.handle(b"first\nsec");
+        handler.handle(b"ond\nthi");
+        handler.handle(b"rd\n");
+
+        assert_eq!(*received.lock().unwrap(), vec!["first", "second", "third"]);
+    }
+
+    #[rstest]
+    fn test_codec_handler_reset_discards_partial_item() {
+        let (handler, received) = lines_handler();
+
+        handler.handle(b"partial");
+        handler.reset();
+        handler.handle(b"complete\n");
+
+        assert_eq!(*received.lock().unwrap(), vec!["complete"]);
+    }
+
+    #[rstest]
+    #[case::inline(None, 1)]
+    #[case::concurrent_config(Some(16), 4)]
+    #[tokio::test]
+    async fn test_lines_codec_round_trip(
+        #[case] handler_queue_capacity: Option<usize>,
+        #[case] handler_concurrency: usize,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            while let Ok(n) = socket.read(&mut buf).await {
+                if n == 0 || socket.write_all(&buf[..n]).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let received = ReceivedLines::default();
+        let received_clone = received.clone();
+        let config = SocketConfig {
+            url: format!("127.0.0.1:{port}"),
+            max_reconnection_tries: Some(0),
+            handler_queue_capacity,
+            handler_concurrency,
+            ..SocketConfig::default()
+        };
+        let client = CodecClient::connect(config, LinesCodec::new(), move |line: String| {
+            received_clone.lock().unwrap().push(line);
+        })
+        .await
+        .unwrap();
+
+        for line in ["hello", "world"] {
+            client.send(line).await.unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for lines");
+
+        assert_eq!(*received.lock().unwrap(), vec!["hello", "world"]);
+
+        client.close().await;
+        server_task.abort();
+    }
+}
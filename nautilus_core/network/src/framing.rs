@@ -0,0 +1,820 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Framing schemes for splitting a byte stream into messages.
+
+use bytes::BytesMut;
+use memchr::memmem;
+
+/// The spare capacity (bytes) reserved in the read buffer before each read.
+pub const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// The byte order of a length prefix header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// The scheme used to delimit messages on a byte stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Framing {
+    /// Each message is terminated by the given sequence of bytes.
+    Delimiter(Vec<u8>),
+    /// Each message is preceded by an unsigned length header.
+    LengthPrefixed {
+        /// The size of the header in bytes (1 to 8).
+        header_bytes: usize,
+        /// The byte order of the header.
+        endian: Endian,
+        /// If the encoded length includes the header itself.
+        includes_header: bool,
+    },
+    /// No framing, each chunk of bytes is delivered as it is read and sent as is.
+    Raw,
+}
+
+impl Framing {
+    /// Encodes `payload` as a single frame ready to be written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload length cannot be represented by the length header.
+    pub fn encode(&self, payload: &[u8]) -> Result<Vec<u8>, FrameError> {
+        match self {
+            Self::Delimiter(suffix) => {
+                let mut frame = Vec::with_capacity(payload.len() + suffix.len());
+                frame.extend_from_slice(payload);
+                frame.extend_from_slice(suffix);
+                Ok(frame)
+            }
+            Self::LengthPrefixed {
+                header_bytes,
+                endian,
+                includes_header,
+            } => {
+                let header_bytes = *header_bytes;
+                let overhead = if *includes_header { header_bytes } else { 0 };
+                let max = max_header_value(header_bytes).saturating_sub(overhead as u64);
+                if payload.len() as u64 > max {
+                    return Err(FrameError::TooLarge {
+                        size: payload.len(),
+                        max: usize::try_from(max).unwrap_or(usize::MAX),
+                    });
+                }
+
+                let length = (payload.len() + overhead) as u64;
+                let mut frame = Vec::with_capacity(header_bytes + payload.len());
+                match endian {
+                    Endian::Big => {
+                        frame.extend_from_slice(&length.to_be_bytes()[8 - header_bytes..])
+                    }
+                    Endian::Little => {
+                        frame.extend_from_slice(&length.to_le_bytes()[..header_bytes])
+                    }
+                }
+                frame.extend_from_slice(payload);
+                Ok(frame)
+            }
+            Self::Raw => Ok(payload.to_vec()),
+        }
+    }
+}
+
+/// Returns the largest value representable by a header of `header_bytes` bytes.
+const fn max_header_value(header_bytes: usize) -> u64 {
+    if header_bytes >= 8 {
+        u64::MAX
+    } else {
+        (1 << (8 * header_bytes)) - 1
+    }
+}
+
+/// An error encoding or decoding frames.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FrameError {
+    /// A frame, or the buffered bytes of an incomplete frame, exceeds the maximum size.
+    #[error("Frame of {size} bytes exceeds the maximum of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+    /// A length header is smaller than the header which contains it.
+    #[error("Invalid length {length} for {header_bytes} byte header which includes itself")]
+    InvalidLength { length: u64, header_bytes: usize },
+}
+
+/// Locates a delimiter suffix within a byte buffer.
+///
+/// Single-byte suffixes are located with `memchr`, multi-byte suffixes with a
+/// reusable `memmem::Finder`. A search cursor is maintained so bytes which have
+/// already been scanned are not rescanned after a partial read.
+#[derive(Debug, Clone)]
+pub struct SuffixFinder {
+    kind: FinderKind,
+    suffix: Vec<u8>,
+    cursor: usize,
+}
+
+#[derive(Debug, Clone)]
+enum FinderKind {
+    Byte(u8),
+    Bytes(Box<memmem::Finder<'static>>),
+}
+
+impl SuffixFinder {
+    /// Creates a new [`SuffixFinder`] instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `suffix` is empty.
+    #[must_use]
+    pub fn new(suffix: &[u8]) -> Self {
+        assert!(!suffix.is_empty(), "`suffix` must not be empty");
+        let kind = match suffix {
+            [byte] => FinderKind::Byte(*byte),
+            _ => FinderKind::Bytes(Box::new(memmem::Finder::new(suffix).into_owned())),
+        };
+        Self {
+            kind,
+            suffix: suffix.to_vec(),
+            cursor: 0,
+        }
+    }
+
+    /// Returns the suffix being located.
+    #[must_use]
+    pub fn suffix(&self) -> &[u8] {
+        &self.suffix
+    }
+
+    /// Returns the length of the suffix.
+    #[must_use]
+    pub fn suffix_len(&self) -> usize {
+        self.suffix.len()
+    }
+
+    /// Returns the index of the first suffix in `buf`, scanning from the cursor.
+    ///
+    /// When no suffix is found the cursor advances so that the next search only
+    /// rescans the bytes which could still form the start of a split suffix.
+    pub fn find(&mut self, buf: &[u8]) -> Option<usize> {
+        let start = self.cursor.min(buf.len());
+        let haystack = &buf[start..];
+        let found = match &self.kind {
+            FinderKind::Byte(byte) => memchr::memchr(*byte, haystack),
+            FinderKind::Bytes(finder) => finder.find(haystack),
+        };
+
+        match found {
+            Some(pos) => Some(start + pos),
+            None => {
+                self.cursor = buf.len().saturating_sub(self.suffix.len() - 1);
+                None
+            }
+        }
+    }
+
+    /// Notifies the finder that `n` bytes were consumed from the front of the buffer.
+    pub fn consume(&mut self, n: usize) {
+        self.cursor = self.cursor.saturating_sub(n);
+    }
+
+    /// Resets the search cursor to the start of the buffer.
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+    }
+}
+
+/// Accumulates received bytes and splits them into frames according to a [`Framing`].
+///
+/// Frames are split off the front of a [`BytesMut`] so consuming a frame is O(1).
+/// The retained bytes are only compacted when more capacity is reserved for the
+/// next read and the space held by previously consumed frames can be reclaimed.
+#[derive(Debug)]
+pub struct FrameDecoder {
+    buf: BytesMut,
+    kind: DecoderKind,
+    max_frame_size: Option<usize>,
+}
+
+#[derive(Debug)]
+enum DecoderKind {
+    Delimiter(SuffixFinder),
+    LengthPrefixed {
+        header_bytes: usize,
+        endian: Endian,
+        includes_header: bool,
+    },
+    Raw,
+}
+
+impl FrameDecoder {
+    /// Creates a new [`FrameDecoder`] instance.
+    ///
+    /// The read buffer is allocated by the first [`FrameDecoder::read_buf`] call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a delimiter is empty or a header size is outside 1 to 8 bytes.
+    #[must_use]
+    pub fn new(framing: &Framing) -> Self {
+        let kind = match framing {
+            Framing::Delimiter(suffix) => DecoderKind::Delimiter(SuffixFinder::new(suffix)),
+            Framing::LengthPrefixed {
+                header_bytes,
+                endian,
+                includes_header,
+            } => {
+                assert!(
+                    (1..=8).contains(header_bytes),
+                    "`header_bytes` must be between 1 and 8"
+                );
+                DecoderKind::LengthPrefixed {
+                    header_bytes: *header_bytes,
+                    endian: *endian,
+                    includes_header: *includes_header,
+                }
+            }
+            Framing::Raw => DecoderKind::Raw,
+        };
+        Self {
+            buf: BytesMut::new(),
+            kind,
+            max_frame_size: None,
+        }
+    }
+
+    /// Sets the maximum frame size (bytes, excluding any suffix or header).
+    ///
+    /// Raw framing has no frames, so the maximum does not apply to it.
+    #[must_use]
+    pub const fn with_max_frame_size(mut self, max_frame_size: Option<usize>) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Sets the read buffer, reusing its capacity.
+    ///
+    /// Any bytes the buffer holds are discarded.
+    #[must_use]
+    pub fn with_buffer(mut self, mut buf: BytesMut) -> Self {
+        buf.clear();
+        self.buf = buf;
+        self
+    }
+
+    /// Takes the read buffer emptied for reuse, discarding any bytes not yet returned as frames.
+    pub fn take_buffer(&mut self) -> BytesMut {
+        if let DecoderKind::Delimiter(finder) = &mut self.kind {
+            finder.reset();
+        }
+        let mut buf = std::mem::take(&mut self.buf);
+        buf.clear();
+        buf
+    }
+
+    /// Returns the read buffer with spare capacity reserved for the next read.
+    pub fn read_buf(&mut self) -> &mut BytesMut {
+        self.buf.reserve(READ_CHUNK_SIZE);
+        &mut self.buf
+    }
+
+    /// Appends `data` to the read buffer.
+    pub fn extend_from_slice(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Returns the number of buffered bytes not yet returned as frames.
+    #[must_use]
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Takes any buffered bytes not yet returned as frames, leaving the decoder empty.
+    pub fn take_remaining(&mut self) -> Option<BytesMut> {
+        if let DecoderKind::Delimiter(finder) = &mut self.kind {
+            finder.reset();
+        }
+        if self.buf.is_empty() {
+            return None;
+        }
+        Some(self.buf.split())
+    }
+
+    /// Returns the next complete frame with any suffix or header removed.
+    ///
+    /// With raw framing all buffered bytes are returned as a single frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a frame exceeds the maximum frame size, if the buffered
+    /// bytes of an incomplete frame can no longer fit within it, or if a length
+    /// header is invalid.
+    pub fn decode(&mut self) -> Result<Option<BytesMut>, FrameError> {
+        match &mut self.kind {
+            DecoderKind::Delimiter(finder) => {
+                Self::decode_delimited(&mut self.buf, finder, self.max_frame_size)
+            }
+            DecoderKind::LengthPrefixed {
+                header_bytes,
+                endian,
+                includes_header,
+            } => Self::decode_length_prefixed(
+                &mut self.buf,
+                *header_bytes,
+                *endian,
+                *includes_header,
+                self.max_frame_size,
+            ),
+            DecoderKind::Raw => Ok((!self.buf.is_empty()).then(|| self.buf.split())),
+        }
+    }
+
+    fn decode_delimited(
+        buf: &mut BytesMut,
+        finder: &mut SuffixFinder,
+        max_frame_size: Option<usize>,
+    ) -> Result<Option<BytesMut>, FrameError> {
+        let Some(i) = finder.find(buf) else {
+            return match max_frame_size {
+                // Bytes beyond the maximum are only allowed if they may be a partial suffix
+                Some(max) if buf.len() > max && !finder.suffix().starts_with(&buf[max..]) => {
+                    Err(FrameError::TooLarge {
+                        size: buf.len(),
+                        max,
+                    })
+                }
+                _ => Ok(None),
+            };
+        };
+
+        if let Some(max) = max_frame_size {
+            if i > max {
+                return Err(FrameError::TooLarge { size: i, max });
+            }
+        }
+
+        let consumed = i + finder.suffix_len();
+        let mut frame = buf.split_to(consumed);
+        frame.truncate(i);
+        finder.consume(consumed);
+        Ok(Some(frame))
+    }
+
+    fn decode_length_prefixed(
+        buf: &mut BytesMut,
+        header_bytes: usize,
+        endian: Endian,
+        includes_header: bool,
+        max_frame_size: Option<usize>,
+    ) -> Result<Option<BytesMut>, FrameError> {
+        if buf.len() < header_bytes {
+            return Ok(None);
+        }
+
+        let mut raw = [0u8; 8];
+        let length = match endian {
+            Endian::Big => {
+                raw[8 - header_bytes..].copy_from_slice(&buf[..header_bytes]);
+                u64::from_be_bytes(raw)
+            }
+            Endian::Little => {
+                raw[..header_bytes].copy_from_slice(&buf[..header_bytes]);
+                u64::from_le_bytes(raw)
+            }
+        };
+
+        let payload_len = if includes_header {
+            length
+                .checked_sub(header_bytes as u64)
+                .ok_or(FrameError::InvalidLength {
+                    length,
+                    header_bytes,
+                })?
+        } else {
+            length
+        };
+        let payload_len = usize::try_from(payload_len).unwrap_or(usize::MAX);
+
+        if let Some(max) = max_frame_size {
+            if payload_len > max {
+                return Err(FrameError::TooLarge {
+                    size: payload_len,
+                    max,
+                });
+            }
+        }
+
+        let frame_len = header_bytes.saturating_add(payload_len);
+        if buf.len() < frame_len {
+            return Ok(None);
+        }
+
+        let mut frame = buf.split_to(frame_len);
+        let _ = frame.split_to(header_bytes);
+        Ok(Some(frame))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rstest::rstest;
+
+    use super::*;
+
+    /// Feeds `chunks` through a finder as successive reads and returns the frames.
+    fn split_frames(suffix: &[u8], chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut finder = SuffixFinder::new(suffix);
+        let mut buf = Vec::new();
+        let mut frames = Vec::new();
+
+        for chunk in chunks {
+            buf.extend_from_slice(chunk);
+            while let Some(i) = finder.find(&buf) {
+                let consumed = i + finder.suffix_len();
+                let mut data: Vec<u8> = buf.drain(..consumed).collect();
+                data.truncate(i);
+                frames.push(data);
+                finder.consume(consumed);
+            }
+        }
+        frames
+    }
+
+    #[rstest]
+    #[case(b"\n".as_slice())]
+    #[case(b"\r\n".as_slice())]
+    #[case(b"<END>".as_slice())]
+    fn test_frames_in_single_read(#[case] suffix: &[u8]) {
+        let data = [b"a".as_slice(), suffix, b"bc", suffix, b"", suffix].concat();
+        let frames = split_frames(suffix, &[&data]);
+
+        assert_eq!(frames, vec![b"a".to_vec(), b"bc".to_vec(), b"".to_vec()]);
+    }
+
+    #[rstest]
+    fn test_suffix_split_across_reads() {
+        let frames = split_frames(b"\r\n", &[b"hello\r", b"\nworld", b"\r", b"\n"]);
+
+        assert_eq!(frames, vec![b"hello".to_vec(), b"world".to_vec()]);
+    }
+
+    #[rstest]
+    fn test_multi_byte_suffix_split_every_byte() {
+        let data = b"first<END>second<END>";
+        let chunks: Vec<&[u8]> = data.chunks(1).collect();
+        let frames = split_frames(b"<END>", &chunks);
+
+        assert_eq!(frames, vec![b"first".to_vec(), b"second".to_vec()]);
+    }
+
+    #[rstest]
+    fn test_cursor_skips_scanned_bytes() {
+        let mut finder = SuffixFinder::new(b"\r\n");
+        let mut buf = b"partial\r".to_vec();
+
+        assert_eq!(finder.find(&buf), None);
+        assert_eq!(finder.cursor, 7); // Only the trailing `\r` is rescanned
+
+        buf.extend_from_slice(b"\n");
+        assert_eq!(finder.find(&buf), Some(7));
+    }
+
+    #[rstest]
+    fn test_reset() {
+        let mut finder = SuffixFinder::new(b"\n");
+        assert_eq!(finder.find(b"abc"), None);
+
+        finder.reset();
+
+        assert_eq!(finder.find(b"a\n"), Some(1));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "`suffix` must not be empty")]
+    fn test_empty_suffix_panics() {
+        let _ = SuffixFinder::new(b"");
+    }
+
+    #[rstest]
+    fn test_decoder_frames_span_reads() {
+        let mut decoder = FrameDecoder::new(&Framing::Delimiter(b"\r\n".to_vec()));
+        decoder.extend_from_slice(b"one\r\ntw");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"one".as_slice())
+        );
+        assert_eq!(decoder.decode(), Ok(None));
+
+        decoder.extend_from_slice(b"o\r");
+        assert_eq!(decoder.decode(), Ok(None));
+        assert_eq!(decoder.buffered_len(), 4);
+
+        decoder.extend_from_slice(b"\nthree\r\n");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"two".as_slice())
+        );
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"three".as_slice())
+        );
+        assert_eq!(decoder.decode(), Ok(None));
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[rstest]
+    fn test_decoder_take_remaining() {
+        let mut decoder = FrameDecoder::new(&Framing::Delimiter(b"\r\n".to_vec()));
+        decoder.extend_from_slice(b"one\r\nlast");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"one".as_slice())
+        );
+        assert_eq!(decoder.decode(), Ok(None));
+
+        assert_eq!(
+            decoder.take_remaining().as_deref(),
+            Some(b"last".as_slice())
+        );
+        assert_eq!(decoder.take_remaining(), None);
+        assert_eq!(decoder.buffered_len(), 0);
+
+        decoder.extend_from_slice(b"two\r\n");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"two".as_slice())
+        );
+    }
+
+    #[rstest]
+    fn test_decoder_buffer_reused_without_data() {
+        let framing = Framing::Delimiter(b"\r\n".to_vec());
+        let mut decoder = FrameDecoder::new(&framing);
+        decoder.read_buf().extend_from_slice(b"one\r\npartial");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"one".as_slice())
+        );
+
+        let buf = decoder.take_buffer();
+        assert!(buf.is_empty());
+        assert_eq!(decoder.buffered_len(), 0);
+
+        let capacity = buf.capacity();
+        let mut decoder = FrameDecoder::new(&framing).with_buffer(buf);
+        let read_buf = decoder.read_buf();
+        assert!(read_buf.capacity() >= capacity);
+        read_buf.extend_from_slice(b"two\r\n");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"two".as_slice())
+        );
+        assert_eq!(decoder.decode(), Ok(None));
+    }
+
+    #[rstest]
+    #[case(b"\n".as_slice())]
+    #[case(b"\r\n".as_slice())]
+    fn test_decoder_stress_interleaved_reads(#[case] suffix: &[u8]) {
+        let mut rng = rand::thread_rng();
+        let expected: Vec<Vec<u8>> = (0..5_000)
+            .map(|i| format!("frame-{i}-{}", "x".repeat(i % 17)).into_bytes())
+            .collect();
+        let stream: Vec<u8> = expected
+            .iter()
+            .flat_map(|frame| [frame.as_slice(), suffix].concat())
+            .collect();
+
+        let mut decoder = FrameDecoder::new(&Framing::Delimiter(suffix.to_vec()));
+        let mut frames = Vec::new();
+        let mut offset = 0;
+
+        while offset < stream.len() {
+            let len = rng.gen_range(1..=64).min(stream.len() - offset);
+            decoder
+                .read_buf()
+                .extend_from_slice(&stream[offset..offset + len]);
+            offset += len;
+
+            // Extract only some of the available frames before the next read
+            for _ in 0..rng.gen_range(0..=3) {
+                match decoder.decode().unwrap() {
+                    Some(frame) => frames.push(frame.to_vec()),
+                    None => break,
+                }
+            }
+        }
+        while let Some(frame) = decoder.decode().unwrap() {
+            frames.push(frame.to_vec());
+        }
+
+        assert_eq!(frames, expected);
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[rstest]
+    fn test_decoder_max_frame_size_allows_frames_spanning_reads() {
+        let mut decoder =
+            FrameDecoder::new(&Framing::Delimiter(b"\r\n".to_vec())).with_max_frame_size(Some(8));
+        decoder.extend_from_slice(b"1234");
+        assert_eq!(decoder.decode(), Ok(None));
+
+        decoder.extend_from_slice(b"5678\r");
+        assert_eq!(decoder.decode(), Ok(None));
+        assert_eq!(decoder.buffered_len(), 9);
+
+        decoder.extend_from_slice(b"\n");
+        assert_eq!(
+            decoder.decode().unwrap().as_deref(),
+            Some(b"12345678".as_slice())
+        );
+    }
+
+    #[rstest]
+    fn test_decoder_max_frame_size_exceeded_without_suffix() {
+        let mut decoder =
+            FrameDecoder::new(&Framing::Delimiter(b"\n".to_vec())).with_max_frame_size(Some(8));
+        decoder.extend_from_slice(b"123456789");
+
+        assert_eq!(
+            decoder.decode(),
+            Err(FrameError::TooLarge { size: 9, max: 8 })
+        );
+    }
+
+    #[rstest]
+    fn test_decoder_max_frame_size_exceeded_by_one_byte_with_multi_byte_suffix() {
+        let mut decoder =
+            FrameDecoder::new(&Framing::Delimiter(b"\r\n".to_vec())).with_max_frame_size(Some(8));
+        decoder.extend_from_slice(b"123456789");
+
+        assert_eq!(
+            decoder.decode(),
+            Err(FrameError::TooLarge { size: 9, max: 8 })
+        );
+    }
+
+    #[rstest]
+    fn test_decoder_max_frame_size_exceeded_by_complete_frame() {
+        let mut decoder =
+            FrameDecoder::new(&Framing::Delimiter(b"\n".to_vec())).with_max_frame_size(Some(4));
+        decoder.extend_from_slice(b"ok\ntoo-long\n");
+
+        assert_eq!(decoder.decode().unwrap().as_deref(), Some(b"ok".as_slice()));
+        assert_eq!(
+            decoder.decode(),
+            Err(FrameError::TooLarge { size: 8, max: 4 })
+        );
+    }
+
+    #[rstest]
+    #[case(2, Endian::Big, false, &[0x00, 0x05])]
+    #[case(2, Endian::Little, false, &[0x05, 0x00])]
+    #[case(4, Endian::Big, false, &[0x00, 0x00, 0x00, 0x05])]
+    #[case(4, Endian::Little, false, &[0x05, 0x00, 0x00, 0x00])]
+    #[case(2, Endian::Big, true, &[0x00, 0x07])]
+    #[case(4, Endian::Little, true, &[0x09, 0x00, 0x00, 0x00])]
+    fn test_length_prefixed_encode(
+        #[case] header_bytes: usize,
+        #[case] endian: Endian,
+        #[case] includes_header: bool,
+        #[case] header: &[u8],
+    ) {
+        let framing = Framing::LengthPrefixed {
+            header_bytes,
+            endian,
+            includes_header,
+        };
+
+        let frame = framing.encode(b"hello").unwrap();
+
+        assert_eq!(frame, [header, b"hello".as_slice()].concat());
+    }
+
+    #[rstest]
+    #[case(2, Endian::Big, false)]
+    #[case(2, Endian::Little, false)]
+    #[case(4, Endian::Big, false)]
+    #[case(4, Endian::Little, true)]
+    fn test_length_prefixed_frames_split_across_reads(
+        #[case] header_bytes: usize,
+        #[case] endian: Endian,
+        #[case] includes_header: bool,
+    ) {
+        let framing = Framing::LengthPrefixed {
+            header_bytes,
+            endian,
+            includes_header,
+        };
+        // Payloads may contain any bytes, including what would otherwise be a delimiter
+        let payloads = [b"first\r\n".to_vec(), Vec::new(), vec![0xFF; 300]];
+        let stream: Vec<u8> = payloads
+            .iter()
+            .flat_map(|payload| framing.encode(payload).unwrap())
+            .collect();
+
+        let mut decoder = FrameDecoder::new(&framing);
+        let mut frames = Vec::new();
+        for chunk in stream.chunks(3) {
+            decoder.extend_from_slice(chunk);
+            while let Some(frame) = decoder.decode().unwrap() {
+                frames.push(frame.to_vec());
+            }
+        }
+
+        assert_eq!(frames, payloads);
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[rstest]
+    fn test_length_prefixed_max_frame_size() {
+        let framing = Framing::LengthPrefixed {
+            header_bytes: 2,
+            endian: Endian::Big,
+            includes_header: false,
+        };
+        let mut decoder = FrameDecoder::new(&framing).with_max_frame_size(Some(4));
+        decoder.extend_from_slice(&[0x00, 0x05]);
+
+        assert_eq!(
+            decoder.decode(),
+            Err(FrameError::TooLarge { size: 5, max: 4 })
+        );
+    }
+
+    #[rstest]
+    fn test_length_prefixed_invalid_length() {
+        let framing = Framing::LengthPrefixed {
+            header_bytes: 4,
+            endian: Endian::Big,
+            includes_header: true,
+        };
+        let mut decoder = FrameDecoder::new(&framing);
+        decoder.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]);
+
+        assert_eq!(
+            decoder.decode(),
+            Err(FrameError::InvalidLength {
+                length: 2,
+                header_bytes: 4
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_length_prefixed_encode_payload_too_large() {
+        let framing = Framing::LengthPrefixed {
+            header_bytes: 1,
+            endian: Endian::Big,
+            includes_header: true,
+        };
+
+        assert_eq!(
+            framing.encode(&[0; 255]),
+            Err(FrameError::TooLarge {
+                size: 255,
+                max: 254
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_delimiter_encode() {
+        let framing = Framing::Delimiter(b"\r\n".to_vec());
+
+        assert_eq!(framing.encode(b"abc").unwrap(), b"abc\r\n");
+    }
+
+    #[rstest]
+    fn test_raw_decoder_returns_chunks_verbatim() {
+        let mut decoder = FrameDecoder::new(&Framing::Raw).with_max_frame_size(Some(2));
+
+        decoder.extend_from_slice(b"abc\n");
+        assert_eq!(decoder.decode().unwrap().unwrap().as_ref(), b"abc\n");
+        assert_eq!(decoder.decode().unwrap(), None);
+
+        decoder.extend_from_slice(b"de");
+        assert_eq!(decoder.decode().unwrap().unwrap().as_ref(), b"de");
+        assert_eq!(decoder.take_remaining(), None);
+    }
+
+    #[rstest]
+    fn test_raw_encode_appends_nothing() {
+        assert_eq!(Framing::Raw.encode(b"abc").unwrap(), b"abc".to_vec());
+    }
+}
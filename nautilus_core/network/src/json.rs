@@ -0,0 +1,166 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Message handler which parses each received message as JSON.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::socket::MessageHandler;
+
+/// Callback invoked with a received message which is not valid JSON and the parse error.
+pub type JsonErrorCallback = Arc<dyn Fn(&[u8], &serde_json::Error) + Send + Sync>;
+
+/// Handler which parses each received message as JSON and passes the value on.
+///
+/// A message which is not valid JSON is skipped and counted, and passed to the
+/// optional error callback, the connection is kept open.
+pub struct JsonHandler<H> {
+    handler: H,
+    on_error: Option<JsonErrorCallback>,
+    parse_errors: AtomicU64,
+}
+
+impl<H> JsonHandler<H>
+where
+    H: Fn(serde_json::Value) + Send + Sync,
+{
+    /// Creates a new [`JsonHandler`] instance calling `handler` with each parsed value.
+    pub const fn new(handler: H) -> Self {
+        Self {
+            handler,
+            on_error: None,
+            parse_errors: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the callback invoked with each message which is not valid JSON.
+    #[must_use]
+    pub fn with_on_error(mut self, on_error: JsonErrorCallback) -> Self {
+        self.on_error = Some(on_error);
+        self
+    }
+
+    /// Returns the number of messages skipped because they were not valid JSON.
+    #[must_use]
+    pub fn parse_errors(&self) -> u64 {
+        self.parse_errors.load(Ordering::Relaxed)
+    }
+}
+
+impl<H> MessageHandler for JsonHandler<H>
+where
+    H: Fn(serde_json::Value) + Send + Sync,
+{
+    fn handle(&self, data: &[u8]) {
+        match serde_json::from_slice(data) {
+            Ok(value) => (self.handler)(value),
+            Err(e) => {
+                self.parse_errors.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!("Skipping message which is not valid JSON: {e}");
+                if let Some(ref on_error) = self.on_error {
+                    on_error(data, &e);
+                }
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rstest::rstest;
+    use serde_json::json;
+    use tokio::{io::AsyncWriteExt, net::TcpListener, task, time::sleep};
+
+    use super::*;
+    use crate::socket::{SocketClient, SocketConfig};
+
+    type ReceivedValues = Arc<std::sync::Mutex<Vec<serde_json::Value>>>;
+
+    fn recording_handler() -> (JsonHandler<impl Fn(serde_json::Value)>, ReceivedValues) {
+        let received = ReceivedValues::default();
+        let received_clone = received.clone();
+        let handler = JsonHandler::new(move |value| received_clone.lock().unwrap().push(value));
+        (handler, received)
+    }
+
+    #[rstest]
+    fn test_malformed_json_is_skipped_and_reported() {
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let errors_clone = errors.clone();
+        let (handler, received) = recording_handler();
+        let handler = handler.with_on_error(Arc::new(move |data, _| {
+            errors_clone.lock().unwrap().push(data.to_vec());
+        }));
+
+        handler.handle(br#"{"a":1"#);
+        handler.handle(b"[1,2]");
+
+        assert_eq!(*received.lock().unwrap(), vec![json!([1, 2])]);
+        assert_eq!(*errors.lock().unwrap(), vec![br#"{"a":1"#.to_vec()]);
+        assert_eq!(handler.parse_errors(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_frames_are_parsed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket
+                .write_all(b"{\"px\":1.5,\"side\":\"buy\"}\nnot json\n[1,null,true]\n")
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (handler, received) = recording_handler();
+        let handler = Arc::new(handler);
+        let config = SocketConfig {
+            url: format!("127.0.0.1:{port}"),
+            handler: handler.clone(),
+            max_reconnection_tries: Some(0),
+            ..SocketConfig::default()
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for values");
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![json!({"px": 1.5, "side": "buy"}), json!([1, null, true])]
+        );
+        assert_eq!(handler.parse_errors(), 1);
+        assert!(client.is_active());
+
+        client.close().await;
+        server_task.abort();
+    }
+}
@@ -35,7 +35,13 @@
 // these deprecations until fully migrated to `IntoPyObject`.
 #![allow(deprecated)]
 
+pub mod backoff;
+pub mod blocking;
+pub mod codec;
+pub mod framing;
 pub mod http;
+pub mod json;
+pub mod proxy;
 pub mod socket;
 pub mod websocket;
 
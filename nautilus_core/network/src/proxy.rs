@@ -0,0 +1,479 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2025 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Tunneling of outbound connections through SOCKS5 and HTTP CONNECT proxies.
+
+use std::{
+    io::{Error, ErrorKind},
+    net::IpAddr,
+};
+
+use base64::prelude::*;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_AUTH_NONE: u8 = 0x00;
+const SOCKS_AUTH_PASSWORD: u8 = 0x02;
+const SOCKS_AUTH_UNACCEPTABLE: u8 = 0xFF;
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+const SOCKS_ATYP_IPV4: u8 = 0x01;
+const SOCKS_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS_ATYP_IPV6: u8 = 0x04;
+
+/// The maximum size of an HTTP CONNECT response header.
+const MAX_HTTP_RESPONSE_BYTES: usize = 8 * 1024;
+
+/// The protocol spoken with the proxy server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub enum ProxyScheme {
+    /// A SOCKS5 proxy (RFC 1928), with optional username/password authentication (RFC 1929).
+    Socks5,
+    /// An HTTP proxy supporting the CONNECT method, with optional basic authentication.
+    Http,
+}
+
+/// Configuration of a proxy through which outbound connections are tunneled.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub struct ProxyConfig {
+    /// The protocol spoken with the proxy.
+    pub scheme: ProxyScheme,
+    /// The proxy host name or IP address.
+    pub host: String,
+    /// The proxy port.
+    pub port: u16,
+    /// The optional username for proxy authentication.
+    pub username: Option<String>,
+    /// The optional password for proxy authentication.
+    pub password: Option<String>,
+}
+
+impl std::fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(ProxyConfig))
+            .field("scheme", &self.scheme)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProxyConfig {
+    /// Returns the address of the proxy server to dial.
+    #[must_use]
+    pub fn address(&self) -> String {
+        format_authority(&self.host, self.port)
+    }
+
+    /// Establishes a tunnel to `target_host:target_port` over `stream`, which must be
+    /// connected to the proxy server.
+    ///
+    /// On success the stream carries the raw byte stream of the target connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy refuses the tunnel or authentication, or if the
+    /// proxy response is malformed.
+    pub async fn tunnel<S>(
+        &self,
+        stream: &mut S,
+        target_host: &str,
+        target_port: u16,
+    ) -> Result<(), Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        match self.scheme {
+            ProxyScheme::Socks5 => self.socks5_connect(stream, target_host, target_port).await,
+            ProxyScheme::Http => self.http_connect(stream, target_host, target_port).await,
+        }
+    }
+
+    async fn socks5_connect<S>(&self, stream: &mut S, host: &str, port: u16) -> Result<(), Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let method = if self.username.is_some() {
+            SOCKS_AUTH_PASSWORD
+        } else {
+            SOCKS_AUTH_NONE
+        };
+        stream.write_all(&[SOCKS_VERSION, 1, method]).await?;
+
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(proxy_error("Invalid SOCKS5 version in proxy reply"));
+        }
+        match reply[1] {
+            SOCKS_AUTH_NONE => {}
+            SOCKS_AUTH_PASSWORD if method == SOCKS_AUTH_PASSWORD => {
+                self.socks5_authenticate(stream).await?;
+            }
+            SOCKS_AUTH_UNACCEPTABLE => {
+                return Err(proxy_error(
+                    "SOCKS5 proxy rejected the authentication methods",
+                ))
+            }
+            other => {
+                return Err(proxy_error(&format!(
+                    "SOCKS5 proxy selected unsupported authentication method {other:#04x}"
+                )))
+            }
+        }
+
+        let mut request = vec![SOCKS_VERSION, SOCKS_CMD_CONNECT, 0x00];
+        match host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => {
+                request.push(SOCKS_ATYP_IPV4);
+                request.extend_from_slice(&ip.octets());
+            }
+            Ok(IpAddr::V6(ip)) => {
+                request.push(SOCKS_ATYP_IPV6);
+                request.extend_from_slice(&ip.octets());
+            }
+            Err(_) => {
+                let len = u8::try_from(host.len())
+                    .map_err(|_| proxy_error("Target host name too long for SOCKS5"))?;
+                request.push(SOCKS_ATYP_DOMAIN);
+                request.push(len);
+                request.extend_from_slice(host.as_bytes());
+            }
+        }
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(proxy_error("Invalid SOCKS5 version in proxy reply"));
+        }
+        if reply[1] != 0x00 {
+            return Err(proxy_error(&format!(
+                "SOCKS5 proxy failed to connect to {}: {}",
+                format_authority(host, port),
+                socks5_reply_reason(reply[1]),
+            )));
+        }
+
+        // Skip the bound address, which is of no use to the client
+        let addr_len = match reply[3] {
+            SOCKS_ATYP_IPV4 => 4,
+            SOCKS_ATYP_IPV6 => 16,
+            SOCKS_ATYP_DOMAIN => usize::from(stream.read_u8().await?),
+            other => {
+                return Err(proxy_error(&format!(
+                    "Invalid SOCKS5 address type {other:#04x} in proxy reply"
+                )))
+            }
+        };
+        let mut bound = vec![0u8; addr_len + 2];
+        stream.read_exact(&mut bound).await?;
+        Ok(())
+    }
+
+    async fn socks5_authenticate<S>(&self, stream: &mut S) -> Result<(), Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let username = self.username.as_deref().unwrap_or_default();
+        let password = self.password.as_deref().unwrap_or_default();
+        let username_len =
+            u8::try_from(username.len()).map_err(|_| proxy_error("SOCKS5 username too long"))?;
+        let password_len =
+            u8::try_from(password.len()).map_err(|_| proxy_error("SOCKS5 password too long"))?;
+
+        let mut request = vec![0x01, username_len];
+        request.extend_from_slice(username.as_bytes());
+        request.push(password_len);
+        request.extend_from_slice(password.as_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0x00 {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "SOCKS5 proxy authentication failed",
+            ));
+        }
+        Ok(())
+    }
+
+    async fn http_connect<S>(&self, stream: &mut S, host: &str, port: u16) -> Result<(), Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let authority = format_authority(host, port);
+        let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+        if let Some(username) = &self.username {
+            let password = self.password.as_deref().unwrap_or_default();
+            let credentials = BASE64_STANDARD.encode(format!("{username}:{password}"));
+            request.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Read byte by byte so that no data of the tunneled stream is consumed
+        let mut response = Vec::with_capacity(256);
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_HTTP_RESPONSE_BYTES {
+                return Err(proxy_error("HTTP proxy response header too large"));
+            }
+            response.push(stream.read_u8().await?);
+        }
+
+        let status_line = response
+            .split(|&b| b == b'\r')
+            .next()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        let mut parts = status_line.split_whitespace();
+        let status = match (parts.next(), parts.next()) {
+            (Some(version), Some(status)) if version.starts_with("HTTP/1.") => status,
+            _ => return Err(proxy_error("Malformed HTTP proxy response")),
+        };
+        match status {
+            "200" => Ok(()),
+            "407" => Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "HTTP proxy authentication required",
+            )),
+            _ => Err(proxy_error(&format!(
+                "HTTP proxy failed to connect to {authority}: {status_line}"
+            ))),
+        }
+    }
+}
+
+/// Splits a `host:port` address into its host and port, removing the brackets
+/// around IPv6 hosts.
+///
+/// # Errors
+///
+/// Returns an error if the address has no valid port.
+pub(crate) fn split_host_port(addr: &str) -> Result<(&str, u16), Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid address '{addr}', expected 'host:port'"),
+        )
+    };
+    let (host, port) = addr.rsplit_once(':').ok_or_else(invalid)?;
+    let port = port.parse().map_err(|_| invalid())?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    Ok((host, port))
+}
+
+fn format_authority(host: &str, port: u16) -> String {
+    if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+fn socks5_reply_reason(code: u8) -> &'static str {
+    match code {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn proxy_error(msg: &str) -> Error {
+    Error::new(ErrorKind::ConnectionRefused, msg)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use tokio::io::{duplex, DuplexStream};
+
+    use super::*;
+
+    fn proxy(scheme: ProxyScheme, credentials: Option<(&str, &str)>) -> ProxyConfig {
+        ProxyConfig {
+            scheme,
+            host: "proxy.test".to_string(),
+            port: 1080,
+            username: credentials.map(|(username, _)| username.to_string()),
+            password: credentials.map(|(_, password)| password.to_string()),
+        }
+    }
+
+    async fn read_bytes(stream: &mut DuplexStream, len: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; len];
+        stream.read_exact(&mut buf).await.unwrap();
+        buf
+    }
+
+    #[tokio::test]
+    async fn test_socks5_tunnel_without_auth() {
+        let (mut client, mut server) = duplex(1024);
+        let server_task = tokio::spawn(async move {
+            assert_eq!(read_bytes(&mut server, 3).await, [5, 1, SOCKS_AUTH_NONE]);
+            server.write_all(&[5, SOCKS_AUTH_NONE]).await.unwrap();
+            let request = read_bytes(&mut server, 10).await;
+            assert_eq!(request, [5, 1, 0, SOCKS_ATYP_IPV4, 10, 0, 0, 1, 0x1F, 0x90]);
+            server
+                .write_all(&[5, 0, 0, SOCKS_ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+            server.write_all(b"tunneled").await.unwrap();
+        });
+
+        proxy(ProxyScheme::Socks5, None)
+            .tunnel(&mut client, "10.0.0.1", 8080)
+            .await
+            .unwrap();
+        assert_eq!(read_bytes(&mut client, 8).await, b"tunneled");
+        server_task.await.unwrap();
+    }
+
+    #[rstest]
+    #[case(0x00, true)]
+    #[case(0x01, false)]
+    #[tokio::test]
+    async fn test_socks5_tunnel_with_auth(#[case] status: u8, #[case] expect_ok: bool) {
+        let (mut client, mut server) = duplex(1024);
+        let server_task = tokio::spawn(async move {
+            assert_eq!(
+                read_bytes(&mut server, 3).await,
+                [5, 1, SOCKS_AUTH_PASSWORD]
+            );
+            server.write_all(&[5, SOCKS_AUTH_PASSWORD]).await.unwrap();
+            assert_eq!(read_bytes(&mut server, 11).await, b"\x01\x04user\x04pass");
+            server.write_all(&[1, status]).await.unwrap();
+            if status != 0x00 {
+                return;
+            }
+            let mut request = read_bytes(&mut server, 5).await;
+            request.extend(read_bytes(&mut server, usize::from(request[4]) + 2).await);
+            assert_eq!(request, b"\x05\x01\x00\x03\x0bexample.com\x01\xbb");
+            server
+                .write_all(&[5, 0, 0, SOCKS_ATYP_DOMAIN, 1, b'x', 0, 0])
+                .await
+                .unwrap();
+        });
+
+        let result = proxy(ProxyScheme::Socks5, Some(("user", "pass")))
+            .tunnel(&mut client, "example.com", 443)
+            .await;
+        assert_eq!(result.is_ok(), expect_ok);
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_socks5_connect_failure() {
+        let (mut client, mut server) = duplex(1024);
+        let server_task = tokio::spawn(async move {
+            read_bytes(&mut server, 3).await;
+            server.write_all(&[5, SOCKS_AUTH_NONE]).await.unwrap();
+            read_bytes(&mut server, 10).await;
+            server
+                .write_all(&[5, 0x05, 0, SOCKS_ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+        });
+
+        let err = proxy(ProxyScheme::Socks5, None)
+            .tunnel(&mut client, "10.0.0.1", 8080)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("connection refused"));
+        server_task.await.unwrap();
+    }
+
+    #[rstest]
+    #[case(None, "HTTP/1.1 200 Connection established\r\n\r\n", true)]
+    #[case(Some(("user", "pass")), "HTTP/1.1 200 OK\r\nVia: proxy\r\n\r\n", true)]
+    #[case(None, "HTTP/1.1 407 Proxy Authentication Required\r\n\r\n", false)]
+    #[case(None, "garbage\r\n\r\n", false)]
+    #[tokio::test]
+    async fn test_http_connect_tunnel(
+        #[case] credentials: Option<(&'static str, &'static str)>,
+        #[case] response: &'static str,
+        #[case] expect_ok: bool,
+    ) {
+        let (mut client, mut server) = duplex(1024);
+        let server_task = tokio::spawn(async move {
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(server.read_u8().await.unwrap());
+            }
+            server.write_all(response.as_bytes()).await.unwrap();
+            server.write_all(b"tunneled").await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let result = proxy(ProxyScheme::Http, credentials)
+            .tunnel(&mut client, "example.com", 443)
+            .await;
+        let request = server_task.await.unwrap();
+
+        assert!(
+            request.starts_with("CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n")
+        );
+        assert_eq!(
+            request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"),
+            credentials.is_some()
+        );
+        assert_eq!(result.is_ok(), expect_ok);
+        if expect_ok {
+            assert_eq!(read_bytes(&mut client, 8).await, b"tunneled");
+        }
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:8080", Some(("127.0.0.1", 8080)))]
+    #[case("example.com:443", Some(("example.com", 443)))]
+    #[case("[::1]:9000", Some(("::1", 9000)))]
+    #[case("example.com", None)]
+    #[case(":443", None)]
+    #[case("example.com:http", None)]
+    fn test_split_host_port(#[case] addr: &str, #[case] expected: Option<(&str, u16)>) {
+        assert_eq!(split_host_port(addr).ok(), expected);
+    }
+
+    #[rstest]
+    fn test_debug_redacts_password() {
+        let config = proxy(ProxyScheme::Http, Some(("user", "secret")));
+        assert!(!format!("{config:?}").contains("secret"));
+    }
+}
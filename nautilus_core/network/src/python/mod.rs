@@ -22,6 +22,7 @@
 #![allow(unexpected_cfgs)]
 
 pub mod http;
+pub mod proxy;
 pub mod socket;
 pub mod websocket;
 
@@ -42,11 +43,17 @@ pub fn network(_: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<crate::http::HttpClient>()?;
     m.add_class::<crate::http::HttpMethod>()?;
     m.add_class::<crate::http::HttpResponse>()?;
+    m.add_class::<crate::proxy::ProxyConfig>()?;
+    m.add_class::<crate::proxy::ProxyScheme>()?;
     m.add_class::<crate::ratelimiter::quota::Quota>()?;
     m.add_class::<crate::websocket::WebSocketClient>()?;
     m.add_class::<crate::websocket::WebSocketConfig>()?;
     m.add_class::<crate::socket::SocketClient>()?;
     m.add_class::<crate::socket::SocketConfig>()?;
+    m.add_class::<crate::socket::SocketStats>()?;
+    m.add_class::<crate::socket::QueueOverflowPolicy>()?;
+    m.add_class::<crate::socket::HandlerQueuePolicy>()?;
+    m.add_class::<crate::backoff::CircuitState>()?;
 
     // Add error classes
     m.add(
@@ -13,37 +13,507 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
 
-use nautilus_core::python::to_pyruntime_err;
-use pyo3::prelude::*;
-use tokio::io::AsyncWriteExt;
+use bytes::Bytes;
+use nautilus_core::{
+    python::{to_pyruntime_err, to_pyvalue_err},
+    UnixNanos,
+};
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyList},
+};
 use tokio_tungstenite::tungstenite::stream::Mode;
 
-use crate::socket::{SocketClient, SocketConfig};
+use crate::{
+    backoff::{BackoffPolicy, CircuitBreakerPolicy, CircuitState},
+    framing::Framing,
+    json::JsonHandler,
+    proxy::ProxyConfig,
+    ratelimiter::quota::Quota,
+    socket::{
+        report_handler_error, BackpressureCallback, ConnectionCallback, ConnectionInfo, Endpoint,
+        EstablishedCallback, FatalCallback, FatalErrorClassifier, FramePosition, GiveUpCallback,
+        HandlerErrorCallback, HandlerQueuePolicy, HeartbeatPayload, HeartbeatResponse,
+        MessageHandler, OverflowCallback, QueueOverflowPolicy, ReconnectAttemptCallback,
+        RecvErrorCallback, RecvTransform, SendTransform, SocketClient, SocketConfig, SocketError,
+        SocketStats, TcpOptions, UrlProvider,
+    },
+};
+
+impl MessageHandler for PyObject {
+    fn handle(&self, data: &[u8]) {
+        if let Err(e) = Python::with_gil(|py| self.call1(py, (data,))) {
+            tracing::error!("Call to handler failed: {e}");
+            report_handler_error(e);
+        }
+    }
+
+    fn handle_batch(&self, frames: &[Bytes]) {
+        let result = Python::with_gil(|py| {
+            let batch = PyList::new(py, frames.iter().map(|data| PyBytes::new(py, data)))?;
+            self.call1(py, (batch,))
+        });
+        if let Err(e) = result {
+            tracing::error!("Call to handler failed: {e}");
+            report_handler_error(e);
+        }
+    }
+
+    fn handle_with_ts(&self, data: &[u8], ts_recv: UnixNanos) {
+        if let Err(e) = Python::with_gil(|py| self.call1(py, (data, ts_recv.as_u64()))) {
+            tracing::error!("Call to handler failed: {e}");
+            report_handler_error(e);
+        }
+    }
+
+    fn handle_with_position(&self, data: &[u8], position: FramePosition) {
+        if let Err(e) = Python::with_gil(|py| self.call1(py, (data, position.seq, position.offset)))
+        {
+            tracing::error!("Call to handler failed: {e}");
+            report_handler_error(e);
+        }
+    }
+
+    fn handle_batch_with_ts(&self, frames: &[Bytes], ts_recv: UnixNanos) {
+        let result = Python::with_gil(|py| {
+            let batch = PyList::new(py, frames.iter().map(|data| PyBytes::new(py, data)))?;
+            self.call1(py, (batch, ts_recv.as_u64()))
+        });
+        if let Err(e) = result {
+            tracing::error!("Call to handler failed: {e}");
+            report_handler_error(e);
+        }
+    }
+}
+
+/// Wraps a Python callable returning either a URL or a `(url, handshake)` tuple.
+fn py_url_provider(callback: PyObject) -> UrlProvider {
+    Arc::new(move || {
+        Python::with_gil(|py| {
+            let result = callback.call0(py)?;
+            match result.extract::<String>(py) {
+                Ok(url) => Ok(Endpoint {
+                    url,
+                    handshake: None,
+                }),
+                Err(_) => {
+                    let (url, handshake) = result.extract::<(String, Option<Vec<u8>>)>(py)?;
+                    Ok(Endpoint { url, handshake })
+                }
+            }
+        })
+        .map_err(|e: PyErr| {
+            std::io::Error::other(format!("Error calling `url_provider` handler: {e}"))
+        })
+    })
+}
+
+/// Wraps a Python callable returning the transformed bytes of a sent message.
+fn py_send_transform(callback: PyObject) -> SendTransform {
+    Arc::new(move |data: &[u8]| {
+        Python::with_gil(|py| callback.call1(py, (data,))?.extract::<Vec<u8>>(py))
+            .map_err(|e| std::io::Error::other(format!("Error calling `on_send` handler: {e}")))
+    })
+}
+
+/// Wraps a Python callable returning the payload of the next heartbeat.
+fn py_heartbeat_payload(callback: PyObject) -> HeartbeatPayload {
+    Arc::new(move || {
+        Python::with_gil(|py| callback.call0(py)?.extract::<Vec<u8>>(py)).map_err(|e| {
+            std::io::Error::other(format!("Error calling `heartbeat_payload` handler: {e}"))
+        })
+    })
+}
+
+/// Wraps a Python callable returning the transformed bytes of a received message.
+fn py_recv_transform(callback: PyObject) -> RecvTransform {
+    Arc::new(move |data: &[u8]| {
+        Python::with_gil(|py| callback.call1(py, (data,))?.extract::<Vec<u8>>(py))
+            .map_err(|e| std::io::Error::other(format!("Error calling `on_recv` handler: {e}")))
+    })
+}
+
+/// Wraps a Python callable invoked with a skipped received message and the error message.
+fn py_recv_error_callback(callback: PyObject) -> RecvErrorCallback {
+    Arc::new(move |data: &[u8], error: &std::io::Error| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (data, error.to_string()))) {
+            tracing::error!("Error calling `on_recv_error` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python callable returning whether the reconnect error message is fatal.
+fn py_fatal_error_classifier(callback: PyObject) -> FatalErrorClassifier {
+    Arc::new(move |error: &SocketError| {
+        Python::with_gil(|py| {
+            callback
+                .call1(py, (error.to_string(),))
+                .and_then(|result| result.is_truthy(py))
+                .unwrap_or_else(|e| {
+                    tracing::error!("Error calling `is_fatal_error` handler: {e}");
+                    error.is_fatal()
+                })
+        })
+    })
+}
+
+/// Wraps a Python callable invoked with the fatal reconnect error message.
+fn py_fatal_callback(callback: PyObject) -> FatalCallback {
+    Arc::new(move |error: &SocketError| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (error.to_string(),))) {
+            tracing::error!("Error calling `on_fatal` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python callable invoked with the last reconnect error message and attempt count.
+fn py_give_up_callback(callback: PyObject) -> GiveUpCallback {
+    Arc::new(move |error: &SocketError, attempts: u64| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (error.to_string(), attempts))) {
+            tracing::error!("Error calling `on_give_up` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python callable receiving if the connection is the initial one.
+fn py_established_callback(callback: PyObject) -> EstablishedCallback {
+    Arc::new(move |initial: bool| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (initial,))) {
+            tracing::error!("Error calling `on_established` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python callable invoked with the message of each handler error.
+fn py_handler_error_callback(callback: PyObject) -> HandlerErrorCallback {
+    Arc::new(move |error: &str| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (error,))) {
+            tracing::error!("Error calling `on_handler_error` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python connection callback, called with the connection info as a dict or
+/// without arguments if the callback does not accept any.
+pub(crate) fn py_connection_callback(callback: PyObject, name: &'static str) -> ConnectionCallback {
+    Arc::new(move |info: ConnectionInfo| {
+        Python::with_gil(|py| {
+            let result = if takes_arguments(py, &callback) {
+                connection_info_to_pydict(py, info).and_then(|info| callback.call1(py, (info,)))
+            } else {
+                callback.call0(py)
+            };
+            match result {
+                Ok(_) => tracing::debug!("Called `{name}` handler"),
+                Err(e) => tracing::error!("Error calling `{name}` handler: {e}"),
+            }
+        });
+    })
+}
+
+fn connection_info_to_pydict(py: Python<'_>, info: ConnectionInfo) -> PyResult<Bound<'_, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("attempt", info.attempt)?;
+    dict.set_item("peer_addr", info.peer_addr.map(|addr| addr.to_string()))?;
+    dict.set_item("elapsed_secs", info.elapsed.as_secs_f64())?;
+    Ok(dict)
+}
+
+fn takes_arguments(py: Python<'_>, callback: &PyObject) -> bool {
+    py.import("inspect")
+        .and_then(|inspect| inspect.call_method1("signature", (callback,)))
+        .and_then(|signature| signature.getattr("parameters"))
+        .and_then(|parameters| parameters.len())
+        .is_ok_and(|len| len > 0)
+}
+
+/// Wraps a Python callable receiving the number of each reconnect attempt.
+fn py_reconnect_attempt_callback(callback: PyObject) -> ReconnectAttemptCallback {
+    Arc::new(move |attempt: u64| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (attempt,))) {
+            tracing::error!("Error calling `on_reconnect_attempt` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python callable receiving the handler queue depth and if backpressure is active.
+fn py_backpressure_callback(callback: PyObject) -> BackpressureCallback {
+    Arc::new(move |depth: usize, active: bool| {
+        if let Err(e) = Python::with_gil(|py| callback.call1(py, (depth, active))) {
+            tracing::error!("Error calling `on_backpressure` handler: {e}");
+        }
+    })
+}
+
+/// Wraps a Python handler to be called with each message parsed as JSON.
+fn py_json_handler(handler: PyObject, on_error: Option<PyObject>) -> Arc<dyn MessageHandler> {
+    let json_handler = JsonHandler::new(move |value: serde_json::Value| {
+        let result = Python::with_gil(|py| {
+            let value = json_to_py(py, &value)?;
+            handler.call1(py, (value,))
+        });
+        if let Err(e) = result {
+            tracing::error!("Call to handler failed: {e}");
+            report_handler_error(e);
+        }
+    });
+    match on_error {
+        Some(callback) => Arc::new(json_handler.with_on_error(Arc::new(
+            move |data: &[u8], error: &serde_json::Error| {
+                if let Err(e) = Python::with_gil(|py| callback.call1(py, (data, error.to_string())))
+                {
+                    tracing::error!("Error calling `on_json_error` handler: {e}");
+                }
+            },
+        ))),
+        None => Arc::new(json_handler),
+    }
+}
+
+/// Converts a JSON value into the equivalent Python object.
+fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+    Ok(match value {
+        serde_json::Value::Null => py.None(),
+        serde_json::Value::Bool(b) => b.into_py(py),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_py(py),
+            (None, Some(u)) => u.into_py(py),
+            _ => n.as_f64().unwrap_or(f64::NAN).into_py(py),
+        },
+        serde_json::Value::String(s) => s.into_py(py),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
 
 #[pymethods]
 impl SocketConfig {
     #[new]
-    #[pyo3(signature = (url, ssl, suffix, handler, heartbeat=None, reconnect_timeout_secs=30, max_reconnection_tries=3))]
+    #[pyo3(signature = (url, ssl, suffix, handler, heartbeat=None, reconnect_timeout_secs=30, max_reconnection_tries=3, reconnect_delay_initial_ms=None, reconnect_delay_max_ms=None, reconnect_backoff_factor=None, reconnect_jitter=None, controller_check_interval_ms=None, handler_batch_size=None, max_message_size=None, on_overflow=None, handler_queue_capacity=None, handler_queue_overflow=None, reconnect_queue_capacity=None, reconnect_queue_overflow=None, max_coalesce_bytes=None, max_coalesce_frames=None, deliver_unterminated_on_eof=false, read_idle_timeout_secs=None, heartbeat_timeout_secs=None, heartbeat_response=None, heartbeat_always_send=false, tcp_nodelay=true, tcp_keepalive_secs=None, tcp_recv_buffer_size=None, tcp_send_buffer_size=None, connect_timeout_secs=None, client_cert_pem=None, client_key_pem=None, root_ca_pem=None, danger_accept_invalid_certs=false, tls_sni=None, proxy=None, fallback_urls=None, url_provider=None, on_connect_send=None, send_wait_active_timeout_ms=None, shutdown_linger_ms=None, reconnect_on_eof=true, is_fatal_error=None, on_fatal=None, on_give_up=None, max_reconnect_duration_secs=None, send_suffix=None, handler_receive_ts=false, handler_queue_high_water=None, handler_queue_low_water=None, on_backpressure=None, drain_on_close=true, parse_json=false, on_json_error=None, auto_flush=true, client_id=None, shutdown_timeout_secs=None, send_rate_limit=None, on_send=None, on_recv=None, on_recv_error=None, heartbeat_payload=None, heartbeat_append_suffix=true, on_established=None, bind_address=None, tls_handshake_timeout_secs=None, alpn_protocols=None, pinned_cert_sha256=None, tcp_dscp=None, tcp_linger_secs=None, read_buffer_capacity=None, handler_yield_every=None, circuit_breaker=None, on_reconnect_attempt=None, handler_receive_position=false, handler_concurrency=1, on_reconnect_send=None, max_handler_errors=None, on_handler_error=None))]
+    #[allow(clippy::too_many_arguments)]
     fn py_new(
         url: String,
         ssl: bool,
         suffix: Vec<u8>,
         handler: PyObject,
-        heartbeat: Option<(u64, Vec<u8>)>,
+        heartbeat: Option<(f64, Vec<u8>)>,
         reconnect_timeout_secs: Option<u64>,
         max_reconnection_tries: Option<u64>,
+        reconnect_delay_initial_ms: Option<u64>,
+        reconnect_delay_max_ms: Option<u64>,
+        reconnect_backoff_factor: Option<f64>,
+        reconnect_jitter: Option<f64>,
+        controller_check_interval_ms: Option<u64>,
+        handler_batch_size: Option<usize>,
+        max_message_size: Option<usize>,
+        on_overflow: Option<PyObject>,
+        handler_queue_capacity: Option<usize>,
+        handler_queue_overflow: Option<HandlerQueuePolicy>,
+        reconnect_queue_capacity: Option<usize>,
+        reconnect_queue_overflow: Option<QueueOverflowPolicy>,
+        max_coalesce_bytes: Option<usize>,
+        max_coalesce_frames: Option<usize>,
+        deliver_unterminated_on_eof: bool,
+        read_idle_timeout_secs: Option<u64>,
+        heartbeat_timeout_secs: Option<u64>,
+        heartbeat_response: Option<PyObject>,
+        heartbeat_always_send: bool,
+        tcp_nodelay: bool,
+        tcp_keepalive_secs: Option<u64>,
+        tcp_recv_buffer_size: Option<usize>,
+        tcp_send_buffer_size: Option<usize>,
+        connect_timeout_secs: Option<u64>,
+        client_cert_pem: Option<Vec<u8>>,
+        client_key_pem: Option<Vec<u8>>,
+        root_ca_pem: Option<Vec<u8>>,
+        danger_accept_invalid_certs: bool,
+        tls_sni: Option<String>,
+        proxy: Option<ProxyConfig>,
+        fallback_urls: Option<Vec<String>>,
+        url_provider: Option<PyObject>,
+        on_connect_send: Option<Vec<Vec<u8>>>,
+        send_wait_active_timeout_ms: Option<u64>,
+        shutdown_linger_ms: Option<u64>,
+        reconnect_on_eof: bool,
+        is_fatal_error: Option<PyObject>,
+        on_fatal: Option<PyObject>,
+        on_give_up: Option<PyObject>,
+        max_reconnect_duration_secs: Option<u64>,
+        send_suffix: Option<Vec<u8>>,
+        handler_receive_ts: bool,
+        handler_queue_high_water: Option<usize>,
+        handler_queue_low_water: Option<usize>,
+        on_backpressure: Option<PyObject>,
+        drain_on_close: bool,
+        parse_json: bool,
+        on_json_error: Option<PyObject>,
+        auto_flush: bool,
+        client_id: Option<String>,
+        shutdown_timeout_secs: Option<u64>,
+        send_rate_limit: Option<Quota>,
+        on_send: Option<PyObject>,
+        on_recv: Option<PyObject>,
+        on_recv_error: Option<PyObject>,
+        heartbeat_payload: Option<PyObject>,
+        heartbeat_append_suffix: bool,
+        on_established: Option<PyObject>,
+        bind_address: Option<(IpAddr, u16)>,
+        tls_handshake_timeout_secs: Option<u64>,
+        alpn_protocols: Option<Vec<Vec<u8>>>,
+        pinned_cert_sha256: Option<Vec<[u8; 32]>>,
+        tcp_dscp: Option<u8>,
+        tcp_linger_secs: Option<u64>,
+        read_buffer_capacity: Option<usize>,
+        handler_yield_every: Option<usize>,
+        circuit_breaker: Option<(u32, f64, f64)>,
+        on_reconnect_attempt: Option<PyObject>,
+        handler_receive_position: bool,
+        handler_concurrency: usize,
+        on_reconnect_send: Option<Vec<Vec<u8>>>,
+        max_handler_errors: Option<u32>,
+        on_handler_error: Option<PyObject>,
     ) -> Self {
         let mode = if ssl { Mode::Tls } else { Mode::Plain };
+        let default_backoff = BackoffPolicy::default();
+        let backoff = BackoffPolicy {
+            delay_initial: reconnect_delay_initial_ms
+                .map_or(default_backoff.delay_initial, Duration::from_millis),
+            delay_max: reconnect_delay_max_ms
+                .map_or(default_backoff.delay_max, Duration::from_millis),
+            factor: reconnect_backoff_factor.unwrap_or(default_backoff.factor),
+            jitter: reconnect_jitter.or(default_backoff.jitter),
+        };
         Self {
             url,
             mode,
-            suffix,
-            handler: Arc::new(handler),
-            heartbeat,
+            framing: Framing::Delimiter(suffix),
+            handler: if parse_json {
+                py_json_handler(handler, on_json_error)
+            } else {
+                Arc::new(handler)
+            },
+            // Invalid intervals map to zero which fails validation on connect
+            heartbeat: heartbeat.map(|(interval_secs, message)| {
+                let interval = Duration::try_from_secs_f64(interval_secs).unwrap_or_default();
+                (interval, message)
+            }),
             reconnect_timeout_secs,
             max_reconnection_tries,
+            backoff,
+            controller_check_interval_ms,
+            handler_batch_size,
+            max_message_size,
+            on_overflow: on_overflow.map(|callback| {
+                Arc::new(move |size: usize| {
+                    if let Err(e) = Python::with_gil(|py| callback.call1(py, (size,))) {
+                        tracing::error!("Error calling `on_overflow` handler: {e}");
+                    }
+                }) as OverflowCallback
+            }),
+            handler_queue_capacity,
+            handler_queue_overflow: handler_queue_overflow.unwrap_or_default(),
+            reconnect_queue_capacity,
+            reconnect_queue_overflow: reconnect_queue_overflow.unwrap_or_default(),
+            max_coalesce_bytes,
+            max_coalesce_frames,
+            connector: None,
+            deliver_unterminated_on_eof,
+            read_idle_timeout_secs,
+            heartbeat_timeout_secs,
+            heartbeat_response: heartbeat_response.map(|callback| {
+                Arc::new(move |data: &[u8]| {
+                    Python::with_gil(|py| {
+                        callback
+                            .call1(py, (PyBytes::new(py, data),))
+                            .and_then(|result| result.is_truthy(py))
+                            .unwrap_or_else(|e| {
+                                tracing::error!("Error calling `heartbeat_response` handler: {e}");
+                                false
+                            })
+                    })
+                }) as HeartbeatResponse
+            }),
+            heartbeat_always_send,
+            tcp_options: TcpOptions {
+                nodelay: tcp_nodelay,
+                keepalive: tcp_keepalive_secs.map(Duration::from_secs),
+                recv_buffer_size: tcp_recv_buffer_size,
+                send_buffer_size: tcp_send_buffer_size,
+                dscp: tcp_dscp,
+                linger: tcp_linger_secs.map(Duration::from_secs),
+            },
+            connect_timeout_secs,
+            client_cert_pem,
+            client_key_pem,
+            root_ca_pem,
+            danger_accept_invalid_certs,
+            tls_sni,
+            proxy,
+            fallback_urls: fallback_urls.unwrap_or_default(),
+            url_provider: url_provider.map(py_url_provider),
+            on_connect_send,
+            send_wait_active_timeout_ms,
+            shutdown_linger_ms,
+            reconnect_on_eof,
+            is_fatal_error: is_fatal_error.map(py_fatal_error_classifier),
+            on_fatal: on_fatal.map(py_fatal_callback),
+            on_give_up: on_give_up.map(py_give_up_callback),
+            max_reconnect_duration_secs,
+            send_framing: send_suffix.map(Framing::Delimiter),
+            handler_receive_ts,
+            handler_queue_high_water,
+            handler_queue_low_water,
+            on_backpressure: on_backpressure.map(py_backpressure_callback),
+            drain_on_close,
+            cancellation_token: None,
+            auto_flush,
+            client_id,
+            shutdown_timeout_secs,
+            send_rate_limit,
+            on_send: on_send.map(py_send_transform),
+            on_recv: on_recv.map(py_recv_transform),
+            on_recv_error: on_recv_error.map(py_recv_error_callback),
+            heartbeat_payload: heartbeat_payload.map(py_heartbeat_payload),
+            heartbeat_append_suffix,
+            on_established: on_established.map(py_established_callback),
+            resolver: None,
+            bind_address: bind_address.map(SocketAddr::from),
+            tls_handshake_timeout_secs,
+            alpn_protocols: alpn_protocols.unwrap_or_default(),
+            pinned_cert_sha256,
+            read_buffer_capacity,
+            handler_yield_every,
+            circuit_breaker: circuit_breaker.map(
+                |(failure_threshold, window_secs, cooldown_secs)| CircuitBreakerPolicy {
+                    failure_threshold,
+                    window: Duration::try_from_secs_f64(window_secs).unwrap_or_default(),
+                    cooldown: Duration::try_from_secs_f64(cooldown_secs).unwrap_or_default(),
+                },
+            ),
+            on_reconnect_attempt: on_reconnect_attempt.map(py_reconnect_attempt_callback),
+            handler_receive_position,
+            handler_concurrency,
+            on_reconnect_send,
+            max_handler_errors,
+            on_handler_error: on_handler_error.map(py_handler_error_callback),
         }
     }
 }
@@ -68,9 +538,11 @@ impl SocketClient {
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             Self::connect(
                 config,
-                post_connection,
-                post_reconnection,
-                post_disconnection,
+                post_connection.map(|callback| py_connection_callback(callback, "post_connection")),
+                post_reconnection
+                    .map(|callback| py_connection_callback(callback, "post_reconnection")),
+                post_disconnection
+                    .map(|callback| py_connection_callback(callback, "post_disconnection")),
             )
             .await
             .map_err(to_pyruntime_err)
@@ -107,6 +579,67 @@ impl SocketClient {
         slf.is_closed()
     }
 
+    #[pyo3(name = "peer_addr")]
+    fn py_peer_addr(slf: PyRef<'_, Self>) -> Option<String> {
+        slf.peer_addr().map(|addr| addr.to_string())
+    }
+
+    #[pyo3(name = "alpn_protocol")]
+    fn py_alpn_protocol(slf: PyRef<'_, Self>) -> Option<Vec<u8>> {
+        slf.alpn_protocol()
+    }
+
+    #[cfg(unix)]
+    #[pyo3(name = "raw_fd")]
+    fn py_raw_fd(slf: PyRef<'_, Self>) -> Option<i32> {
+        slf.raw_fd()
+    }
+
+    #[pyo3(name = "last_exit_reason")]
+    fn py_last_exit_reason(slf: PyRef<'_, Self>) -> Option<String> {
+        slf.last_exit_reason().map(|reason| reason.to_string())
+    }
+
+    #[pyo3(name = "active_url")]
+    fn py_active_url(slf: PyRef<'_, Self>) -> Option<String> {
+        slf.active_url()
+    }
+
+    #[pyo3(name = "seconds_since_last_recv")]
+    fn py_seconds_since_last_recv(slf: PyRef<'_, Self>) -> Option<f64> {
+        slf.seconds_since_last_recv()
+    }
+
+    #[pyo3(name = "seconds_since_last_send")]
+    fn py_seconds_since_last_send(slf: PyRef<'_, Self>) -> Option<f64> {
+        slf.seconds_since_last_send()
+    }
+
+    #[pyo3(name = "circuit_state")]
+    fn py_circuit_state(slf: PyRef<'_, Self>) -> CircuitState {
+        slf.circuit_state()
+    }
+
+    #[pyo3(name = "reconnect_count")]
+    fn py_reconnect_count(slf: PyRef<'_, Self>) -> u64 {
+        slf.reconnect_count()
+    }
+
+    #[pyo3(name = "uptime_secs")]
+    fn py_uptime_secs(slf: PyRef<'_, Self>) -> Option<f64> {
+        slf.uptime().map(|uptime| uptime.as_secs_f64())
+    }
+
+    #[pyo3(name = "stats")]
+    fn py_stats(slf: PyRef<'_, Self>) -> SocketStats {
+        slf.stats()
+    }
+
+    #[pyo3(name = "local_addr")]
+    fn py_local_addr(slf: PyRef<'_, Self>) -> Option<String> {
+        slf.local_addr().map(|addr| addr.to_string())
+    }
+
     /// Close the client.
     ///
     /// The connection is not completely closed until all references
@@ -119,12 +652,51 @@ impl SocketClient {
     #[pyo3(name = "close")]
     fn py_close<'py>(slf: PyRef<'_, Self>, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let disconnect_mode = slf.disconnect_mode.clone();
+        let connection_state = slf.connection_state.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             disconnect_mode.store(true, Ordering::SeqCst);
+            connection_state.mark_disconnecting();
             Ok(())
         })
     }
 
+    /// Force a fresh connection, replacing the current one.
+    ///
+    /// # Errors
+    ///
+    /// - Throws an Exception if the client is not active or the reconnect attempt fails.
+    #[pyo3(name = "reconnect_now")]
+    fn py_reconnect_now<'py>(slf: PyRef<'_, Self>, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let connection_state = slf.connection_state.clone();
+        let reconnect_tx = slf.reconnect_tx.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            Self::request_reconnect(&connection_state, &reconnect_tx)
+                .await
+                .map_err(to_pyruntime_err)
+        })
+    }
+
+    /// Wait up to `timeout_secs` for the client to become active.
+    ///
+    /// # Errors
+    ///
+    /// - Throws an Exception if the client is not active within the timeout or closes first.
+    #[pyo3(name = "wait_until_active")]
+    fn py_wait_until_active<'py>(
+        slf: PyRef<'_, Self>,
+        timeout_secs: f64,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let timeout = Duration::try_from_secs_f64(timeout_secs).map_err(to_pyvalue_err)?;
+        let connection_state = slf.connection_state.clone();
+        let disconnect_mode = slf.disconnect_mode.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            Self::await_active(&connection_state, &disconnect_mode, timeout)
+                .await
+                .map_err(to_pyruntime_err)
+        })
+    }
+
     /// Send bytes data to the connection.
     ///
     /// # Errors
@@ -133,16 +705,92 @@ impl SocketClient {
     #[pyo3(name = "send")]
     fn py_send<'py>(
         slf: PyRef<'_, Self>,
-        mut data: Vec<u8>,
+        data: Vec<u8>,
         py: Python<'py>,
     ) -> PyResult<Bound<'py, PyAny>> {
-        let writer = slf.writer.clone();
-        data.extend(&slf.suffix);
+        let sender = slf.sender.clone();
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let mut writer = writer.lock().await;
-            writer.write_all(&data).await?;
-            Ok(())
+            sender.send_bytes(&data).await.map_err(to_pyruntime_err)
+        })
+    }
+
+    /// Send bytes data to the connection, completing once it is written and flushed.
+    ///
+    /// # Errors
+    ///
+    /// - Throws an Exception if it is not able to send data or the flush fails.
+    #[pyo3(name = "send_flushed")]
+    fn py_send_flushed<'py>(
+        slf: PyRef<'_, Self>,
+        data: Vec<u8>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let sender = slf.sender.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            sender
+                .send_bytes_flushed(&data)
+                .await
+                .map_err(to_pyruntime_err)
+        })
+    }
+
+    #[pyo3(name = "pause_reading")]
+    fn py_pause_reading(&self) {
+        self.pause_reading();
+    }
+
+    #[pyo3(name = "resume_reading")]
+    fn py_resume_reading(&self) {
+        self.resume_reading();
+    }
+
+    #[pyo3(name = "is_reading_paused")]
+    fn py_is_reading_paused(&self) -> bool {
+        self.is_reading_paused()
+    }
+
+    /// Send bytes data to the connection without waiting.
+    ///
+    /// # Errors
+    ///
+    /// - Throws an Exception if the client is not active or the writer is busy.
+    #[pyo3(name = "try_send")]
+    fn py_try_send(&self, data: Vec<u8>) -> PyResult<()> {
+        self.try_send_bytes(&data).map_err(to_pyruntime_err)
+    }
+
+    /// Send multiple frames contiguously on the connection.
+    ///
+    /// # Errors
+    ///
+    /// - Throws an Exception if it is not able to send data.
+    #[pyo3(name = "send_many")]
+    fn py_send_many<'py>(
+        slf: PyRef<'_, Self>,
+        frames: Vec<Vec<u8>>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let sender = slf.sender.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let frames: Vec<&[u8]> = frames.iter().map(Vec::as_slice).collect();
+            sender.send_many(&frames).await.map_err(to_pyruntime_err)
+        })
+    }
+
+    /// Waits until all data sent before this call is written and flushed.
+    ///
+    /// # Errors
+    ///
+    /// - Throws an Exception if the client is closed or the flush fails.
+    #[pyo3(name = "flush")]
+    fn py_flush<'py>(slf: PyRef<'_, Self>, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let sender = slf.sender.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            sender.flush().await.map_err(to_pyruntime_err)
         })
     }
 }
@@ -185,7 +185,7 @@ where
     pub async fn until_key_ready(&self, key: &K) {
         loop {
             match self.check_key(key) {
-                Ok(x) => x,
+                Ok(()) => break,
                 Err(neg) => {
                     sleep(neg.wait_time_from(self.clock.now())).await;
                 }
@@ -342,4 +342,19 @@ mod tests {
         assert!(mock_limiter.check_key(&"per_second".to_string()).is_ok());
         assert!(mock_limiter.check_key(&"per_minute".to_string()).is_err());
     }
+
+    #[tokio::test]
+    async fn test_until_key_ready_returns_once_ready() {
+        let mock_limiter = initialize_mock_rate_limiter();
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            mock_limiter.until_key_ready(&"default".to_string()).await;
+            mock_limiter.until_key_ready(&"default".to_string()).await;
+        })
+        .await
+        .expect("Key never reported ready");
+
+        // Both cells of the burst were consumed
+        assert!(mock_limiter.check_key(&"default".to_string()).is_err());
+    }
 }
@@ -16,6 +16,7 @@
 use std::{num::NonZeroU32, prelude::v1::*, time::Duration};
 
 use nonzero_ext::nonzero;
+#[cfg(feature = "python")]
 use pyo3::{exceptions::PyException, prelude::*};
 
 use super::nanos::Nanos;
@@ -52,6 +53,7 @@ pub struct Quota {
     pub(crate) replenish_1_per: Duration,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl Quota {
     #[staticmethod]
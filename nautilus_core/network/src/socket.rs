@@ -16,720 +16,11024 @@
 //! High-performance raw TCP client implementation with TLS capability, automatic reconnection
 //! and state management.
 
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, RawFd};
 use std::{
+    cell::Cell,
+    collections::VecDeque,
+    net::SocketAddr,
     sync::{
-        atomic::{AtomicBool, AtomicU8, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering},
         Arc,
     },
     time::Duration,
 };
 
+use bytes::{Bytes, BytesMut};
+use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use nautilus_core::{time::get_atomic_clock_realtime, UnixNanos};
 use nautilus_cryptography::providers::install_cryptographic_provider;
-use pyo3::prelude::*;
+use rustls::pki_types::ServerName;
+use socket2::{SockRef, TcpKeepalive};
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf},
-    net::TcpStream,
-    sync::Mutex,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpSocket, TcpStream},
+    sync::{mpsc, oneshot, watch, Mutex, Notify},
+    time::Instant,
 };
 use tokio_tungstenite::{
     tungstenite::{client::IntoClientRequest, stream::Mode, Error},
     MaybeTlsStream,
 };
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
-use crate::tls::tcp_tls;
+use crate::{
+    backoff::{
+        BackoffPolicy, CircuitBreaker, CircuitBreakerPolicy, CircuitState, ExponentialBackoff,
+    },
+    framing::{FrameDecoder, FrameError, Framing, READ_CHUNK_SIZE},
+    proxy::{split_host_port, ProxyConfig},
+    ratelimiter::{clock::MonotonicClock, quota::Quota, RateLimiter},
+    tls::{tcp_tls, tcp_tls_with_server_name, Connector, TlsClientOptions},
+};
+
+/// The URL scheme selecting a Unix domain socket transport.
+pub const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// The read half of a socket connection.
+pub type SocketReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// The write half of a socket connection.
+pub type SocketWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+type FrameSender = mpsc::Sender<Vec<u8>>;
+
+/// Rate limiter pacing the frames sent by a client.
+pub(crate) type SendRateLimiter = RateLimiter<(), MonotonicClock>;
+type SharedFrameReceiver = Arc<Mutex<mpsc::Receiver<Vec<u8>>>>;
+
+/// Channel used to request the writer task to flush, replying once the bytes are written.
+type FlushReply = oneshot::Sender<std::io::Result<()>>;
+pub(crate) type FlushRequestSender = mpsc::UnboundedSender<FlushReply>;
+type SharedFlushReceiver = Arc<Mutex<mpsc::UnboundedReceiver<FlushReply>>>;
+
+/// Channel used to request the controller to reconnect, replying with the outcome.
+type ReconnectReply = oneshot::Sender<Result<(), SocketError>>;
+type ReconnectRequestSender = mpsc::UnboundedSender<ReconnectReply>;
+type ReconnectRequestReceiver = mpsc::UnboundedReceiver<ReconnectReply>;
+
+/// The default time a send waits for the client to become active.
+const DEFAULT_SEND_WAIT_ACTIVE_TIMEOUT_MS: u64 = 2_000;
+
+/// The default maximum time (milliseconds) shutdown waits for the read task to observe EOF.
+const DEFAULT_SHUTDOWN_LINGER_MS: u64 = 100;
+
+/// The default maximum time (seconds) shutdown waits for the writer to close.
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 5;
+
+/// The capacity of the channel feeding encoded frames to the writer task.
+const WRITER_CHANNEL_CAPACITY: usize = 1_024;
+
+/// The default number of messages buffered for a [`SocketStream`] and the handler queue feeding it.
+const DEFAULT_STREAM_CAPACITY: usize = 1_024;
+
+/// The number of times a write failing with `WouldBlock` or `Interrupted` is retried.
+const WRITE_RETRY_ATTEMPTS: u32 = 3;
+
+/// The delay before the first retry of a failed write, growing linearly per attempt.
+const WRITE_RETRY_DELAY: Duration = Duration::from_millis(5);
 
-type TcpWriter = WriteHalf<MaybeTlsStream<TcpStream>>;
-type SharedTcpWriter = Arc<Mutex<WriteHalf<MaybeTlsStream<TcpStream>>>>;
-type TcpReader = ReadHalf<MaybeTlsStream<TcpStream>>;
+/// The delay before racing the next resolved address while earlier connection attempts
+/// are still pending, as recommended by RFC 8305.
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
 
 /// Connection state for the Socket client.
 ///
 /// - ACTIVE: Normal operation, all tasks running
 /// - RECONNECTING: In process of reconnecting, tasks paused
+/// - DISCONNECTING: Close requested, waiting for the controller to shut down
 /// - CLOSED: Connection terminated, cleanup in progress
 ///
 /// Connection state transitions:
 /// ACTIVE <-> RECONNECTING: During reconnection attempts
-/// ACTIVE/RECONNECTING -> CLOSED: Only when controller task terminates
-const CONNECTION_ACTIVE: u8 = 0;
-const CONNECTION_RECONNECTING: u8 = 1;
-const CONNECTION_CLOSED: u8 = 2;
-
-/// Configuration for TCP socket connection.
-#[derive(Debug, Clone)]
-#[cfg_attr(
-    feature = "python",
-    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
-)]
-pub struct SocketConfig {
-    /// The URL to connect to.
-    pub url: String,
-    /// The connection mode {Plain, TLS}.
-    pub mode: Mode,
-    /// The sequence of bytes which separates lines.
-    pub suffix: Vec<u8>,
-    /// The Python function to handle incoming messages.
-    pub handler: Arc<PyObject>,
-    /// The optional heartbeat with period and beat message.
-    pub heartbeat: Option<(u64, Vec<u8>)>,
-    /// The timeout (seconds) for reconnects.
-    pub reconnect_timeout_secs: Option<u64>,
-    /// The maximum reconnection attempts before closing the client.
-    pub max_reconnection_tries: Option<u64>,
+/// ACTIVE/RECONNECTING -> DISCONNECTING: When the client is closed
+/// ACTIVE/RECONNECTING/DISCONNECTING -> CLOSED: Only when controller task terminates
+///
+/// The state is stored in an `AtomicU8` and converted at the boundary.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    Active = 0,
+    Reconnecting = 1,
+    Disconnecting = 2,
+    Closed = 3,
 }
 
-/// Creates a TcpStream with the server.
-///
-/// The stream can be encrypted with TLS or Plain. The stream is split into
-/// read and write ends:
-/// - The read end is passed to the task that keeps receiving
-///   messages from the server and passing them to a handler.
-/// - The write end is wrapped in an `Arc<Mutex>` and used to send messages
-///   or heart beats.
-///
-/// The heartbeat is optional and can be configured with an interval and data to
-/// send.
-///
-/// The client uses a suffix to separate messages on the byte stream. It is
-/// appended to all sent messages and heartbeats. It is also used to split
-/// the received byte stream.
-#[cfg_attr(
-    feature = "python",
-    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
-)]
-struct SocketClientInner {
-    config: SocketConfig,
-    read_task: Arc<tokio::task::JoinHandle<()>>,
-    heartbeat_task: Option<tokio::task::JoinHandle<()>>,
-    writer: SharedTcpWriter,
-    reconnection_lock: Arc<Mutex<()>>,
-    connection_state: Arc<AtomicU8>,
-    reconnect_timeout_secs: u64,
+impl ConnectionState {
+    /// Loads the state from the given atomic.
+    #[inline]
+    #[must_use]
+    pub fn from_atomic(value: &AtomicU8) -> Self {
+        Self::from(value.load(Ordering::SeqCst))
+    }
 }
 
-impl SocketClientInner {
-    pub async fn connect_url(config: SocketConfig) -> Result<Self, Error> {
-        install_cryptographic_provider();
+impl From<u8> for ConnectionState {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Active,
+            1 => Self::Reconnecting,
+            2 => Self::Disconnecting,
+            3 => Self::Closed,
+            _ => panic!("Invalid `ConnectionState` value, was {value}"),
+        }
+    }
+}
 
-        let SocketConfig {
-            url,
-            mode,
-            heartbeat,
-            suffix,
-            handler,
-            reconnect_timeout_secs,
-            max_reconnection_tries: _,
-        } = &config;
-        let (reader, writer) = Self::tls_connect_with_server(url, *mode).await?;
-        let writer = Arc::new(Mutex::new(writer));
+impl From<ConnectionState> for u8 {
+    fn from(value: ConnectionState) -> Self {
+        value as Self
+    }
+}
 
-        let connection_state = Arc::new(AtomicU8::new(CONNECTION_ACTIVE));
-        let reconnection_lock = Arc::new(Mutex::new(()));
-        let reconnect_timeout_secs = reconnect_timeout_secs.unwrap_or(30);
+impl std::fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Active => "ACTIVE",
+            Self::Reconnecting => "RECONNECTING",
+            Self::Disconnecting => "DISCONNECTING",
+            Self::Closed => "CLOSED",
+        };
+        f.write_str(s)
+    }
+}
 
-        let handler = Python::with_gil(|py| handler.clone_ref(py));
-        let read_task = Arc::new(Self::spawn_read_task(reader, handler, suffix.clone()));
+/// Shared connection state which publishes every transition to watchers.
+///
+/// The atomic is updated under the watch channel lock so that both always
+/// agree on the latest state and transitions are observed in order.
+#[derive(Debug)]
+pub(crate) struct ConnectionStateCell {
+    value: AtomicU8,
+    tx: watch::Sender<ConnectionState>,
+}
 
-        // Optionally spawn a heartbeat task to periodically ping server
-        let heartbeat_task = heartbeat.as_ref().map(|heartbeat| {
-            Self::spawn_heartbeat_task(
-                connection_state.clone(),
-                heartbeat.clone(),
-                writer.clone(),
-                suffix.clone(),
-            )
-        });
+impl ConnectionStateCell {
+    pub(crate) fn new(state: ConnectionState) -> Self {
+        let (tx, _) = watch::channel(state);
+        Self {
+            value: AtomicU8::new(state.into()),
+            tx,
+        }
+    }
 
-        Ok(Self {
-            config,
-            read_task,
-            heartbeat_task,
-            writer,
-            reconnection_lock,
-            connection_state,
-            reconnect_timeout_secs,
-        })
+    #[inline]
+    pub(crate) fn load(&self) -> ConnectionState {
+        ConnectionState::from_atomic(&self.value)
     }
 
-    pub async fn tls_connect_with_server(
-        url: &str,
-        mode: Mode,
-    ) -> Result<(TcpReader, TcpWriter), Error> {
-        tracing::debug!("Connecting to server");
-        let stream = TcpStream::connect(url).await?;
-        tracing::debug!("Making TLS connection");
-        let request = url.into_client_request()?;
-        tcp_tls(&request, mode, stream, None)
-            .await
-            .map(tokio::io::split)
+    pub(crate) fn store(&self, state: ConnectionState) {
+        self.tx.send_if_modified(|current| {
+            self.value.store(state.into(), Ordering::SeqCst);
+            if *current == state {
+                false
+            } else {
+                *current = state;
+                true
+            }
+        });
     }
 
-    /// Reconnect with server.
+    /// Stores `state` unless the connection is disconnecting or has closed.
     ///
-    /// Make a new connection with server. Use the new read and write halves
-    /// to update the shared writer and the read and heartbeat tasks.
-    async fn reconnect(&mut self) -> Result<(), Error> {
-        tracing::debug!("Reconnecting client");
+    /// Returns `false` if close was initiated, in which case the state is unchanged.
+    pub(crate) fn store_unless_closing(&self, state: ConnectionState) -> bool {
+        let mut stored = false;
+        self.tx.send_if_modified(|current| {
+            if matches!(
+                *current,
+                ConnectionState::Closed | ConnectionState::Disconnecting
+            ) {
+                return false;
+            }
+            stored = true;
+            self.value.store(state.into(), Ordering::SeqCst);
+            if *current == state {
+                false
+            } else {
+                *current = state;
+                true
+            }
+        });
+        stored
+    }
 
-        let timeout = Duration::from_secs(self.reconnect_timeout_secs);
-        tokio::time::timeout(timeout, async {
-            let state_guard = {
-                let guard = self.reconnection_lock.lock().await;
-                self.connection_state
-                    .store(CONNECTION_RECONNECTING, Ordering::SeqCst);
-                guard
-            };
+    /// Marks the connection as reconnecting if it is currently active.
+    ///
+    /// Returns `false` if the connection was in any other state.
+    pub(crate) fn mark_reconnecting(&self) -> bool {
+        self.tx.send_if_modified(|current| {
+            if *current != ConnectionState::Active {
+                return false;
+            }
+            self.value
+                .store(ConnectionState::Reconnecting.into(), Ordering::SeqCst);
+            *current = ConnectionState::Reconnecting;
+            true
+        })
+    }
 
-            // Clean up existing tasks
-            shutdown(
-                self.read_task.clone(),
-                self.heartbeat_task.take(),
-                self.writer.clone(),
-            )
-            .await;
+    /// Marks the connection as disconnecting unless it has already closed.
+    pub(crate) fn mark_disconnecting(&self) {
+        self.tx.send_if_modified(|current| {
+            if matches!(
+                *current,
+                ConnectionState::Closed | ConnectionState::Disconnecting
+            ) {
+                return false;
+            }
+            self.value
+                .store(ConnectionState::Disconnecting.into(), Ordering::SeqCst);
+            *current = ConnectionState::Disconnecting;
+            true
+        });
+    }
 
-            let SocketConfig {
-                url,
-                mode,
-                heartbeat,
-                suffix,
-                handler,
-                reconnect_timeout_secs: _,
-                max_reconnection_tries: _,
-            } = &self.config;
-            // Create a fresh connection
-            let (reader, writer) = Self::tls_connect_with_server(url, *mode).await?;
-            let writer = Arc::new(Mutex::new(writer));
-            self.writer = writer.clone();
+    pub(crate) fn subscribe(&self) -> watch::Receiver<ConnectionState> {
+        self.tx.subscribe()
+    }
+}
 
-            // Spawn new read task
-            let handler_for_read = Python::with_gil(|py| handler.clone_ref(py));
-            self.read_task = Arc::new(Self::spawn_read_task(
-                reader,
-                handler_for_read,
-                suffix.clone(),
-            ));
+/// Errors returned by the [`SocketClient`].
+#[derive(thiserror::Error, Debug)]
+pub enum SocketError {
+    #[error("Not connected")]
+    NotConnected,
 
-            // Optionally spawn new heartbeat task
-            self.heartbeat_task = heartbeat.as_ref().map(|heartbeat| {
-                Self::spawn_heartbeat_task(
-                    self.connection_state.clone(),
-                    heartbeat.clone(),
-                    writer.clone(),
-                    suffix.clone(),
-                )
-            });
+    #[error("Timed out: {0}")]
+    Timeout(String),
 
-            drop(state_guard);
-            self.connection_state
-                .store(CONNECTION_ACTIVE, Ordering::SeqCst);
+    #[error("Client is closed")]
+    Closed,
 
-            tracing::debug!("Reconnect succeeded");
-            Ok(())
-        })
-        .await
-        .map_err(|_| {
-            Error::Io(std::io::Error::new(
-                std::io::ErrorKind::TimedOut,
-                format!("reconnection timed out after {}s", timeout.as_secs()),
-            ))
-        })?
-    }
+    #[error("IO error: {0}")]
+    Io(std::io::Error),
 
-    /// Check if the client is still alive.
-    ///
-    /// The client is connected if the read task has not finished. It is expected
-    /// that in case of any failure client or server side. The read task will be
-    /// shutdown. There might be some delay between the connection being closed
-    /// and the client detecting it.
-    #[inline]
-    #[must_use]
-    pub fn is_alive(&self) -> bool {
-        !self.read_task.is_finished()
-    }
+    #[error("TLS error: {0}")]
+    Tls(String),
 
-    #[must_use]
-    fn spawn_read_task(
-        mut reader: TcpReader,
-        handler: PyObject,
-        suffix: Vec<u8>,
-    ) -> tokio::task::JoinHandle<()> {
-        tracing::debug!("Started task 'read'");
+    #[error("Reached max reconnection tries")]
+    MaxRetriesExceeded,
 
-        tokio::task::spawn(async move {
-            let mut buf = Vec::new();
+    #[error("Writer is busy")]
+    Busy,
 
-            loop {
-                match reader.read_buf(&mut buf).await {
-                    // Connection has been terminated or vector buffer is complete
-                    Ok(0) => {
-                        tracing::debug!("Connection closed by server");
-                        break;
-                    }
-                    Err(e) => {
-                        tracing::debug!("Connection ended: {e}");
-                        break;
-                    }
-                    // Received bytes of data
-                    Ok(bytes) => {
-                        tracing::trace!("Received <binary> {bytes} bytes");
-
-                        // While received data has a line break
-                        // drain it and pass it to the handler
-                        while let Some((i, _)) = &buf
-                            .windows(suffix.len())
-                            .enumerate()
-                            .find(|(_, pair)| pair.eq(&suffix))
-                        {
-                            let mut data: Vec<u8> = buf.drain(0..i + suffix.len()).collect();
-                            data.truncate(data.len() - suffix.len());
-
-                            if let Err(e) =
-                                Python::with_gil(|py| handler.call1(py, (data.as_slice(),)))
-                            {
-                                tracing::error!("Call to handler failed: {e}");
-                                break;
-                            }
-                        }
-                    }
-                };
-            }
-        })
-    }
+    #[error("Cancelled")]
+    Cancelled,
 
-    fn spawn_heartbeat_task(
-        connection_state: Arc<AtomicU8>,
-        heartbeat: (u64, Vec<u8>),
-        writer: SharedTcpWriter,
-        suffix: Vec<u8>,
-    ) -> tokio::task::JoinHandle<()> {
-        tracing::debug!("Started task 'heartbeat'");
-        let (interval_secs, mut message) = heartbeat;
+    #[error("Rate limited")]
+    RateLimited,
 
-        tokio::task::spawn(async move {
-            let interval = Duration::from_secs(interval_secs);
-            message.extend(suffix);
+    #[error("TLS handshake timed out after {0:?}")]
+    TlsHandshakeTimeout(Duration),
+}
 
-            loop {
-                tokio::time::sleep(interval).await;
-
-                match connection_state.load(Ordering::SeqCst) {
-                    CONNECTION_ACTIVE => {
-                        let mut guard = writer.lock().await;
-                        match guard.write_all(&message).await {
-                            Ok(()) => tracing::trace!("Sent heartbeat"),
-                            Err(e) => tracing::error!("Failed to send heartbeat: {e}"),
-                        }
-                    }
-                    CONNECTION_CLOSED => break,
-                    _ => continue, // Reconnecting
-                }
-            }
-        })
+impl SocketError {
+    /// Returns whether retrying after this error is pointless, e.g. on a TLS
+    /// certificate failure or a permission error.
+    #[must_use]
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            Self::Tls(_) => true,
+            Self::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::PermissionDenied
+                    | std::io::ErrorKind::InvalidInput
+                    | std::io::ErrorKind::Unsupported
+            ),
+            _ => false,
+        }
     }
 }
 
-/// Shutdown socket connection.
-///
-/// The client must be explicitly shutdown before dropping otherwise
-/// the connection might still be alive for some time before terminating.
-/// Closing the connection is an async call which cannot be done by the
-/// drop method so it must be done explicitly.
-async fn shutdown(
-    read_task: Arc<tokio::task::JoinHandle<()>>,
-    heartbeat_task: Option<tokio::task::JoinHandle<()>>,
-    writer: SharedTcpWriter,
-) {
-    tracing::debug!("Closing");
-
-    let timeout = Duration::from_secs(5);
-    if tokio::time::timeout(timeout, async {
-        // Final close of writer
-        let mut writer = writer.lock().await;
-        if let Err(e) = writer.shutdown().await {
-            tracing::error!("Error on shutdown: {e}");
+impl Clone for SocketError {
+    fn clone(&self) -> Self {
+        match self {
+            Self::NotConnected => Self::NotConnected,
+            Self::Timeout(msg) => Self::Timeout(msg.clone()),
+            Self::Closed => Self::Closed,
+            Self::Io(e) => Self::Io(std::io::Error::new(e.kind(), e.to_string())),
+            Self::Tls(msg) => Self::Tls(msg.clone()),
+            Self::MaxRetriesExceeded => Self::MaxRetriesExceeded,
+            Self::Busy => Self::Busy,
+            Self::Cancelled => Self::Cancelled,
+            Self::RateLimited => Self::RateLimited,
+            Self::TlsHandshakeTimeout(timeout) => Self::TlsHandshakeTimeout(*timeout),
         }
-        drop(writer);
-
-        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
 
-        // Abort tasks
-        if !read_task.is_finished() {
-            read_task.abort();
-            tracing::debug!("Aborted read task");
+impl From<std::io::Error> for SocketError {
+    fn from(e: std::io::Error) -> Self {
+        if let Some(TlsHandshakeTimeout(timeout)) =
+            e.get_ref().and_then(|inner| inner.downcast_ref())
+        {
+            return Self::TlsHandshakeTimeout(*timeout);
         }
-        if let Some(task) = heartbeat_task {
-            if !task.is_finished() {
-                task.abort();
-                tracing::debug!("Aborted heartbeat task");
-            }
+        let is_tls = e.get_ref().is_some_and(|inner| inner.is::<rustls::Error>());
+        match e.kind() {
+            std::io::ErrorKind::TimedOut => Self::Timeout(e.to_string()),
+            std::io::ErrorKind::NotConnected => Self::NotConnected,
+            _ if is_tls => Self::Tls(e.to_string()),
+            _ => Self::Io(e),
         }
-    })
-    .await
-    .is_err()
-    {
-        tracing::error!("Shutdown timed out after {}s", timeout.as_secs());
     }
-
-    tracing::debug!("Closed");
 }
 
-impl Drop for SocketClientInner {
-    fn drop(&mut self) {
-        if !self.read_task.is_finished() {
-            self.read_task.abort();
-        }
-
-        // Cancel heart beat task
-        if let Some(ref handle) = self.heartbeat_task.take() {
-            if !handle.is_finished() {
-                handle.abort();
-            }
+impl From<Error> for SocketError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e.into(),
+            Error::Tls(e) => Self::Tls(e.to_string()),
+            e => Self::Io(std::io::Error::other(e)),
         }
     }
 }
 
-#[cfg_attr(
-    feature = "python",
-    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
-)]
-pub struct SocketClient {
-    pub(crate) writer: SharedTcpWriter,
-    pub(crate) controller_task: tokio::task::JoinHandle<()>,
-    pub(crate) disconnect_mode: Arc<AtomicBool>,
-    pub(crate) connection_state: Arc<AtomicU8>,
-    pub(crate) suffix: Vec<u8>,
+/// The source of a [`std::io::ErrorKind::TimedOut`] error raised when the TLS handshake
+/// of a connection does not complete within `tls_handshake_timeout_secs`.
+#[derive(thiserror::Error, Debug)]
+#[error("TLS handshake timed out after {0:?}")]
+struct TlsHandshakeTimeout(Duration);
+
+/// The position of a received message on its connection (when `handler_receive_position` is set).
+///
+/// Both counters start at zero for every connection, and count every frame decoded from
+/// the stream, including any dropped by the `on_recv` hook, so a gap in `seq` means a
+/// message was not delivered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FramePosition {
+    /// The sequence number of the message on its connection.
+    pub seq: u64,
+    /// The byte offset of the start of the message, including its framing, on its connection.
+    pub offset: u64,
 }
 
-impl SocketClient {
-    pub async fn connect(
-        config: SocketConfig,
-        post_connection: Option<PyObject>,
-        post_reconnection: Option<PyObject>,
-        post_disconnection: Option<PyObject>,
-    ) -> Result<Self, Error> {
-        let suffix = config.suffix.clone();
-        let max_reconnection_tries = config.max_reconnection_tries;
-        let inner = SocketClientInner::connect_url(config).await?;
-        let writer = inner.writer.clone();
-        let disconnect_mode = Arc::new(AtomicBool::new(false));
-        let connection_state = inner.connection_state.clone();
+/// Handler for messages received by the [`SocketClient`].
+///
+/// The read task calls the handler with each complete message (without the framing).
+/// Messages are delivered as [`Bytes`] slices of the read buffer, so a handler which
+/// keeps a message beyond the call can hold on to it without copying. Any `Fn(&[u8])`
+/// closure is a handler, which allows pure-Rust consumers to use the client without
+/// any Python involvement. With the `python` feature enabled a `PyObject` callable
+/// is also a handler.
+pub trait MessageHandler: Send + Sync {
+    /// Handles a single received message.
+    fn handle(&self, data: &[u8]);
 
-        let controller_task = Self::spawn_controller_task(
-            inner,
-            disconnect_mode.clone(),
-            post_reconnection,
-            post_disconnection,
-            max_reconnection_tries,
-        );
+    /// Handles a single received message as a shared slice of the read buffer.
+    ///
+    /// The default implementation calls [`MessageHandler::handle`].
+    fn handle_bytes(&self, data: Bytes) {
+        self.handle(&data);
+    }
 
-        if let Some(handler) = post_connection {
-            Python::with_gil(|py| match handler.call0(py) {
-                Ok(_) => tracing::debug!("Called `post_connection` handler"),
-                Err(e) => tracing::error!("Error calling `post_connection` handler: {e}"),
-            });
+    /// Handles a batch of received messages (when `handler_batch_size` is configured).
+    ///
+    /// The default implementation calls [`MessageHandler::handle`] for each message.
+    fn handle_batch(&self, frames: &[Bytes]) {
+        for data in frames {
+            self.handle(data);
         }
+    }
 
-        Ok(Self {
-            writer,
-            controller_task,
-            disconnect_mode,
-            connection_state,
-            suffix,
-        })
+    /// Handles a single received message with the time it was read (when `handler_receive_ts` is set).
+    ///
+    /// The default implementation ignores the timestamp and calls [`MessageHandler::handle`].
+    fn handle_with_ts(&self, data: &[u8], ts_recv: UnixNanos) {
+        let _ = ts_recv;
+        self.handle(data);
     }
 
-    /// Check if the client connection is active.
+    /// Handles a single received message as a shared slice of the read buffer, with the
+    /// time it was read (when `handler_receive_ts` is set).
     ///
-    /// Returns `true` if the client is connected and has not been signalled to disconnect.
-    /// The client will automatically retry connection based on its configuration.
-    #[inline]
-    #[must_use]
-    pub fn is_active(&self) -> bool {
-        let disconnect = self.disconnect_mode.load(Ordering::SeqCst);
-        self.connection_state.load(Ordering::SeqCst) == CONNECTION_ACTIVE && !disconnect
+    /// The default implementation calls [`MessageHandler::handle_with_ts`].
+    fn handle_bytes_with_ts(&self, data: Bytes, ts_recv: UnixNanos) {
+        self.handle_with_ts(&data, ts_recv);
     }
 
-    /// Check if the client is reconnecting.
+    /// Handles a single received message with its position on the connection (when
+    /// `handler_receive_position` is set).
     ///
-    /// Returns `true` if the client lost connection and is attempting to reestablish it.
-    /// The client will automatically retry connection based on its configuration.
-    #[inline]
-    #[must_use]
-    pub fn is_reconnecting(&self) -> bool {
-        self.connection_state.load(Ordering::SeqCst) == CONNECTION_RECONNECTING
+    /// The default implementation ignores the position and calls [`MessageHandler::handle`].
+    fn handle_with_position(&self, data: &[u8], position: FramePosition) {
+        let _ = position;
+        self.handle(data);
     }
 
-    /// Check if the client is disconnecting.
+    /// Handles a batch of received messages with the time they were read (when both
+    /// `handler_batch_size` and `handler_receive_ts` are set).
     ///
-    /// Returns `true` if the client is in disconnect mode.
-    #[inline]
-    #[must_use]
-    pub fn is_disconnecting(&self) -> bool {
-        self.disconnect_mode.load(Ordering::SeqCst)
+    /// The default implementation ignores the timestamp and calls [`MessageHandler::handle_batch`].
+    fn handle_batch_with_ts(&self, frames: &[Bytes], ts_recv: UnixNanos) {
+        let _ = ts_recv;
+        self.handle_batch(frames);
     }
 
-    /// Check if the client is closed.
+    /// Called before the first message of each connection, so a stateful handler can
+    /// discard anything left over from the previous connection.
     ///
-    /// Returns `true` if the client has been explicitly disconnected or reached
-    /// maximum reconnection attempts. In this state, the client cannot be reused
-    /// and a new client must be created for further connections.
-    #[inline]
+    /// The default implementation does nothing.
+    fn reset(&self) {}
+}
+
+impl<F> MessageHandler for F
+where
+    F: Fn(&[u8]) + Send + Sync,
+{
+    fn handle(&self, data: &[u8]) {
+        self(data);
+    }
+}
+
+impl std::fmt::Debug for dyn MessageHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(stringify!(MessageHandler))
+    }
+}
+
+/// Callback invoked with the number of buffered bytes when `max_message_size` is exceeded.
+pub type OverflowCallback = Arc<dyn Fn(usize) + Send + Sync>;
+
+/// Callback invoked with the handler queue depth and `true` when it reaches the high-water
+/// mark, or `false` when it drains back to the low-water mark.
+pub type BackpressureCallback = Arc<dyn Fn(usize, bool) + Send + Sync>;
+
+/// Callback producing the payload of each heartbeat, e.g. to include a sequence number.
+pub type HeartbeatPayload = Arc<dyn Fn() -> std::io::Result<Vec<u8>> + Send + Sync>;
+
+/// Predicate which returns `true` if a received message is a response to a heartbeat.
+pub type HeartbeatResponse = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// Predicate which returns `true` if a reconnect error is fatal and the client should close.
+pub type FatalErrorClassifier = Arc<dyn Fn(&SocketError) -> bool + Send + Sync>;
+
+/// Callback invoked with the error when the client closes on a fatal reconnect error.
+pub type FatalCallback = Arc<dyn Fn(&SocketError) + Send + Sync>;
+
+/// Callback invoked with the last error and the attempt count when reconnection tries are exhausted.
+pub type GiveUpCallback = Arc<dyn Fn(&SocketError, u64) + Send + Sync>;
+
+/// Callback invoked after every established connection, with `true` for the initial connect
+/// and `false` for each reconnect.
+pub type EstablishedCallback = Arc<dyn Fn(bool) + Send + Sync>;
+
+/// Callback invoked with the [`ConnectionInfo`] once a connection is established or lost.
+pub type ConnectionCallback = Arc<dyn Fn(ConnectionInfo) + Send + Sync>;
+
+/// Callback invoked with the attempt number, starting at 1, before each reconnect attempt.
+pub type ReconnectAttemptCallback = Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Callback invoked with each error reported by the handler.
+pub type HandlerErrorCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// The endpoint to dial for a connection attempt, as returned by a [`UrlProvider`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Endpoint {
+    /// The URL to connect to.
+    pub url: String,
+    /// The optional raw bytes written to the connection once established, before any frames.
+    pub handshake: Option<Vec<u8>>,
+}
+
+/// Hook transforming the payload of a sent message before it is framed.
+///
+/// An error from the hook fails the send.
+pub type SendTransform = Arc<dyn Fn(&[u8]) -> std::io::Result<Vec<u8>> + Send + Sync>;
+
+/// Hook transforming a received message before it is passed to the handler.
+///
+/// A message for which the hook fails is skipped, the connection stays up.
+pub type RecvTransform = Arc<dyn Fn(&[u8]) -> std::io::Result<Vec<u8>> + Send + Sync>;
+
+/// Callback invoked with a received message and the error of the `on_recv` hook.
+pub type RecvErrorCallback = Arc<dyn Fn(&[u8], &std::io::Error) + Send + Sync>;
+
+/// Provider called before every connection attempt to obtain the endpoint to dial.
+///
+/// An error from the provider counts as a failed connection attempt.
+pub type UrlProvider = Arc<dyn Fn() -> std::io::Result<Endpoint> + Send + Sync>;
+
+/// Resolver returning the addresses of a `host:port`, called on every connection attempt.
+///
+/// Resolution is never cached, so a reconnect picks up addresses changed by a failover.
+pub type Resolver =
+    Arc<dyn Fn(&str) -> BoxFuture<'static, std::io::Result<Vec<SocketAddr>>> + Send + Sync>;
+
+/// Factory which establishes a new connection, returning its read and write halves.
+///
+/// The client calls the connector for the initial connection and on every
+/// reconnect, which separates how to dial from how the connection is supervised.
+pub type SocketConnector =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<SocketConnection, Error>> + Send + Sync>;
+
+/// An established connection split into read and write halves.
+pub struct SocketConnection {
+    /// The read half of the connection.
+    pub reader: SocketReader,
+    /// The write half of the connection.
+    pub writer: SocketWriter,
+    /// The address of the remote peer, if known.
+    pub peer_addr: Option<SocketAddr>,
+    /// The local address the connection is bound to, if known.
+    pub local_addr: Option<SocketAddr>,
+    /// The URL of the endpoint connected to, if known.
+    pub url: Option<String>,
+    /// The protocol negotiated with ALPN during the TLS handshake, if any.
+    pub alpn_protocol: Option<Vec<u8>>,
+    /// The file descriptor of the underlying socket, if known.
+    #[cfg(unix)]
+    pub raw_fd: Option<RawFd>,
+}
+
+impl SocketConnection {
+    /// Creates a new [`SocketConnection`] instance by splitting `stream`.
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (reader, writer) = tokio::io::split(stream);
+        Self {
+            reader: Box::new(reader),
+            writer: Box::new(writer),
+            peer_addr: None,
+            local_addr: None,
+            url: None,
+            alpn_protocol: None,
+            #[cfg(unix)]
+            raw_fd: None,
+        }
+    }
+
+    /// Sets the address of the remote peer.
     #[must_use]
-    pub fn is_closed(&self) -> bool {
-        self.connection_state.load(Ordering::SeqCst) == CONNECTION_CLOSED
+    pub const fn with_peer_addr(mut self, peer_addr: Option<SocketAddr>) -> Self {
+        self.peer_addr = peer_addr;
+        self
     }
 
-    /// Close the client.
-    ///
-    /// Controller task will periodically check the disconnect mode
-    /// and shutdown the client if it is not alive.
-    pub async fn close(&self) {
-        self.disconnect_mode.store(true, Ordering::SeqCst);
+    /// Sets the local address the connection is bound to.
+    #[must_use]
+    pub const fn with_local_addr(mut self, local_addr: Option<SocketAddr>) -> Self {
+        self.local_addr = local_addr;
+        self
+    }
 
-        match tokio::time::timeout(Duration::from_secs(5), async {
-            while !self.is_closed() {
-                tokio::time::sleep(Duration::from_millis(10)).await;
-            }
+    /// Sets the URL of the endpoint connected to.
+    #[must_use]
+    pub fn with_url(mut self, url: Option<String>) -> Self {
+        self.url = url;
+        self
+    }
 
-            if !self.controller_task.is_finished() {
-                self.controller_task.abort();
-                tracing::debug!("Aborted controller task");
-            }
-        })
-        .await
-        {
-            Ok(()) => {
-                tracing::debug!("Controller task finished");
-            }
-            Err(_) => {
-                tracing::error!("Timeout waiting for controller task to finish");
-            }
+    /// Sets the protocol negotiated with ALPN.
+    #[must_use]
+    pub fn with_alpn_protocol(mut self, alpn_protocol: Option<Vec<u8>>) -> Self {
+        self.alpn_protocol = alpn_protocol;
+        self
+    }
+
+    /// Sets the file descriptor of the underlying socket.
+    #[cfg(unix)]
+    #[must_use]
+    pub fn with_raw_fd(mut self, raw_fd: Option<RawFd>) -> Self {
+        self.raw_fd = raw_fd;
+        self
+    }
+}
+
+impl std::fmt::Debug for SocketConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(SocketConnection))
+            .field("peer_addr", &self.peer_addr)
+            .field("local_addr", &self.local_addr)
+            .field("url", &self.url)
+            .field("alpn_protocol", &self.alpn_protocol)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Options applied to the TCP socket of every connection before the TLS handshake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TcpOptions {
+    /// If `TCP_NODELAY` is set, disabling Nagle's algorithm (default true, for latency).
+    pub nodelay: bool,
+    /// The optional idle time before OS keepalive probes are sent.
+    pub keepalive: Option<Duration>,
+    /// The optional size (bytes) of the socket receive buffer.
+    pub recv_buffer_size: Option<usize>,
+    /// The optional size (bytes) of the socket send buffer.
+    pub send_buffer_size: Option<usize>,
+    /// The optional 6-bit DSCP marked in the `IP_TOS` field, or the IPv6 traffic class, of
+    /// outgoing packets, set before connecting.
+    pub dscp: Option<u8>,
+    /// The optional `SO_LINGER` time the OS keeps trying to deliver unsent data on close.
+    ///
+    /// Zero makes closing abortive: the connection is reset without a FIN, which frees the
+    /// port immediately, avoids `TIME_WAIT` and discards any unsent data. A positive time
+    /// bounds how long the OS lingers over unsent data. By default the OS closes gracefully
+    /// in the background.
+    pub linger: Option<Duration>,
+}
+
+impl Default for TcpOptions {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            keepalive: None,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+            dscp: None,
+            linger: None,
         }
     }
+}
 
-    pub async fn send_bytes(&self, data: &[u8]) -> Result<(), std::io::Error> {
-        if self.is_closed() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::NotConnected,
-                "Not connected",
-            ));
+impl TcpOptions {
+    /// Applies the options to `stream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any option cannot be set on the socket.
+    pub fn apply(&self, stream: &TcpStream) -> std::io::Result<()> {
+        stream.set_nodelay(self.nodelay)?;
+        let socket = SockRef::from(stream);
+        if let Some(keepalive) = self.keepalive {
+            socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(keepalive))?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        if let Some(linger) = self.linger {
+            socket.set_linger(Some(linger))?;
         }
+        Ok(())
+    }
 
-        let timeout = Duration::from_secs(2);
-        let check_interval = Duration::from_millis(1);
+    /// Returns `true` if closing resets the connection instead of sending a FIN.
+    pub(crate) fn is_abortive_close(&self) -> bool {
+        self.linger == Some(Duration::ZERO)
+    }
 
-        if !self.is_active() {
-            tracing::debug!("Waiting for client to become active before sending (2s)...");
-            match tokio::time::timeout(timeout, async {
-                while !self.is_active() {
-                    tokio::time::sleep(check_interval).await;
-                }
-            })
-            .await
-            {
-                Ok(_) => tracing::debug!("Client now active"),
-                Err(_) => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::TimedOut,
-                        "Client did not become active within timeout",
-                    ))
-                }
+    /// Applies the options which must be set before `socket` connects to `addr`.
+    fn apply_before_connect(&self, socket: &TcpSocket, addr: &SocketAddr) -> std::io::Result<()> {
+        if let Some(dscp) = self.dscp {
+            // The DSCP occupies the upper 6 bits of the TOS byte and traffic class alike
+            let value = u32::from(dscp) << 2;
+            if addr.is_ipv6() {
+                SockRef::from(socket).set_tclass_v6(value)?;
+            } else {
+                SockRef::from(socket).set_tos(value)?;
             }
         }
+        Ok(())
+    }
+}
+
+/// Settings used by the default connector to dial the server.
+#[derive(Clone)]
+struct DialOptions {
+    urls: Vec<String>,
+    mode: Mode,
+    tcp_options: TcpOptions,
+    connect_timeout: Option<Duration>,
+    tls_handshake_timeout: Option<Duration>,
+    tls_connector: Option<Connector>,
+    tls_sni: Option<String>,
+    proxy: Option<ProxyConfig>,
+    url_provider: Option<UrlProvider>,
+    resolver: Option<Resolver>,
+    bind_address: Option<SocketAddr>,
+}
+
+impl DialOptions {
+    fn from_config(config: &SocketConfig) -> std::io::Result<Self> {
+        let tls_options = TlsClientOptions {
+            client_cert_pem: config.client_cert_pem.clone(),
+            client_key_pem: config.client_key_pem.clone(),
+            root_ca_pem: config.root_ca_pem.clone(),
+            danger_accept_invalid_certs: config.danger_accept_invalid_certs,
+            alpn_protocols: config.alpn_protocols.clone(),
+            pinned_cert_sha256: config.pinned_cert_sha256.clone(),
+        };
+        // Without custom options `tcp_tls` falls back to the platform defaults
+        let tls_connector = match config.mode {
+            Mode::Tls if !tls_options.is_default() => Some(Connector::Rustls(tls_options.build()?)),
+            _ => None,
+        };
 
-        let mut writer = self.writer.lock().await;
-        writer.write_all(data).await?;
-        writer.write_all(&self.suffix).await
+        Ok(Self {
+            urls: std::iter::once(&config.url)
+                .chain(&config.fallback_urls)
+                .cloned()
+                .collect(),
+            mode: config.mode,
+            tcp_options: config.tcp_options,
+            connect_timeout: config.connect_timeout_secs.map(Duration::from_secs),
+            tls_handshake_timeout: config.tls_handshake_timeout_secs.map(Duration::from_secs),
+            tls_connector,
+            tls_sni: config.tls_sni.clone(),
+            proxy: config.proxy.clone(),
+            url_provider: config.url_provider.clone(),
+            resolver: config.resolver.clone(),
+            bind_address: config.bind_address,
+        })
     }
+}
 
-    fn spawn_controller_task(
-        mut inner: SocketClientInner,
-        disconnect_mode: Arc<AtomicBool>,
-        post_reconnection: Option<PyObject>,
-        post_disconnection: Option<PyObject>,
-        max_reconnection_tries: Option<u64>,
-    ) -> tokio::task::JoinHandle<()> {
-        tokio::task::spawn(async move {
-            let check_interval = Duration::from_millis(10);
-            let retry_interval = Duration::from_millis(1000);
-            let mut retry_counter: u64 = 0;
+/// Addresses and negotiated protocol of the current connection, refreshed on every reconnect.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConnectionAddrs {
+    peer: Option<SocketAddr>,
+    local: Option<SocketAddr>,
+    url: Option<String>,
+    alpn_protocol: Option<Vec<u8>>,
+    #[cfg(unix)]
+    raw_fd: Option<RawFd>,
+}
 
-            loop {
-                tokio::time::sleep(check_interval).await;
+type SharedConnectionAddrs = Arc<std::sync::Mutex<ConnectionAddrs>>;
 
-                // Check if client needs to disconnect
-                let disconnect = disconnect_mode.load(Ordering::SeqCst);
-                match (disconnect, inner.is_alive()) {
-                    (false, false) => match inner.reconnect().await {
-                        Ok(()) => {
-                            tracing::debug!("Reconnected successfully");
-                            retry_counter = 0;
-
-                            if let Some(ref handler) = post_reconnection {
-                                Python::with_gil(|py| match handler.call0(py) {
-                                    Ok(_) => tracing::debug!("Called `post_reconnection` handler"),
-                                    Err(e) => {
-                                        tracing::error!(
-                                            "Error calling `post_reconnection` handler: {e}"
-                                        );
-                                    }
-                                });
-                            }
-                        }
-                        Err(e) => {
-                            retry_counter += 1;
+/// The reason the read task of a connection stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadExitReason {
+    /// The server closed the connection cleanly.
+    Eof,
+    /// The connection failed or was dropped by the client, e.g. on a read idle
+    /// timeout (`TimedOut`) or an invalid frame (`InvalidData`).
+    Io(std::io::ErrorKind),
+    /// The handler reported `max_handler_errors` consecutive errors.
+    HandlerErrors,
+}
 
-                            if let Some(max) = max_reconnection_tries {
-                                tracing::warn!("Reconnect failed {e}. Retry {retry_counter}/{max}");
+impl std::fmt::Display for ReadExitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Eof => f.write_str("EOF"),
+            Self::Io(kind) => write!(f, "IO error: {kind}"),
+            Self::HandlerErrors => f.write_str("handler errors"),
+        }
+    }
+}
 
-                                if retry_counter >= max {
-                                    tracing::error!("Reached max reconnection tries");
-                                    break;
-                                }
-                            } else {
-                                tracing::warn!(
-                                    "Reconnect failed {e}. Retry {retry_counter} (infinite)"
-                                );
-                            }
+type SharedExitReason = Arc<std::sync::Mutex<Option<ReadExitReason>>>;
 
-                            tokio::time::sleep(retry_interval).await;
-                        }
-                    },
-                    (true, true) => {
-                        tracing::debug!("Shutting down inner client");
-                        shutdown(
-                            inner.read_task.clone(),
-                            inner.heartbeat_task.take(),
-                            inner.writer.clone(),
-                        )
-                        .await;
-                        if let Some(ref handler) = post_disconnection {
-                            Python::with_gil(|py| match handler.call0(py) {
-                                Ok(_) => tracing::debug!("Called `post_disconnection` handler"),
-                                Err(e) => {
-                                    tracing::error!(
-                                        "Error calling `post_disconnection` handler: {e}"
-                                    );
-                                }
-                            });
-                        }
-                        break;
-                    }
-                    (true, false) => {
-                        tracing::debug!("Inner client is disconnected");
-                        tracing::debug!("Shutting down inner client to clean up running tasks");
-                        shutdown(
-                            inner.read_task.clone(),
-                            inner.heartbeat_task.take(),
-                            inner.writer.clone(),
-                        )
-                        .await;
-                    }
-                    _ => (),
-                }
-            }
-            inner
-                .connection_state
-                .store(CONNECTION_CLOSED, Ordering::SeqCst);
-        })
+/// Context passed to the `post_connection`, `post_reconnection` and `post_disconnection` callbacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    /// The reconnection attempt, 1 for the first retry after a disconnect and 0 for the
+    /// initial connection.
+    pub attempt: u64,
+    /// The address of the remote peer, if known.
+    pub peer_addr: Option<SocketAddr>,
+    /// The time elapsed since the connection was lost.
+    pub elapsed: Duration,
+}
+
+/// A snapshot of the traffic counters of a [`SocketClient`].
+///
+/// The counters accumulate over the lifetime of the client, across reconnects.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(frozen, get_all, module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub struct SocketStats {
+    /// The number of encoded bytes passed to the writer, including framing.
+    pub bytes_sent: u64,
+    /// The number of bytes read from the connection.
+    pub bytes_received: u64,
+    /// The number of frames passed to the writer.
+    pub frames_sent: u64,
+    /// The number of frames passed to the handler.
+    pub frames_received: u64,
+    /// The number of successful reconnects.
+    pub reconnect_count: u64,
+    /// The number of received frames dropped because the handler queue was full.
+    pub handler_frames_dropped: u64,
+    /// The number of items currently waiting in the handler queue.
+    pub handler_queue_depth: u64,
+    /// The maximum number of items observed waiting in the handler queue.
+    pub handler_queue_max_depth: u64,
+    /// The exponential moving average (microseconds) of the time spent in each handler call.
+    pub handler_latency_avg_us: u64,
+    /// The maximum time (microseconds) spent in a single handler call.
+    pub handler_latency_max_us: u64,
+}
+
+/// Sentinel for an activity timestamp which has not been recorded yet.
+const NO_ACTIVITY: u64 = u64::MAX;
+
+/// Inverse weight of each new sample in the handler latency moving average.
+const HANDLER_LATENCY_EWMA_WEIGHT: u64 = 8;
+
+/// Traffic counters and last-activity timestamps shared by the client and its tasks.
+///
+/// Timestamps are monotonic milliseconds since the counters were created.
+#[derive(Debug)]
+pub(crate) struct SocketCounters {
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    frames_sent: AtomicU64,
+    frames_received: AtomicU64,
+    reconnect_count: AtomicU64,
+    handler_frames_dropped: AtomicU64,
+    handler_queue_max_depth: AtomicU64,
+    handler_latency_avg_us: AtomicU64,
+    handler_latency_max_us: AtomicU64,
+    epoch: Instant,
+    last_send_ms: AtomicU64,
+    last_recv_ms: AtomicU64,
+    connected_ms: AtomicU64,
+    circuit_state: AtomicU8,
+}
+
+impl Default for SocketCounters {
+    fn default() -> Self {
+        Self {
+            bytes_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            frames_sent: AtomicU64::new(0),
+            frames_received: AtomicU64::new(0),
+            reconnect_count: AtomicU64::new(0),
+            handler_frames_dropped: AtomicU64::new(0),
+            handler_queue_max_depth: AtomicU64::new(0),
+            handler_latency_avg_us: AtomicU64::new(NO_ACTIVITY),
+            handler_latency_max_us: AtomicU64::new(0),
+            epoch: Instant::now(),
+            last_send_ms: AtomicU64::new(NO_ACTIVITY),
+            last_recv_ms: AtomicU64::new(NO_ACTIVITY),
+            connected_ms: AtomicU64::new(NO_ACTIVITY),
+            circuit_state: AtomicU8::new(CircuitState::Closed as u8),
+        }
     }
 }
 
-////////////////////////////////////////////////////////////////////////////////
-// Tests
-////////////////////////////////////////////////////////////////////////////////
-#[cfg(test)]
-#[cfg(target_os = "linux")] // Only run network tests on Linux (CI stability)
-mod tests {
-    use std::{ffi::CString, net::TcpListener};
+impl SocketCounters {
+    pub(crate) fn record_sent(&self, bytes: usize, frames: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.frames_sent.fetch_add(frames as u64, Ordering::Relaxed);
+        self.last_send_ms.store(self.now_ms(), Ordering::Release);
+    }
 
-    use pyo3::prepare_freethreaded_python;
-    use tokio::{
-        io::{AsyncReadExt, AsyncWriteExt},
-        net::TcpStream,
-        task,
-        time::{sleep, Duration},
-    };
+    fn record_received_bytes(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.last_recv_ms.store(self.now_ms(), Ordering::Release);
+    }
 
-    use super::*;
+    /// Returns the time elapsed since the last frame was sent, if any.
+    pub(crate) fn since_last_send(&self) -> Option<Duration> {
+        self.since(&self.last_send_ms)
+    }
 
-    fn create_handler() -> PyObject {
-        let code_raw = r#"
-class Counter:
-    def __init__(self):
-        self.count = 0
-        self.check = False
+    /// Returns the time elapsed since the last bytes were received, if any.
+    pub(crate) fn since_last_recv(&self) -> Option<Duration> {
+        self.since(&self.last_recv_ms)
+    }
 
-    def handler(self, bytes):
-        msg = bytes.decode()
-        if msg == 'ping':
-            self.count += 1
-        elif msg == 'heartbeat message':
-            self.check = True
+    fn since(&self, timestamp_ms: &AtomicU64) -> Option<Duration> {
+        match timestamp_ms.load(Ordering::Acquire) {
+            NO_ACTIVITY => None,
+            ms => Some(Duration::from_millis(self.now_ms().saturating_sub(ms))),
+        }
+    }
 
-    def get_check(self):
-        return self.check
+    fn now_ms(&self) -> u64 {
+        u64::try_from(self.epoch.elapsed().as_millis()).unwrap_or(NO_ACTIVITY - 1)
+    }
 
-    def get_count(self):
-        return self.count
+    fn record_received_frames(&self, frames: u64) {
+        self.frames_received.fetch_add(frames, Ordering::Relaxed);
+    }
 
-counter = Counter()
-"#;
-        let code = CString::new(code_raw).unwrap();
-        let filename = CString::new("test".to_string()).unwrap();
-        let module = CString::new("test".to_string()).unwrap();
-        Python::with_gil(|py| {
-            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
-            let counter = pymod.getattr("counter").unwrap().into_py(py);
-            let handler = counter.getattr(py, "handler").unwrap().into_py(py);
-            handler
-        })
+    fn record_reconnect(&self) {
+        self.reconnect_count.fetch_add(1, Ordering::Relaxed);
+        self.record_connected();
     }
 
-    fn bind_test_server() -> (u16, TcpListener) {
-        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind ephemeral port");
-        let port = listener.local_addr().unwrap().port();
-        (port, listener)
+    /// Records that the current connection was established.
+    fn record_connected(&self) {
+        self.connected_ms.store(self.now_ms(), Ordering::Release);
     }
 
-    async fn run_echo_server(mut socket: TcpStream) {
-        let mut buf = Vec::new();
-        loop {
-            match socket.read_buf(&mut buf).await {
-                Ok(0) => {
-                    break;
-                }
-                Ok(_n) => {
-                    while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
-                        let mut line = buf.drain(..idx + 2).collect::<Vec<u8>>();
-                        // Remove trailing \r\n
-                        line.truncate(line.len() - 2);
+    /// Returns the instant the current connection was established, if any.
+    fn connected_since(&self) -> Option<Instant> {
+        match self.connected_ms.load(Ordering::Acquire) {
+            NO_ACTIVITY => None,
+            ms => Some(self.epoch + Duration::from_millis(ms)),
+        }
+    }
+
+    fn record_circuit_state(&self, state: CircuitState) {
+        self.circuit_state.store(state as u8, Ordering::Release);
+    }
+
+    fn circuit_state(&self) -> CircuitState {
+        CircuitState::from(self.circuit_state.load(Ordering::Acquire))
+    }
+
+    fn record_handler_dropped(&self, frames: usize) {
+        self.handler_frames_dropped
+            .fetch_add(frames as u64, Ordering::Relaxed);
+    }
+
+    fn record_handler_queue_depth(&self, depth: usize) {
+        self.handler_queue_max_depth
+            .fetch_max(depth as u64, Ordering::Relaxed);
+    }
+
+    /// Records the duration of a handler call.
+    ///
+    /// Concurrent handler workers may record at the same time, so the moving average is
+    /// updated with a compare-and-swap loop to not lose samples.
+    fn record_handler_latency(&self, elapsed: Duration) {
+        let sample = u64::try_from(elapsed.as_micros()).unwrap_or(NO_ACTIVITY - 1);
+        let _ =
+            self.handler_latency_avg_us
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |avg| {
+                    Some(match avg {
+                        NO_ACTIVITY => sample,
+                        avg => {
+                            avg - avg / HANDLER_LATENCY_EWMA_WEIGHT
+                                + sample / HANDLER_LATENCY_EWMA_WEIGHT
+                        }
+                    })
+                });
+        self.handler_latency_max_us
+            .fetch_max(sample, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> SocketStats {
+        SocketStats {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            frames_sent: self.frames_sent.load(Ordering::Relaxed),
+            frames_received: self.frames_received.load(Ordering::Relaxed),
+            reconnect_count: self.reconnect_count.load(Ordering::Relaxed),
+            handler_frames_dropped: self.handler_frames_dropped.load(Ordering::Relaxed),
+            handler_queue_depth: 0,
+            handler_queue_max_depth: self.handler_queue_max_depth.load(Ordering::Relaxed),
+            handler_latency_avg_us: match self.handler_latency_avg_us.load(Ordering::Relaxed) {
+                NO_ACTIVITY => 0,
+                avg => avg,
+            },
+            handler_latency_max_us: self.handler_latency_max_us.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Flow control flag shared by the client and its read task.
+///
+/// While paused the read task stops reading from the socket, so the kernel receive
+/// window fills and the peer is slowed down by TCP backpressure.
+#[derive(Debug, Default)]
+pub(crate) struct ReadPause {
+    paused: AtomicBool,
+    resumed: Notify,
+}
+
+impl ReadPause {
+    pub(crate) fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        self.resumed.notify_waiters();
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Waits until reading is not paused.
+    pub(crate) async fn wait_resumed(&self) {
+        loop {
+            // Register interest before checking the flag so a resume
+            // between the check and the await is not missed
+            let notified = self.resumed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if !self.is_paused() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Read buffer kept by the client and reused by the read task of each connection.
+///
+/// Reconnect-heavy feeds would otherwise allocate, and grow, a fresh buffer for every
+/// connection. The buffer is emptied before it is reused, so no bytes received on one
+/// connection are decoded on the next.
+#[derive(Debug)]
+struct ReadBufferPool {
+    capacity: usize,
+    buf: std::sync::Mutex<Option<BytesMut>>,
+    allocations: AtomicUsize,
+}
+
+impl ReadBufferPool {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            buf: std::sync::Mutex::new(None),
+            allocations: AtomicUsize::new(0),
+        }
+    }
+
+    /// Takes the pooled buffer, allocating one if none is pooled.
+    fn take(&self) -> BytesMut {
+        if let Some(buf) = self.buf.lock().unwrap().take() {
+            return buf;
+        }
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        BytesMut::with_capacity(self.capacity)
+    }
+
+    /// Returns an emptied buffer to the pool.
+    fn put(&self, mut buf: BytesMut) {
+        buf.clear();
+        *self.buf.lock().unwrap() = Some(buf);
+    }
+
+    /// Returns the number of buffers allocated because none was pooled.
+    #[cfg(test)]
+    fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Relaxed)
+    }
+}
+
+/// Frame decoder which returns its read buffer to the pool when dropped.
+///
+/// Dropping covers both a read task which ends and one which is aborted.
+struct PooledDecoder {
+    decoder: FrameDecoder,
+    pool: Arc<ReadBufferPool>,
+}
+
+impl std::ops::Deref for PooledDecoder {
+    type Target = FrameDecoder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.decoder
+    }
+}
+
+impl std::ops::DerefMut for PooledDecoder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.decoder
+    }
+}
+
+impl Drop for PooledDecoder {
+    fn drop(&mut self) {
+        self.pool.put(self.decoder.take_buffer());
+    }
+}
+
+/// Policy applied when a frame is sent while the reconnect queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub enum QueueOverflowPolicy {
+    /// Reject the frame with an error.
+    #[default]
+    Error,
+    /// Drop the oldest queued frame to make room.
+    DropOldest,
+    /// Wait until the queue has been flushed after reconnecting.
+    Block,
+}
+
+/// Bounded queue of encoded frames sent while the client is reconnecting.
+///
+/// The queue is flushed in order to the new writer task before the client becomes
+/// active again, so frames queued during a reconnect are never overtaken.
+#[derive(Debug)]
+pub(crate) struct ReconnectQueue {
+    frames: Mutex<VecDeque<Vec<u8>>>,
+    capacity: usize,
+    policy: QueueOverflowPolicy,
+    flushed: Notify,
+}
+
+impl ReconnectQueue {
+    pub(crate) fn new(capacity: usize, policy: QueueOverflowPolicy) -> Self {
+        Self {
+            frames: Mutex::new(VecDeque::new()),
+            capacity,
+            policy,
+            flushed: Notify::new(),
+        }
+    }
+
+    /// Queues `frame` if the client is reconnecting, otherwise returns it to be sent now.
+    pub(crate) async fn push_if_reconnecting(
+        &self,
+        connection_state: &ConnectionStateCell,
+        frame: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, std::io::Error> {
+        loop {
+            let mut frames = self.frames.lock().await;
+            if connection_state.load() != ConnectionState::Reconnecting {
+                return Ok(Some(frame));
+            }
+            if frames.len() < self.capacity {
+                frames.push_back(frame);
+                return Ok(None);
+            }
+
+            match self.policy {
+                QueueOverflowPolicy::Error => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::WouldBlock,
+                        "Reconnect queue is full",
+                    ));
+                }
+                QueueOverflowPolicy::DropOldest => {
+                    tracing::warn!("Reconnect queue full, dropping oldest frame");
+                    frames.pop_front();
+                    frames.push_back(frame);
+                    return Ok(None);
+                }
+                QueueOverflowPolicy::Block => {
+                    let notified = self.flushed.notified();
+                    tokio::pin!(notified);
+                    notified.as_mut().enable();
+                    drop(frames);
+                    notified.await;
+                }
+            }
+        }
+    }
+
+    /// Passes all queued frames in order to the writer task, then marks the connection active.
+    pub(crate) async fn flush(
+        &self,
+        writer_tx: &FrameSender,
+        connection_state: &ConnectionStateCell,
+    ) -> Result<(), std::io::Error> {
+        let mut frames = self.frames.lock().await;
+        if !frames.is_empty() {
+            tracing::debug!("Replaying {} queued frame(s)", frames.len());
+            while let Some(frame) = frames.pop_front() {
+                writer_tx.send(frame).await.map_err(|_| writer_closed())?;
+            }
+        }
+        connection_state.store_unless_closing(ConnectionState::Active);
+        drop(frames);
+
+        self.flushed.notify_waiters();
+        Ok(())
+    }
+
+    /// Waits until a reconnect in progress has passed the queued frames to the writer task.
+    pub(crate) async fn wait_flushed(&self, connection_state: &ConnectionStateCell) {
+        loop {
+            let frames = self.frames.lock().await;
+            if connection_state.load() != ConnectionState::Reconnecting {
+                return;
+            }
+            let notified = self.flushed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            drop(frames);
+            notified.await;
+        }
+    }
+
+    /// Discards any queued frames and releases blocked senders.
+    pub(crate) async fn clear(&self) {
+        let mut frames = self.frames.lock().await;
+        if !frames.is_empty() {
+            tracing::warn!("Discarding {} queued frame(s)", frames.len());
+            frames.clear();
+        }
+        drop(frames);
+
+        self.flushed.notify_waiters();
+    }
+}
+
+/// Policy applied when a frame is received while the handler queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(eq, eq_int, module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub enum HandlerQueuePolicy {
+    /// Stop reading until the worker makes room, applying TCP backpressure.
+    #[default]
+    Block,
+    /// Drop the frame and count it in [`SocketStats::handler_frames_dropped`].
+    Drop,
+}
+
+enum HandlerItem {
+    Frame(Bytes, UnixNanos, Option<FramePosition>),
+    Batch(Vec<Bytes>, UnixNanos),
+    /// One per worker, each waiting on the barrier so the reset runs while no frame is handled.
+    Reset(Arc<std::sync::Barrier>),
+}
+
+thread_local! {
+    /// The error reported by the handler call in progress on this thread.
+    static HANDLER_ERROR: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+/// Reports that the [`MessageHandler`] call in progress on this thread failed.
+///
+/// Handler methods cannot return errors, so a handler calls this before returning to
+/// have the error passed to `on_handler_error` and counted towards `max_handler_errors`.
+/// Reports made outside of a handler call are discarded.
+pub fn report_handler_error(error: impl std::fmt::Display) {
+    HANDLER_ERROR.set(Some(error.to_string()));
+}
+
+/// Counts consecutive handler errors for the `max_handler_errors` policy.
+pub(crate) struct HandlerErrors {
+    max_consecutive: Option<u32>,
+    callback: Option<HandlerErrorCallback>,
+    consecutive: AtomicU32,
+}
+
+impl HandlerErrors {
+    fn new(config: &SocketConfig) -> Option<Arc<Self>> {
+        if config.max_handler_errors.is_none() && config.on_handler_error.is_none() {
+            return None;
+        }
+        Some(Arc::new(Self {
+            max_consecutive: config.max_handler_errors,
+            callback: config.on_handler_error.clone(),
+            consecutive: AtomicU32::new(0),
+        }))
+    }
+
+    /// Records the outcome of a handler call, a success resetting the count.
+    fn record(&self, error: Option<String>) {
+        match error {
+            Some(error) => {
+                if let Some(ref callback) = self.callback {
+                    callback(&error);
+                }
+                self.consecutive.fetch_add(1, Ordering::AcqRel);
+            }
+            None => self.consecutive.store(0, Ordering::Release),
+        }
+    }
+
+    /// Returns whether the handler has failed `max_handler_errors` times in a row.
+    fn exceeded(&self) -> bool {
+        self.max_consecutive
+            .is_some_and(|max| self.consecutive.load(Ordering::Acquire) >= max)
+    }
+
+    fn clear(&self) {
+        self.consecutive.store(0, Ordering::Release);
+    }
+}
+
+/// A handler together with whether it receives message timestamps.
+#[derive(Clone)]
+pub(crate) struct HandlerTarget {
+    handler: Arc<dyn MessageHandler>,
+    receive_ts: bool,
+    errors: Option<Arc<HandlerErrors>>,
+}
+
+impl HandlerTarget {
+    fn new(config: &SocketConfig) -> Self {
+        Self {
+            handler: config.handler.clone(),
+            receive_ts: config.handler_receive_ts,
+            errors: HandlerErrors::new(config),
+        }
+    }
+
+    fn handle(&self, data: Bytes, ts_recv: UnixNanos, position: Option<FramePosition>) {
+        HANDLER_ERROR.set(None);
+        if let Some(position) = position {
+            self.handler.handle_with_position(&data, position);
+        } else if self.receive_ts {
+            self.handler.handle_bytes_with_ts(data, ts_recv);
+        } else {
+            self.handler.handle_bytes(data);
+        }
+        self.record_outcome();
+    }
+
+    fn handle_batch(&self, frames: &[Bytes], ts_recv: UnixNanos) {
+        HANDLER_ERROR.set(None);
+        if self.receive_ts {
+            self.handler.handle_batch_with_ts(frames, ts_recv);
+        } else {
+            self.handler.handle_batch(frames);
+        }
+        self.record_outcome();
+    }
+
+    /// Records the error reported during the call, if any.
+    fn record_outcome(&self) {
+        let error = HANDLER_ERROR.take();
+        if let Some(ref errors) = self.errors {
+            errors.record(error);
+        }
+    }
+}
+
+/// Edge-triggered high-water and low-water marks on the handler queue depth.
+struct Backpressure {
+    high_water: usize,
+    low_water: usize,
+    active: AtomicBool,
+    callback: BackpressureCallback,
+}
+
+impl Backpressure {
+    fn new(config: &SocketConfig) -> Option<Arc<Self>> {
+        let high_water = config.handler_queue_high_water?;
+        let callback = config.on_backpressure.clone()?;
+        Some(Arc::new(Self {
+            high_water,
+            low_water: config.handler_queue_low_water.unwrap_or(high_water / 2),
+            active: AtomicBool::new(false),
+            callback,
+        }))
+    }
+
+    /// Calls the callback if `depth` crosses a mark since the last call.
+    fn update(&self, depth: usize) {
+        let crossed = if depth >= self.high_water {
+            self.active
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        } else if depth <= self.low_water {
+            self.active
+                .compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        } else {
+            false
+        };
+        if crossed {
+            let active = depth >= self.high_water;
+            tracing::debug!("Handler queue backpressure {active} at depth {depth}");
+            (self.callback)(depth, active);
+        }
+    }
+}
+
+/// Bounded queue between the read task and the workers calling the handler.
+///
+/// Each worker runs on a blocking thread so a slow handler does not stall the
+/// runtime, and they exit once the queue is drained after the client is dropped.
+/// A single worker handles items in the order they were received. With several
+/// workers, items are taken in order but handled concurrently, so they may complete
+/// in any order. A reset is a barrier across all workers, which runs once every frame
+/// of the previous connection is handled and before any frame of the new one.
+pub(crate) struct HandlerQueue {
+    tx: mpsc::Sender<HandlerItem>,
+    workers: usize,
+    policy: HandlerQueuePolicy,
+    counters: Arc<SocketCounters>,
+    backpressure: Option<Arc<Backpressure>>,
+    errors: Option<Arc<HandlerErrors>>,
+}
+
+impl HandlerQueue {
+    fn spawn(
+        handler: HandlerTarget,
+        capacity: usize,
+        workers: usize,
+        policy: HandlerQueuePolicy,
+        counters: Arc<SocketCounters>,
+        backpressure: Option<Arc<Backpressure>>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(capacity);
+        let errors = handler.errors.clone();
+        // Workers take turns receiving, so each item is handled by exactly one of them
+        let rx = Arc::new(std::sync::Mutex::new(rx));
+        for worker in 0..workers {
+            let rx = rx.clone();
+            let handler = handler.clone();
+            let worker_counters = counters.clone();
+            let worker_backpressure = backpressure.clone();
+            let span = tracing::Span::current();
+            tokio::task::spawn_blocking(move || {
+                let _entered = span.enter();
+                tracing::debug!("Started task 'handler' {worker}");
+                loop {
+                    let item = {
+                        let mut rx = rx.lock().unwrap();
+                        let item = rx.blocking_recv();
+                        if let Some(ref backpressure) = worker_backpressure {
+                            backpressure.update(rx.len());
+                        }
+                        item
+                    };
+                    let Some(item) = item else {
+                        break;
+                    };
+                    let start = Instant::now();
+                    match item {
+                        HandlerItem::Frame(data, ts_recv, position) => {
+                            handler.handle(data, ts_recv, position);
+                        }
+                        HandlerItem::Batch(frames, ts_recv) => {
+                            handler.handle_batch(&frames, ts_recv);
+                        }
+                        HandlerItem::Reset(barrier) => {
+                            // A worker holding a reset takes nothing else until all hold one
+                            if barrier.wait().is_leader() {
+                                handler.handler.reset();
+                            }
+                            barrier.wait();
+                            continue;
+                        }
+                    }
+                    worker_counters.record_handler_latency(start.elapsed());
+                }
+                tracing::debug!("Completed task 'handler' {worker}");
+            });
+        }
+
+        Self {
+            tx,
+            workers,
+            policy,
+            counters,
+            backpressure,
+            errors,
+        }
+    }
+
+    /// Returns the number of items waiting for the worker.
+    pub(crate) fn depth(&self) -> usize {
+        self.tx.max_capacity() - self.tx.capacity()
+    }
+
+    /// Queues a reset for every worker, never dropped so no frame of a new connection
+    /// is handled before it.
+    async fn push_reset(&self) {
+        let barrier = Arc::new(std::sync::Barrier::new(self.workers));
+        for _ in 0..self.workers {
+            if self
+                .tx
+                .send(HandlerItem::Reset(barrier.clone()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    async fn push(&self, item: HandlerItem) {
+        let frames = match &item {
+            HandlerItem::Frame(..) => 1,
+            HandlerItem::Batch(frames, _) => frames.len(),
+            HandlerItem::Reset(_) => 0,
+        };
+        let result = match self.policy {
+            HandlerQueuePolicy::Block => self.tx.send(item).await.map_err(|_| ()),
+            HandlerQueuePolicy::Drop => match self.tx.try_send(item) {
+                Ok(()) => Ok(()),
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    tracing::debug!("Handler queue full, dropping {frames} frame(s)");
+                    self.counters.record_handler_dropped(frames);
+                    Ok(())
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => Err(()),
+            },
+        };
+        if result.is_err() {
+            tracing::error!("Handler worker has stopped, dropping {frames} frame(s)");
+            self.counters.record_handler_dropped(frames);
+        }
+        let depth = self.depth();
+        self.counters.record_handler_queue_depth(depth);
+        if let Some(ref backpressure) = self.backpressure {
+            backpressure.update(depth);
+        }
+    }
+}
+
+/// Delivers decoded frames to the handler, either inline or through a [`HandlerQueue`].
+#[derive(Clone)]
+pub(crate) enum HandlerDispatch {
+    Inline(HandlerTarget, Arc<SocketCounters>),
+    Queued(Arc<HandlerQueue>),
+}
+
+impl HandlerDispatch {
+    fn new(config: &SocketConfig, counters: &Arc<SocketCounters>) -> Self {
+        match config.handler_queue_capacity {
+            Some(capacity) => Self::Queued(Arc::new(HandlerQueue::spawn(
+                HandlerTarget::new(config),
+                capacity,
+                config.handler_concurrency,
+                config.handler_queue_overflow,
+                counters.clone(),
+                Backpressure::new(config),
+            ))),
+            None => Self::Inline(HandlerTarget::new(config), counters.clone()),
+        }
+    }
+
+    fn queue(&self) -> Option<Arc<HandlerQueue>> {
+        match self {
+            Self::Inline(..) => None,
+            Self::Queued(queue) => Some(queue.clone()),
+        }
+    }
+
+    fn errors(&self) -> Option<Arc<HandlerErrors>> {
+        match self {
+            Self::Inline(handler, _) => handler.errors.clone(),
+            Self::Queued(queue) => queue.errors.clone(),
+        }
+    }
+
+    async fn handle(&self, data: Bytes, ts_recv: UnixNanos, position: Option<FramePosition>) {
+        match self {
+            Self::Inline(handler, counters) => {
+                let start = Instant::now();
+                handler.handle(data, ts_recv, position);
+                counters.record_handler_latency(start.elapsed());
+            }
+            Self::Queued(queue) => {
+                queue
+                    .push(HandlerItem::Frame(data, ts_recv, position))
+                    .await;
+            }
+        }
+    }
+
+    async fn reset(&self) {
+        match self {
+            Self::Inline(handler, _) => handler.handler.reset(),
+            Self::Queued(queue) => queue.push_reset().await,
+        }
+    }
+
+    async fn handle_batch(&self, frames: Vec<Bytes>, ts_recv: UnixNanos) {
+        match self {
+            Self::Inline(handler, counters) => {
+                let start = Instant::now();
+                handler.handle_batch(&frames, ts_recv);
+                counters.record_handler_latency(start.elapsed());
+            }
+            Self::Queued(queue) => queue.push(HandlerItem::Batch(frames, ts_recv)).await,
+        }
+    }
+}
+
+/// Configuration for TCP socket connection.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub struct SocketConfig {
+    /// The URL to connect to, either `host:port` or `unix://<path>` for a Unix domain socket.
+    pub url: String,
+    /// The connection mode {Plain, TLS}.
+    pub mode: Mode,
+    /// The framing scheme which separates messages on the byte stream, also used to
+    /// encode sent messages unless a `send_framing` is set.
+    pub framing: Framing,
+    /// The handler for incoming messages.
+    pub handler: Arc<dyn MessageHandler>,
+    /// The optional heartbeat with period and beat message.
+    pub heartbeat: Option<(Duration, Vec<u8>)>,
+    /// The timeout (seconds) for reconnects.
+    pub reconnect_timeout_secs: Option<u64>,
+    /// The maximum reconnection attempts before closing the client.
+    pub max_reconnection_tries: Option<u64>,
+    /// The backoff policy applied between failed reconnection attempts.
+    pub backoff: BackoffPolicy,
+    /// The interval (milliseconds) at which the controller checks the connection (default 10ms).
+    pub controller_check_interval_ms: Option<u64>,
+    /// The optional maximum number of messages delivered to the handler in a single batch.
+    pub handler_batch_size: Option<usize>,
+    /// The optional maximum size (bytes) of a received message, excluding any suffix or header.
+    pub max_message_size: Option<usize>,
+    /// The optional callback invoked before the connection is dropped for exceeding `max_message_size`.
+    pub on_overflow: Option<OverflowCallback>,
+    /// The optional capacity of the queue to a dedicated handler worker, otherwise the handler is called by the read task.
+    pub handler_queue_capacity: Option<usize>,
+    /// The policy applied when a frame is received while the handler queue is full.
+    pub handler_queue_overflow: HandlerQueuePolicy,
+    /// The optional capacity of the queue holding frames sent while reconnecting.
+    pub reconnect_queue_capacity: Option<usize>,
+    /// The policy applied when sending while the reconnect queue is full.
+    pub reconnect_queue_overflow: QueueOverflowPolicy,
+    /// The optional maximum bytes coalesced from queued frames into a single write.
+    pub max_coalesce_bytes: Option<usize>,
+    /// The optional maximum number of queued frames coalesced into a single write.
+    pub max_coalesce_frames: Option<usize>,
+    /// The optional connector used to dial connections, otherwise TCP with optional TLS to `url`.
+    pub connector: Option<SocketConnector>,
+    /// If any bytes left unterminated when the server closes are delivered as a final message.
+    pub deliver_unterminated_on_eof: bool,
+    /// The optional timeout (seconds) without received data after which the connection is dropped and reconnected.
+    pub read_idle_timeout_secs: Option<u64>,
+    /// The optional timeout (seconds) without a heartbeat response after which the connection is dropped and reconnected.
+    pub heartbeat_timeout_secs: Option<u64>,
+    /// The optional predicate identifying heartbeat responses, otherwise any received message counts as one.
+    pub heartbeat_response: Option<HeartbeatResponse>,
+    /// If heartbeats are sent on every interval, otherwise only when nothing was sent within the interval.
+    pub heartbeat_always_send: bool,
+    /// The options applied to the TCP socket of each connection.
+    pub tcp_options: TcpOptions,
+    /// The optional timeout (seconds) for establishing the TCP connection.
+    pub connect_timeout_secs: Option<u64>,
+    /// The optional PEM-encoded client certificate chain presented for mutual TLS.
+    pub client_cert_pem: Option<Vec<u8>>,
+    /// The optional PEM-encoded private key of the client certificate.
+    pub client_key_pem: Option<Vec<u8>>,
+    /// The optional PEM-encoded CA certificates trusted in addition to the platform roots.
+    pub root_ca_pem: Option<Vec<u8>>,
+    /// If server certificates are accepted without verification, strictly for test environments.
+    pub danger_accept_invalid_certs: bool,
+    /// The optional server name sent during the TLS handshake in place of the URL host.
+    pub tls_sni: Option<String>,
+    /// The optional proxy through which connections are tunneled.
+    pub proxy: Option<ProxyConfig>,
+    /// The fallback URLs tried in order, wrapping around, when the current URL cannot be reached.
+    pub fallback_urls: Vec<String>,
+    /// The optional provider of the endpoint to dial, called before every connection attempt in place of `url`.
+    pub url_provider: Option<UrlProvider>,
+    /// The optional messages sent, in order, on every new connection before it becomes active.
+    pub on_connect_send: Option<Vec<Vec<u8>>>,
+    /// The maximum time a send waits for the client to become active (default 2000ms), zero fails immediately.
+    pub send_wait_active_timeout_ms: Option<u64>,
+    /// The maximum time (milliseconds) shutdown waits for the read task to observe EOF (default 100ms), zero skips the wait.
+    pub shutdown_linger_ms: Option<u64>,
+    /// If the client reconnects after the server closes the connection cleanly, otherwise it closes (default true).
+    pub reconnect_on_eof: bool,
+    /// The optional predicate classifying reconnect errors as fatal, otherwise [`SocketError::is_fatal`] is used.
+    pub is_fatal_error: Option<FatalErrorClassifier>,
+    /// The optional callback invoked when the client closes on a fatal reconnect error.
+    pub on_fatal: Option<FatalCallback>,
+    /// The optional callback invoked with the last error and attempt count when `max_reconnection_tries` is exhausted.
+    pub on_give_up: Option<GiveUpCallback>,
+    /// The optional maximum time (seconds) spent reconnecting after a disconnect before the client closes.
+    pub max_reconnect_duration_secs: Option<u64>,
+    /// The optional framing used to encode sent messages and heartbeats, otherwise `framing` is used.
+    pub send_framing: Option<Framing>,
+    /// If the handler is called with the receive timestamp of each message, see [`MessageHandler::handle_with_ts`].
+    pub handler_receive_ts: bool,
+    /// The optional handler queue depth at or above which `on_backpressure` is called.
+    pub handler_queue_high_water: Option<usize>,
+    /// The optional handler queue depth at or below which backpressure clears (defaults to half the high-water mark).
+    pub handler_queue_low_water: Option<usize>,
+    /// The optional callback invoked when the handler queue crosses its high-water or low-water mark.
+    pub on_backpressure: Option<BackpressureCallback>,
+    /// If frames still queued for the writer are written before the connection closes.
+    pub drain_on_close: bool,
+    /// The optional token which, once cancelled, aborts connecting and closes the client.
+    pub cancellation_token: Option<CancellationToken>,
+    /// If the writer is flushed after every write, otherwise only on [`SocketClient::flush`] and close.
+    pub auto_flush: bool,
+    /// The optional identifier of the client attached to its task spans, a unique one is generated when `None`.
+    pub client_id: Option<String>,
+    /// The optional maximum time (seconds) shutdown waits for the writer to close, and close waits for the client to shut down (default 5).
+    ///
+    /// With `drain_on_close` up to half of it is spent writing queued frames, leaving the rest to close the writer.
+    pub shutdown_timeout_secs: Option<u64>,
+    /// The optional rate limit applied to sent frames, heartbeats are not limited.
+    pub send_rate_limit: Option<Quota>,
+    /// The optional hook transforming the payload of each sent message before it is framed.
+    pub on_send: Option<SendTransform>,
+    /// The optional hook transforming each received message before it is passed to the handler.
+    pub on_recv: Option<RecvTransform>,
+    /// The optional callback invoked with a received message skipped because the `on_recv` hook failed.
+    pub on_recv_error: Option<RecvErrorCallback>,
+    /// The optional callback producing each heartbeat payload instead of the static `heartbeat` message.
+    pub heartbeat_payload: Option<HeartbeatPayload>,
+    /// If heartbeats are framed like data messages, otherwise the payload is sent verbatim without the suffix.
+    pub heartbeat_append_suffix: bool,
+    /// The optional callback invoked after the initial connect and each reconnect.
+    pub on_established: Option<EstablishedCallback>,
+    /// The optional resolver of the dialed address, otherwise the system resolver is used.
+    pub resolver: Option<Resolver>,
+    /// The optional local address connections are made from, otherwise chosen by the OS.
+    pub bind_address: Option<SocketAddr>,
+    /// The optional timeout (seconds) for completing the TLS handshake once the TCP connection is established.
+    pub tls_handshake_timeout_secs: Option<u64>,
+    /// The protocols offered with ALPN during the TLS handshake, in order of preference.
+    pub alpn_protocols: Vec<Vec<u8>>,
+    /// The optional SHA-256 fingerprints of pinned server certificates, connections are refused unless the leaf certificate matches one.
+    pub pinned_cert_sha256: Option<Vec<[u8; 32]>>,
+    /// The optional capacity (bytes) of the read buffer reused across connections (default and minimum 8 KiB).
+    pub read_buffer_capacity: Option<usize>,
+    /// The optional number of messages delivered between yields of the read task, letting other tasks and Python threads run under sustained load.
+    pub handler_yield_every: Option<usize>,
+    /// The optional circuit breaker suspending reconnection attempts after repeated failures.
+    pub circuit_breaker: Option<CircuitBreakerPolicy>,
+    /// The optional callback invoked with the attempt number before each reconnect attempt.
+    pub on_reconnect_attempt: Option<ReconnectAttemptCallback>,
+    /// If the handler is called with the sequence number and byte offset of each message on its connection.
+    pub handler_receive_position: bool,
+    /// The number of workers calling the handler from the handler queue, above 1 messages are handled concurrently and may complete out of order (default 1), which is not allowed with `Framing::Raw`.
+    pub handler_concurrency: usize,
+    /// The optional messages sent, in order, after the `on_connect_send` frames on every reconnection before it becomes active.
+    pub on_reconnect_send: Option<Vec<Vec<u8>>>,
+    /// The optional number of consecutive handler errors after which the connection is dropped, see [`report_handler_error`].
+    pub max_handler_errors: Option<u32>,
+    /// The optional callback invoked with each error reported by the handler.
+    pub on_handler_error: Option<HandlerErrorCallback>,
+}
+
+impl std::fmt::Debug for SocketConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(SocketConfig))
+            .field("url", &self.url)
+            .field("mode", &self.mode)
+            .field("framing", &self.framing)
+            .field("handler", &self.handler)
+            .field("heartbeat", &self.heartbeat)
+            .field("reconnect_timeout_secs", &self.reconnect_timeout_secs)
+            .field("max_reconnection_tries", &self.max_reconnection_tries)
+            .field("backoff", &self.backoff)
+            .field(
+                "controller_check_interval_ms",
+                &self.controller_check_interval_ms,
+            )
+            .field("handler_batch_size", &self.handler_batch_size)
+            .field("max_message_size", &self.max_message_size)
+            .field("handler_queue_capacity", &self.handler_queue_capacity)
+            .field("handler_queue_overflow", &self.handler_queue_overflow)
+            .field("reconnect_queue_capacity", &self.reconnect_queue_capacity)
+            .field("reconnect_queue_overflow", &self.reconnect_queue_overflow)
+            .field("max_coalesce_bytes", &self.max_coalesce_bytes)
+            .field("max_coalesce_frames", &self.max_coalesce_frames)
+            .field(
+                "deliver_unterminated_on_eof",
+                &self.deliver_unterminated_on_eof,
+            )
+            .field("read_idle_timeout_secs", &self.read_idle_timeout_secs)
+            .field("heartbeat_timeout_secs", &self.heartbeat_timeout_secs)
+            .field("heartbeat_always_send", &self.heartbeat_always_send)
+            .field("tcp_options", &self.tcp_options)
+            .field("connect_timeout_secs", &self.connect_timeout_secs)
+            .field(
+                "danger_accept_invalid_certs",
+                &self.danger_accept_invalid_certs,
+            )
+            .field("tls_sni", &self.tls_sni)
+            .field("proxy", &self.proxy)
+            .field("fallback_urls", &self.fallback_urls)
+            .field("on_connect_send", &self.on_connect_send)
+            .field(
+                "send_wait_active_timeout_ms",
+                &self.send_wait_active_timeout_ms,
+            )
+            .field("shutdown_linger_ms", &self.shutdown_linger_ms)
+            .field("reconnect_on_eof", &self.reconnect_on_eof)
+            .field(
+                "max_reconnect_duration_secs",
+                &self.max_reconnect_duration_secs,
+            )
+            .field("send_framing", &self.send_framing)
+            .field("handler_receive_ts", &self.handler_receive_ts)
+            .field("handler_queue_high_water", &self.handler_queue_high_water)
+            .field("handler_queue_low_water", &self.handler_queue_low_water)
+            .field("drain_on_close", &self.drain_on_close)
+            .field("cancellation_token", &self.cancellation_token)
+            .field("auto_flush", &self.auto_flush)
+            .field("client_id", &self.client_id)
+            .field("shutdown_timeout_secs", &self.shutdown_timeout_secs)
+            .field("send_rate_limit", &self.send_rate_limit)
+            .field("heartbeat_append_suffix", &self.heartbeat_append_suffix)
+            .field("bind_address", &self.bind_address)
+            .field(
+                "tls_handshake_timeout_secs",
+                &self.tls_handshake_timeout_secs,
+            )
+            .field("alpn_protocols", &self.alpn_protocols)
+            .field("pinned_cert_sha256", &self.pinned_cert_sha256)
+            .field("read_buffer_capacity", &self.read_buffer_capacity)
+            .field("handler_yield_every", &self.handler_yield_every)
+            .field("circuit_breaker", &self.circuit_breaker)
+            .field("handler_receive_position", &self.handler_receive_position)
+            .field("handler_concurrency", &self.handler_concurrency)
+            .field("on_reconnect_send", &self.on_reconnect_send)
+            .field("max_handler_errors", &self.max_handler_errors)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SocketConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is empty, if any interval or delay is zero, which would
+    /// spin the controller loop, if the backoff policy is out of range, or if the framing
+    /// is invalid.
+    pub fn validate(&self) -> Result<(), std::io::Error> {
+        if self.url.is_empty() && self.connector.is_none() && self.url_provider.is_none() {
+            return Err(invalid_config("`url` must not be empty"));
+        }
+        for framing in std::iter::once(&self.framing).chain(&self.send_framing) {
+            if matches!(framing, Framing::Delimiter(suffix) if suffix.is_empty()) {
+                return Err(invalid_config("`framing` suffix must not be empty"));
+            }
+            if let Framing::LengthPrefixed { header_bytes, .. } = framing {
+                if !(1..=8).contains(header_bytes) {
+                    return Err(invalid_config(
+                        "`framing` header_bytes must be between 1 and 8",
+                    ));
+                }
+            }
+        }
+        if self.controller_check_interval_ms == Some(0) {
+            return Err(invalid_config(
+                "`controller_check_interval_ms` must be positive",
+            ));
+        }
+        if self.backoff.delay_initial.is_zero() {
+            return Err(invalid_config("`backoff.delay_initial` must be positive"));
+        }
+        if self.backoff.delay_max < self.backoff.delay_initial {
+            return Err(invalid_config(
+                "`backoff.delay_max` must not be below `backoff.delay_initial`",
+            ));
+        }
+        if !self.backoff.factor.is_finite() || self.backoff.factor < 1.0 {
+            return Err(invalid_config(
+                "`backoff.factor` must be finite and at least 1",
+            ));
+        }
+        if self
+            .backoff
+            .jitter
+            .is_some_and(|jitter| !(0.0..=1.0).contains(&jitter))
+        {
+            return Err(invalid_config("`backoff.jitter` must be between 0 and 1"));
+        }
+        if self.handler_batch_size == Some(0) {
+            return Err(invalid_config("`handler_batch_size` must be positive"));
+        }
+        if let Some(policy) = self.circuit_breaker {
+            if policy.failure_threshold == 0 {
+                return Err(invalid_config(
+                    "`circuit_breaker` failure threshold must be positive",
+                ));
+            }
+            if policy.window.is_zero() || policy.cooldown.is_zero() {
+                return Err(invalid_config(
+                    "`circuit_breaker` window and cooldown must be positive",
+                ));
+            }
+        }
+        if self.handler_receive_position && self.handler_batch_size.is_some() {
+            return Err(invalid_config(
+                "`handler_receive_position` cannot be combined with `handler_batch_size`",
+            ));
+        }
+        if self.handler_receive_position && self.handler_receive_ts {
+            return Err(invalid_config(
+                "`handler_receive_position` cannot be combined with `handler_receive_ts`",
+            ));
+        }
+        if self.max_handler_errors == Some(0) {
+            return Err(invalid_config("`max_handler_errors` must be positive"));
+        }
+        if self.handler_yield_every == Some(0) {
+            return Err(invalid_config("`handler_yield_every` must be positive"));
+        }
+        if self.handler_queue_capacity == Some(0) {
+            return Err(invalid_config("`handler_queue_capacity` must be positive"));
+        }
+        if self.handler_concurrency == 0 {
+            return Err(invalid_config("`handler_concurrency` must be positive"));
+        }
+        if self.handler_concurrency > 1 && self.handler_queue_capacity.is_none() {
+            return Err(invalid_config(
+                "`handler_concurrency` above 1 requires a `handler_queue_capacity`",
+            ));
+        }
+        if self.handler_concurrency > 1 && self.framing == Framing::Raw {
+            return Err(invalid_config(
+                "`handler_concurrency` above 1 cannot be combined with `Framing::Raw`",
+            ));
+        }
+        if let Some(high_water) = self.handler_queue_high_water {
+            if self
+                .handler_queue_capacity
+                .is_none_or(|capacity| high_water > capacity)
+            {
+                return Err(invalid_config(
+                    "`handler_queue_high_water` must not exceed `handler_queue_capacity`",
+                ));
+            }
+            if high_water == 0 {
+                return Err(invalid_config(
+                    "`handler_queue_high_water` must be positive",
+                ));
+            }
+            if self
+                .handler_queue_low_water
+                .is_some_and(|low| low >= high_water)
+            {
+                return Err(invalid_config(
+                    "`handler_queue_low_water` must be below `handler_queue_high_water`",
+                ));
+            }
+        } else if self.handler_queue_low_water.is_some() {
+            return Err(invalid_config(
+                "`handler_queue_low_water` requires a `handler_queue_high_water`",
+            ));
+        }
+        if self
+            .read_buffer_capacity
+            .is_some_and(|capacity| capacity < READ_CHUNK_SIZE)
+        {
+            return Err(invalid_config(&format!(
+                "`read_buffer_capacity` must be at least {READ_CHUNK_SIZE} bytes"
+            )));
+        }
+        if self.max_message_size == Some(0) {
+            return Err(invalid_config("`max_message_size` must be positive"));
+        }
+        if self.reconnect_queue_capacity == Some(0) {
+            return Err(invalid_config(
+                "`reconnect_queue_capacity` must be positive",
+            ));
+        }
+        if matches!(self.heartbeat, Some((interval, _)) if interval.is_zero()) {
+            return Err(invalid_config("`heartbeat` interval must be positive"));
+        }
+        if self.heartbeat_timeout_secs.is_some() && self.heartbeat.is_none() {
+            return Err(invalid_config(
+                "`heartbeat_timeout_secs` requires a `heartbeat`",
+            ));
+        }
+        if self.heartbeat_timeout_secs == Some(0) {
+            return Err(invalid_config("`heartbeat_timeout_secs` must be positive"));
+        }
+        if self.client_cert_pem.is_some() != self.client_key_pem.is_some() {
+            return Err(invalid_config(
+                "`client_cert_pem` and `client_key_pem` must be set together",
+            ));
+        }
+        if self
+            .pinned_cert_sha256
+            .as_ref()
+            .is_some_and(|pins| pins.is_empty())
+        {
+            return Err(invalid_config("`pinned_cert_sha256` must not be empty"));
+        }
+        if self.tcp_options.dscp.is_some_and(|dscp| dscp > 63) {
+            return Err(invalid_config("`tcp_options.dscp` must fit in 6 bits"));
+        }
+        if self.connect_timeout_secs == Some(0) {
+            return Err(invalid_config("`connect_timeout_secs` must be positive"));
+        }
+        if self.tls_handshake_timeout_secs == Some(0) {
+            return Err(invalid_config(
+                "`tls_handshake_timeout_secs` must be positive",
+            ));
+        }
+        if self.max_reconnect_duration_secs == Some(0) {
+            return Err(invalid_config(
+                "`max_reconnect_duration_secs` must be positive",
+            ));
+        }
+        for path in std::iter::once(&self.url)
+            .chain(&self.fallback_urls)
+            .filter_map(|url| unix_socket_path(url))
+        {
+            if cfg!(not(unix)) {
+                return Err(invalid_config(
+                    "Unix domain sockets are not supported on this platform",
+                ));
+            }
+            if path.is_empty() {
+                return Err(invalid_config("Unix socket `url` must include a path"));
+            }
+            if matches!(self.mode, Mode::Tls) {
+                return Err(invalid_config(
+                    "TLS is not supported over Unix domain sockets",
+                ));
+            }
+            if self.proxy.is_some() {
+                return Err(invalid_config(
+                    "`proxy` is not supported for Unix domain sockets",
+                ));
+            }
+        }
+        if let Some(server_name) = &self.tls_sni {
+            if !matches!(
+                ServerName::try_from(server_name.as_str()),
+                Ok(ServerName::DnsName(_))
+            ) {
+                return Err(invalid_config(&format!(
+                    "`tls_sni` must be a valid DNS name, was '{server_name}'"
+                )));
+            }
+        }
+        if self.read_idle_timeout_secs == Some(0) {
+            return Err(invalid_config("`read_idle_timeout_secs` must be positive"));
+        }
+        if self.max_coalesce_bytes == Some(0) || self.max_coalesce_frames == Some(0) {
+            return Err(invalid_config("Coalescing limits must be positive"));
+        }
+        if let Some((_, message)) = &self.heartbeat {
+            if let Err(e) = self.send_framing().encode(message) {
+                return Err(invalid_config(&format!("Invalid heartbeat message: {e}")));
+            }
+        }
+        for data in self.on_connect_send.iter().flatten() {
+            if let Err(e) = self.send_framing().encode(data) {
+                return Err(invalid_config(&format!(
+                    "Invalid `on_connect_send` frame: {e}"
+                )));
+            }
+        }
+        for data in self.on_reconnect_send.iter().flatten() {
+            if let Err(e) = self.send_framing().encode(data) {
+                return Err(invalid_config(&format!(
+                    "Invalid `on_reconnect_send` frame: {e}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the write coalescing limits, if coalescing is enabled.
+    pub(crate) fn coalesce_limits(&self) -> Option<CoalesceLimits> {
+        if self.max_coalesce_bytes.is_none() && self.max_coalesce_frames.is_none() {
+            return None;
+        }
+        Some(CoalesceLimits {
+            max_bytes: self.max_coalesce_bytes.unwrap_or(usize::MAX),
+            max_frames: self.max_coalesce_frames.unwrap_or(usize::MAX),
+        })
+    }
+
+    /// Returns the framing used to encode sent messages.
+    pub(crate) fn send_framing(&self) -> &Framing {
+        self.send_framing.as_ref().unwrap_or(&self.framing)
+    }
+
+    /// Returns the maximum time shutdown waits for the read task to observe EOF.
+    ///
+    /// After an abortive close the server never answers with EOF, so there is no wait.
+    pub(crate) fn shutdown_linger(&self) -> Duration {
+        if self.tcp_options.is_abortive_close() {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(
+            self.shutdown_linger_ms
+                .unwrap_or(DEFAULT_SHUTDOWN_LINGER_MS),
+        )
+    }
+
+    /// Returns the maximum time shutdown waits for the writer to close.
+    pub(crate) fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.shutdown_timeout_secs
+                .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS),
+        )
+    }
+}
+
+impl Default for SocketConfig {
+    /// Creates a plain TCP configuration with `\n` framing, no heartbeat, a 30s reconnect
+    /// timeout and infinite reconnection tries, whose handler discards all messages.
+    ///
+    /// The `url` is empty and must be set before connecting.
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            mode: Mode::Plain,
+            framing: Framing::Delimiter(b"\n".to_vec()),
+            handler: Arc::new(|_: &[u8]| {}),
+            heartbeat: None,
+            reconnect_timeout_secs: Some(30),
+            max_reconnection_tries: None,
+            backoff: BackoffPolicy::default(),
+            controller_check_interval_ms: None,
+            handler_batch_size: None,
+            max_message_size: None,
+            on_overflow: None,
+            handler_queue_capacity: None,
+            handler_queue_overflow: HandlerQueuePolicy::default(),
+            reconnect_queue_capacity: None,
+            reconnect_queue_overflow: QueueOverflowPolicy::default(),
+            max_coalesce_bytes: None,
+            max_coalesce_frames: None,
+            connector: None,
+            deliver_unterminated_on_eof: false,
+            read_idle_timeout_secs: None,
+            heartbeat_timeout_secs: None,
+            heartbeat_response: None,
+            heartbeat_always_send: false,
+            tcp_options: TcpOptions::default(),
+            connect_timeout_secs: None,
+            client_cert_pem: None,
+            client_key_pem: None,
+            root_ca_pem: None,
+            danger_accept_invalid_certs: false,
+            tls_sni: None,
+            proxy: None,
+            fallback_urls: Vec::new(),
+            url_provider: None,
+            on_connect_send: None,
+            send_wait_active_timeout_ms: None,
+            shutdown_linger_ms: None,
+            reconnect_on_eof: true,
+            is_fatal_error: None,
+            on_fatal: None,
+            on_give_up: None,
+            max_reconnect_duration_secs: None,
+            send_framing: None,
+            handler_receive_ts: false,
+            handler_queue_high_water: None,
+            handler_queue_low_water: None,
+            on_backpressure: None,
+            drain_on_close: true,
+            cancellation_token: None,
+            auto_flush: true,
+            client_id: None,
+            shutdown_timeout_secs: None,
+            send_rate_limit: None,
+            on_send: None,
+            on_recv: None,
+            on_recv_error: None,
+            heartbeat_payload: None,
+            heartbeat_append_suffix: true,
+            on_established: None,
+            resolver: None,
+            bind_address: None,
+            tls_handshake_timeout_secs: None,
+            alpn_protocols: Vec::new(),
+            pinned_cert_sha256: None,
+            read_buffer_capacity: None,
+            handler_yield_every: None,
+            circuit_breaker: None,
+            on_reconnect_attempt: None,
+            handler_receive_position: false,
+            handler_concurrency: 1,
+            on_reconnect_send: None,
+            max_handler_errors: None,
+            on_handler_error: None,
+        }
+    }
+}
+
+/// Generates a builder setter assigning the value to the config field of the same name.
+macro_rules! config_setters {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty,)*) => {
+        $(
+            $(#[$doc])*
+            #[must_use]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+/// Generates a builder setter assigning `Some(value)` to the optional config field of the same name.
+macro_rules! config_option_setters {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty,)*) => {
+        $(
+            $(#[$doc])*
+            #[must_use]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = Some($field);
+                self
+            }
+        )*
+    };
+}
+
+/// Builder for a [`SocketConfig`], starting from [`SocketConfig::default`].
+///
+/// Each setter documents the field it sets on [`SocketConfig`].
+#[derive(Clone, Debug, Default)]
+pub struct SocketConfigBuilder {
+    config: SocketConfig,
+}
+
+impl SocketConfig {
+    /// Creates a new [`SocketConfigBuilder`] with the default configuration.
+    #[must_use]
+    pub fn builder() -> SocketConfigBuilder {
+        SocketConfigBuilder::default()
+    }
+}
+
+impl SocketConfigBuilder {
+    /// Creates a new [`SocketConfigBuilder`] with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the URL to connect to.
+    #[must_use]
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.config.url = url.into();
+        self
+    }
+
+    /// Sets the handler for incoming messages.
+    #[must_use]
+    pub fn handler(mut self, handler: impl MessageHandler + 'static) -> Self {
+        self.config.handler = Arc::new(handler);
+        self
+    }
+
+    /// Sets delimiter framing with the given message suffix for both directions.
+    #[must_use]
+    pub fn suffix(mut self, suffix: impl Into<Vec<u8>>) -> Self {
+        self.config.framing = Framing::Delimiter(suffix.into());
+        self.config.send_framing = None;
+        self
+    }
+
+    /// Sets the suffix appended to sent messages and heartbeats.
+    #[must_use]
+    pub fn send_suffix(mut self, suffix: impl Into<Vec<u8>>) -> Self {
+        self.config.send_framing = Some(Framing::Delimiter(suffix.into()));
+        self
+    }
+
+    /// Sets the suffix which splits received messages.
+    #[must_use]
+    pub fn recv_suffix(mut self, suffix: impl Into<Vec<u8>>) -> Self {
+        self.config.framing = Framing::Delimiter(suffix.into());
+        self
+    }
+
+    /// Sets the heartbeat sent every `interval_secs`.
+    #[must_use]
+    pub fn heartbeat(self, interval_secs: u64, message: impl Into<Vec<u8>>) -> Self {
+        self.heartbeat_interval(Duration::from_secs(interval_secs), message)
+    }
+
+    /// Sets the heartbeat sent every `interval`, allowing sub-second periods.
+    #[must_use]
+    pub fn heartbeat_interval(mut self, interval: Duration, message: impl Into<Vec<u8>>) -> Self {
+        self.config.heartbeat = Some((interval, message.into()));
+        self
+    }
+
+    config_setters! {
+        /// Sets the connection mode.
+        mode: Mode,
+        /// Sets the framing scheme.
+        framing: Framing,
+        /// Sets the backoff policy between reconnection attempts.
+        backoff: BackoffPolicy,
+        /// Sets the policy applied when the handler queue is full.
+        handler_queue_overflow: HandlerQueuePolicy,
+        /// Sets the policy applied when the reconnect queue is full.
+        reconnect_queue_overflow: QueueOverflowPolicy,
+        /// Sets if unterminated bytes are delivered when the server closes.
+        deliver_unterminated_on_eof: bool,
+        /// Sets if heartbeats are sent on every interval.
+        heartbeat_always_send: bool,
+        /// Sets the TCP socket options.
+        tcp_options: TcpOptions,
+        /// Sets if server certificates are accepted without verification.
+        danger_accept_invalid_certs: bool,
+        /// Sets the fallback URLs.
+        fallback_urls: Vec<String>,
+        /// Sets the protocols offered with ALPN.
+        alpn_protocols: Vec<Vec<u8>>,
+        /// Sets if the client reconnects after a clean server close.
+        reconnect_on_eof: bool,
+        /// Sets if the handler is called with the receive timestamp of each message.
+        handler_receive_ts: bool,
+        /// Sets if queued frames are written before the connection closes.
+        drain_on_close: bool,
+        /// Sets if the writer is flushed after every write.
+        auto_flush: bool,
+        /// Sets if heartbeats are framed like data messages.
+        heartbeat_append_suffix: bool,
+        /// Sets if the handler receives the position of each message on its connection.
+        handler_receive_position: bool,
+        /// Sets the number of workers calling the handler from the handler queue.
+        handler_concurrency: usize,
+    }
+
+    config_option_setters! {
+        /// Sets the reconnect timeout (seconds).
+        reconnect_timeout_secs: u64,
+        /// Sets the maximum reconnection attempts.
+        max_reconnection_tries: u64,
+        /// Sets the controller check interval (milliseconds).
+        controller_check_interval_ms: u64,
+        /// Sets the maximum number of messages per handler batch.
+        handler_batch_size: usize,
+        /// Sets the number of messages delivered between yields of the read task.
+        handler_yield_every: usize,
+        /// Sets the maximum received message size (bytes).
+        max_message_size: usize,
+        /// Sets the callback invoked when `max_message_size` is exceeded.
+        on_overflow: OverflowCallback,
+        /// Sets the capacity of the handler queue.
+        handler_queue_capacity: usize,
+        /// Sets the capacity of the reconnect queue.
+        reconnect_queue_capacity: usize,
+        /// Sets the maximum bytes coalesced into a single write.
+        max_coalesce_bytes: usize,
+        /// Sets the maximum frames coalesced into a single write.
+        max_coalesce_frames: usize,
+        /// Sets the connector used to dial connections.
+        connector: SocketConnector,
+        /// Sets the read idle timeout (seconds).
+        read_idle_timeout_secs: u64,
+        /// Sets the heartbeat response timeout (seconds).
+        heartbeat_timeout_secs: u64,
+        /// Sets the predicate identifying heartbeat responses.
+        heartbeat_response: HeartbeatResponse,
+        /// Sets the TCP connect timeout (seconds).
+        connect_timeout_secs: u64,
+        /// Sets the TLS handshake timeout (seconds).
+        tls_handshake_timeout_secs: u64,
+        /// Sets the SHA-256 fingerprints of pinned server certificates.
+        pinned_cert_sha256: Vec<[u8; 32]>,
+        /// Sets the PEM-encoded client certificate chain.
+        client_cert_pem: Vec<u8>,
+        /// Sets the PEM-encoded client private key.
+        client_key_pem: Vec<u8>,
+        /// Sets the PEM-encoded CA certificates to trust.
+        root_ca_pem: Vec<u8>,
+        /// Sets the TLS server name override.
+        tls_sni: String,
+        /// Sets the proxy to tunnel through.
+        proxy: ProxyConfig,
+        /// Sets the provider of the endpoint to dial.
+        url_provider: UrlProvider,
+        /// Sets the messages sent on every new connection.
+        on_connect_send: Vec<Vec<u8>>,
+        /// Sets the messages sent on every reconnection, after the connect messages.
+        on_reconnect_send: Vec<Vec<u8>>,
+        /// Sets the maximum time (milliseconds) a send waits for an active connection.
+        send_wait_active_timeout_ms: u64,
+        /// Sets the maximum time (milliseconds) shutdown waits for EOF.
+        shutdown_linger_ms: u64,
+        /// Sets the predicate classifying reconnect errors as fatal.
+        is_fatal_error: FatalErrorClassifier,
+        /// Sets the callback invoked on a fatal reconnect error.
+        on_fatal: FatalCallback,
+        /// Sets the callback invoked when reconnection tries are exhausted.
+        on_give_up: GiveUpCallback,
+        /// Sets the maximum time (seconds) spent reconnecting.
+        max_reconnect_duration_secs: u64,
+        /// Sets the framing used to encode sent messages.
+        send_framing: Framing,
+        /// Sets the token which aborts connecting and closes the client once cancelled.
+        cancellation_token: CancellationToken,
+        /// Sets the handler queue depth at which backpressure is signalled.
+        handler_queue_high_water: usize,
+        /// Sets the handler queue depth at which backpressure clears.
+        handler_queue_low_water: usize,
+        /// Sets the callback invoked when backpressure is signalled or clears.
+        on_backpressure: BackpressureCallback,
+        /// Sets the identifier attached to the client task spans.
+        client_id: String,
+        /// Sets the maximum time (seconds) shutdown waits for the writer to close.
+        shutdown_timeout_secs: u64,
+        /// Sets the rate limit applied to sent frames.
+        send_rate_limit: Quota,
+        /// Sets the hook transforming the payload of each sent message.
+        on_send: SendTransform,
+        /// Sets the hook transforming each received message.
+        on_recv: RecvTransform,
+        /// Sets the callback invoked when the `on_recv` hook fails.
+        on_recv_error: RecvErrorCallback,
+        /// Sets the callback producing each heartbeat payload.
+        heartbeat_payload: HeartbeatPayload,
+        /// Sets the callback invoked after every established connection.
+        on_established: EstablishedCallback,
+        /// Sets the resolver of the dialed address.
+        resolver: Resolver,
+        /// Sets the local address connections are made from.
+        bind_address: SocketAddr,
+        /// Sets the circuit breaker suspending reconnection attempts after repeated failures.
+        circuit_breaker: CircuitBreakerPolicy,
+        /// Sets the callback invoked before each reconnect attempt.
+        on_reconnect_attempt: ReconnectAttemptCallback,
+        /// Sets the number of consecutive handler errors which drop the connection.
+        max_handler_errors: u32,
+        /// Sets the callback invoked with each error reported by the handler.
+        on_handler_error: HandlerErrorCallback,
+        /// Sets the capacity of the read buffer reused across connections.
+        read_buffer_capacity: usize,
+    }
+
+    /// Validates and returns the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`SocketConfig::validate`] fails.
+    pub fn build(self) -> Result<SocketConfig, std::io::Error> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Limits on how many queued frames the writer task combines into one write.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CoalesceLimits {
+    max_bytes: usize,
+    max_frames: usize,
+}
+
+/// Returns the socket path if `url` addresses a Unix domain socket.
+fn unix_socket_path(url: &str) -> Option<&str> {
+    url.strip_prefix(UNIX_SOCKET_SCHEME)
+}
+
+/// Awaits `connect`, failing with [`std::io::ErrorKind::TimedOut`] once `timeout` elapses.
+async fn with_connect_timeout<T>(
+    connect: impl std::future::Future<Output = std::io::Result<T>>,
+    timeout: Option<Duration>,
+    addr: &str,
+) -> std::io::Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connect).await.map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("Connection to {addr} timed out after {timeout:?}"),
+            )
+        })?,
+        None => connect.await,
+    }
+}
+
+/// Resolves `addr` and connects to the first of its addresses to accept the connection.
+///
+/// With a `bind_address` only the resolved addresses of the same IP family are dialed.
+async fn connect_tcp(
+    addr: &str,
+    resolver: Option<&Resolver>,
+    bind_address: Option<SocketAddr>,
+    tcp_options: TcpOptions,
+) -> std::io::Result<TcpStream> {
+    let mut addrs = match resolver {
+        Some(resolver) => resolver(addr).await?,
+        None => tokio::net::lookup_host(addr).await?.collect(),
+    };
+    if addrs.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No addresses resolved for {addr}"),
+        ));
+    }
+    if let Some(bind_address) = bind_address {
+        addrs.retain(|resolved| resolved.is_ipv6() == bind_address.is_ipv6());
+        if addrs.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "No address of {addr} matches the IP family of bind address {bind_address}"
+                ),
+            ));
+        }
+    }
+    connect_happy_eyeballs(
+        interleave_families(addrs),
+        CONNECTION_ATTEMPT_DELAY,
+        bind_address,
+        tcp_options,
+    )
+    .await
+}
+
+/// Connects to `addr`, from `bind_address` if set.
+async fn connect_addr(
+    addr: SocketAddr,
+    bind_address: Option<SocketAddr>,
+    tcp_options: TcpOptions,
+) -> std::io::Result<TcpStream> {
+    if bind_address.is_none() && tcp_options.dscp.is_none() {
+        return TcpStream::connect(addr).await;
+    }
+    let socket = if addr.is_ipv6() {
+        TcpSocket::new_v6()?
+    } else {
+        TcpSocket::new_v4()?
+    };
+    tcp_options.apply_before_connect(&socket, &addr)?;
+    if let Some(bind_address) = bind_address {
+        socket.bind(bind_address)?;
+    }
+    socket.connect(addr).await
+}
+
+/// Orders resolved addresses alternating between IP families, starting with the family
+/// of the first address returned by the resolver.
+fn interleave_families(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let Some(prefer_ipv6) = addrs.first().map(SocketAddr::is_ipv6) else {
+        return addrs;
+    };
+    let (mut preferred, mut other): (VecDeque<_>, VecDeque<_>) = addrs
+        .into_iter()
+        .partition(|addr| addr.is_ipv6() == prefer_ipv6);
+
+    let mut ordered = Vec::with_capacity(preferred.len() + other.len());
+    while !preferred.is_empty() || !other.is_empty() {
+        ordered.extend(preferred.pop_front());
+        ordered.extend(other.pop_front());
+    }
+    ordered
+}
+
+/// Races connection attempts to `addrs` in order, returning the first to succeed.
+///
+/// The next attempt starts as soon as the previous one fails, or once `delay` elapses
+/// while it is still pending. The remaining attempts are cancelled on success.
+async fn connect_happy_eyeballs(
+    addrs: Vec<SocketAddr>,
+    delay: Duration,
+    bind_address: Option<SocketAddr>,
+    tcp_options: TcpOptions,
+) -> std::io::Result<TcpStream> {
+    let mut pending = addrs.into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+
+    loop {
+        if let Some(addr) = pending.next() {
+            attempts
+                .push(async move { (addr, connect_addr(addr, bind_address, tcp_options).await) });
+        } else if attempts.is_empty() {
+            break;
+        }
+
+        tokio::select! {
+            Some((addr, result)) = attempts.next() => match result {
+                Ok(stream) => return Ok(stream),
+                Err(e) => {
+                    tracing::debug!("Failed to connect to {addr}: {e}");
+                    last_error = Some(e);
+                }
+            },
+            () = tokio::time::sleep(delay), if !pending.as_slice().is_empty() => {}
+        }
+    }
+    Err(last_error.expect("at least one address was attempted"))
+}
+
+/// Replies to every pending reconnect request with the outcome of the attempt.
+fn reply_reconnect_requests(
+    requests: &mut Vec<ReconnectReply>,
+    outcome: impl Fn() -> Result<(), SocketError>,
+) {
+    for reply_tx in requests.drain(..) {
+        let _ = reply_tx.send(outcome());
+    }
+}
+
+fn invalid_config(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, msg)
+}
+
+fn writer_closed() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Writer task closed")
+}
+
+/// Requests the writer task to flush and waits for the outcome.
+async fn request_flush(flush_tx: &FlushRequestSender) -> Result<(), SocketError> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    flush_tx.send(reply_tx).map_err(|_| SocketError::Closed)?;
+    match reply_rx.await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(SocketError::NotConnected),
+    }
+}
+
+/// Requests the writer task to flush once any frames queued during a reconnect have been
+/// passed to it, so they are covered by the flush, waiting at most `timeout` in total.
+async fn request_flush_after_queued(
+    flush_tx: &FlushRequestSender,
+    reconnect_queue: Option<&ReconnectQueue>,
+    connection_state: &ConnectionStateCell,
+    timeout: Duration,
+) -> Result<(), SocketError> {
+    tokio::time::timeout(timeout, async {
+        if let Some(queue) = reconnect_queue {
+            queue.wait_flushed(connection_state).await;
+        }
+        request_flush(flush_tx).await
+    })
+    .await
+    .map_err(|_| SocketError::Timeout(format!("Flush did not complete within {timeout:?}")))?
+}
+
+/// Completes once `token` is cancelled, or never without a token.
+async fn cancelled(token: Option<&CancellationToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn reconnect_cancelled() -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::Interrupted,
+        "Reconnect cancelled by close",
+    ))
+}
+
+/// Liveness check performed by the heartbeat task before each heartbeat.
+struct HeartbeatWatchdog {
+    timeout_ms: u64,
+    last_response_ms: Arc<AtomicU64>,
+    read_task: tokio::task::AbortHandle,
+    exit_reason: SharedExitReason,
+}
+
+/// Drops the connection when the writer task fails, so the controller reconnects instead
+/// of senders queueing frames which are never written.
+struct WriteFailureHook {
+    read_task: tokio::task::AbortHandle,
+    exit_reason: SharedExitReason,
+}
+
+impl WriteFailureHook {
+    /// Drops the connection after the writer task stopped on `error`.
+    fn on_error(&self, error: &std::io::Error) {
+        let kind = error.kind();
+        tracing::warn!("Write failed with {kind}, dropping connection");
+        *self.exit_reason.lock().unwrap() = Some(ReadExitReason::Io(kind));
+        self.read_task.abort();
+    }
+}
+
+/// Handle to the task which owns the write half of a connection.
+struct WriterTask {
+    handle: tokio::task::JoinHandle<()>,
+    /// Signals the writer to close, first writing any queued frames for up to the given time.
+    shutdown_tx: oneshot::Sender<Option<Duration>>,
+}
+
+/// Creates a TcpStream with the server.
+///
+/// The stream can be encrypted with TLS or Plain. The stream is split into
+/// read and write ends:
+/// - The read end is passed to the task that keeps receiving
+///   messages from the server and passing them to a handler.
+/// - The write end is owned by a writer task which writes the frames sent
+///   through a channel, so messages and heart beats never contend on a lock.
+///   The channel outlives each connection and the writer task is replaced
+///   on reconnect.
+///
+/// The heartbeat is optional and can be configured with an interval and data to
+/// send.
+///
+/// The client uses a [`Framing`] to separate messages on the byte stream. It is
+/// applied to all sent messages and heartbeats. It is also used to split
+/// the received byte stream.
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+struct SocketClientInner {
+    config: SocketConfig,
+    read_task: tokio::task::JoinHandle<()>,
+    heartbeat_task: Option<tokio::task::JoinHandle<()>>,
+    writer_task: Option<WriterTask>,
+    writer_tx: FrameSender,
+    writer_rx: SharedFrameReceiver,
+    flush_tx: FlushRequestSender,
+    flush_rx: SharedFlushReceiver,
+    reconnection_lock: Arc<Mutex<()>>,
+    connection_state: Arc<ConnectionStateCell>,
+    reconnect_queue: Option<Arc<ReconnectQueue>>,
+    reconnect_timeout_secs: u64,
+    connector: Option<SocketConnector>,
+    addrs: SharedConnectionAddrs,
+    last_heartbeat_response: Arc<AtomicU64>,
+    counters: Arc<SocketCounters>,
+    read_pause: Arc<ReadPause>,
+    read_buffers: Arc<ReadBufferPool>,
+    dispatch: HandlerDispatch,
+    disconnect_mode: Arc<AtomicBool>,
+    exit_reason: SharedExitReason,
+    runtime: Option<tokio::runtime::Handle>,
+    shutdown_done: AtomicBool,
+    span: tracing::Span,
+}
+
+impl SocketClientInner {
+    pub async fn connect_url(config: SocketConfig) -> Result<Self, Error> {
+        install_cryptographic_provider();
+
+        let connector = match config.connector.clone() {
+            Some(connector) => connector,
+            None => Self::default_connector(&config)?,
+        };
+        let mut connection = connector().await?;
+        Self::send_on_connect(&config, &mut connection.writer).await?;
+        Ok(Self::from_parts(config, connection, Some(connector)))
+    }
+
+    /// Writes the configured `on_connect_send` frames, in order, to a new connection.
+    async fn send_on_connect(
+        config: &SocketConfig,
+        writer: &mut SocketWriter,
+    ) -> Result<(), std::io::Error> {
+        Self::send_frames(config, writer, config.on_connect_send.as_deref()).await
+    }
+
+    /// Writes the configured `on_connect_send` then `on_reconnect_send` frames, in order,
+    /// to a replacement connection before any task can use it.
+    async fn send_on_reconnect(
+        config: &SocketConfig,
+        writer: &mut SocketWriter,
+    ) -> Result<(), std::io::Error> {
+        Self::send_on_connect(config, writer).await?;
+        Self::send_frames(config, writer, config.on_reconnect_send.as_deref()).await
+    }
+
+    async fn send_frames(
+        config: &SocketConfig,
+        writer: &mut SocketWriter,
+        frames: Option<&[Vec<u8>]>,
+    ) -> Result<(), std::io::Error> {
+        let Some(frames) = frames else {
+            return Ok(());
+        };
+        for data in frames {
+            let frame = config
+                .send_framing()
+                .encode(data)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            writer.write_all(&frame).await?;
+        }
+        writer.flush().await
+    }
+
+    /// Creates the inner client from an established connection.
+    fn from_parts(
+        mut config: SocketConfig,
+        connection: SocketConnection,
+        connector: Option<SocketConnector>,
+    ) -> Self {
+        let SocketConnection {
+            reader,
+            writer,
+            peer_addr,
+            local_addr,
+            url,
+            alpn_protocol,
+            #[cfg(unix)]
+            raw_fd,
+        } = connection;
+        let addrs = Arc::new(std::sync::Mutex::new(ConnectionAddrs {
+            peer: peer_addr,
+            local: local_addr,
+            url,
+            alpn_protocol,
+            #[cfg(unix)]
+            raw_fd,
+        }));
+        // Tasks spawned while the span is entered inherit it
+        let span = client_span(&mut config);
+        let _entered = span.clone().entered();
+        let framing = config.send_framing();
+        let SocketConfig {
+            heartbeat,
+            reconnect_timeout_secs,
+            reconnect_queue_capacity,
+            reconnect_queue_overflow,
+            ..
+        } = &config;
+        let (writer_tx, writer_rx) = mpsc::channel(WRITER_CHANNEL_CAPACITY);
+        let writer_rx = Arc::new(Mutex::new(writer_rx));
+        let (flush_tx, flush_rx) = mpsc::unbounded_channel();
+        let flush_rx = Arc::new(Mutex::new(flush_rx));
+        let connection_state = Arc::new(ConnectionStateCell::new(ConnectionState::Active));
+        let reconnection_lock = Arc::new(Mutex::new(()));
+        let reconnect_timeout_secs = reconnect_timeout_secs.unwrap_or(30);
+        let reconnect_queue = reconnect_queue_capacity
+            .map(|capacity| Arc::new(ReconnectQueue::new(capacity, *reconnect_queue_overflow)));
+
+        let last_heartbeat_response = Arc::new(AtomicU64::new(0));
+        let counters = Arc::new(SocketCounters::default());
+        counters.record_connected();
+        let read_pause = Arc::new(ReadPause::default());
+        let read_buffers = Arc::new(ReadBufferPool::new(
+            config.read_buffer_capacity.unwrap_or(READ_CHUNK_SIZE),
+        ));
+        let dispatch = HandlerDispatch::new(&config, &counters);
+        let exit_reason = SharedExitReason::default();
+        let read_task = Self::spawn_read_task(
+            reader,
+            &config,
+            &dispatch,
+            &last_heartbeat_response,
+            &counters,
+            &read_pause,
+            &read_buffers,
+            &exit_reason,
+        );
+        let writer_task = Some(Self::spawn_writer_task(
+            writer,
+            writer_rx.clone(),
+            flush_rx.clone(),
+            config.coalesce_limits(),
+            config.auto_flush,
+            !config.tcp_options.is_abortive_close(),
+            Some(WriteFailureHook {
+                read_task: read_task.abort_handle(),
+                exit_reason: exit_reason.clone(),
+            }),
+        ));
+
+        // Optionally spawn a heartbeat task to periodically ping server
+        let heartbeat_task = heartbeat.as_ref().map(|heartbeat| {
+            Self::spawn_heartbeat_task(
+                connection_state.clone(),
+                heartbeat.clone(),
+                writer_tx.clone(),
+                config.heartbeat_append_suffix.then_some(framing),
+                Self::heartbeat_watchdog(
+                    &config,
+                    &read_task,
+                    &last_heartbeat_response,
+                    &exit_reason,
+                ),
+                (!config.heartbeat_always_send).then(|| counters.clone()),
+                config.heartbeat_payload.clone(),
+            )
+        });
+
+        Self {
+            config,
+            read_task,
+            heartbeat_task,
+            writer_task,
+            writer_tx,
+            writer_rx,
+            flush_tx,
+            flush_rx,
+            reconnection_lock,
+            connection_state,
+            reconnect_queue,
+            reconnect_timeout_secs,
+            connector,
+            addrs,
+            last_heartbeat_response,
+            counters,
+            read_pause,
+            read_buffers,
+            dispatch,
+            disconnect_mode: Arc::new(AtomicBool::new(false)),
+            exit_reason,
+            runtime: tokio::runtime::Handle::try_current().ok(),
+            shutdown_done: AtomicBool::new(false),
+            span,
+        }
+    }
+
+    /// Returns a connector which dials the config `url` over TCP, with TLS when `mode` requires it.
+    ///
+    /// The TLS client config is built once and reused for every reconnect.
+    fn default_connector(config: &SocketConfig) -> std::io::Result<SocketConnector> {
+        let options = Arc::new(DialOptions::from_config(config)?);
+        let current_url = Arc::new(AtomicUsize::new(0));
+        Ok(Arc::new(move || {
+            let options = options.clone();
+            let current_url = current_url.clone();
+            Box::pin(async move { Self::connect_any(&options, &current_url).await })
+        }))
+    }
+
+    /// Connects to the first reachable URL, starting from the one last connected to
+    /// and wrapping around the list.
+    async fn connect_any(
+        options: &DialOptions,
+        current_url: &AtomicUsize,
+    ) -> Result<SocketConnection, Error> {
+        if let Some(url_provider) = &options.url_provider {
+            let Endpoint { url, handshake } = url_provider()?;
+            let mut connection = Self::tls_connect_with_server(options, &url).await?;
+            if let Some(handshake) = handshake {
+                connection.writer.write_all(&handshake).await?;
+                connection.writer.flush().await?;
+            }
+            return Ok(connection.with_url(Some(url)));
+        }
+
+        let start = current_url.load(Ordering::Relaxed);
+        let mut last_error = None;
+        for offset in 0..options.urls.len() {
+            let index = (start + offset) % options.urls.len();
+            let url = &options.urls[index];
+            match Self::tls_connect_with_server(options, url).await {
+                Ok(connection) => {
+                    current_url.store(index, Ordering::Relaxed);
+                    return Ok(connection.with_url(Some(url.clone())));
+                }
+                Err(e) => {
+                    if options.urls.len() > 1 {
+                        tracing::warn!("Failed to connect to {url}: {e}");
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.expect("`urls` always contains the primary URL"))
+    }
+
+    async fn tls_connect_with_server(
+        options: &DialOptions,
+        url: &str,
+    ) -> Result<SocketConnection, Error> {
+        let DialOptions {
+            urls: _,
+            url_provider: _,
+            resolver,
+            bind_address,
+            mode,
+            tcp_options,
+            connect_timeout,
+            tls_handshake_timeout,
+            tls_connector,
+            tls_sni,
+            proxy,
+        } = options;
+
+        #[cfg(unix)]
+        if let Some(path) = unix_socket_path(url) {
+            tracing::debug!("Connecting to Unix socket {path}");
+            let connect = tokio::net::UnixStream::connect(path);
+            let stream = with_connect_timeout(connect, *connect_timeout, path).await?;
+            let raw_fd = stream.as_raw_fd();
+            return Ok(SocketConnection::new(stream).with_raw_fd(Some(raw_fd)));
+        }
+
+        // With a proxy the TCP session is made to the proxy, which tunnels to the server
+        let dial_addr = proxy
+            .as_ref()
+            .map_or_else(|| url.to_string(), ProxyConfig::address);
+        tracing::debug!("Connecting to {dial_addr}");
+        let connect = connect_tcp(
+            dial_addr.as_str(),
+            resolver.as_ref(),
+            *bind_address,
+            *tcp_options,
+        );
+        let mut stream = with_connect_timeout(connect, *connect_timeout, &dial_addr).await?;
+        tcp_options.apply(&stream)?;
+        let peer_addr = stream.peer_addr().ok();
+        let local_addr = stream.local_addr().ok();
+        #[cfg(unix)]
+        let raw_fd = stream.as_raw_fd();
+        if let Some(proxy) = proxy {
+            let (host, port) = split_host_port(url)?;
+            tracing::debug!(
+                "Tunneling to {host}:{port} through {:?} proxy",
+                proxy.scheme
+            );
+            proxy.tunnel(&mut stream, host, port).await?;
+        }
+        tracing::debug!("Making TLS connection");
+        let handshake = async {
+            match tls_sni {
+                Some(server_name) => {
+                    tcp_tls_with_server_name(
+                        server_name.clone(),
+                        *mode,
+                        stream,
+                        tls_connector.clone(),
+                    )
+                    .await
+                }
+                None => {
+                    let request = url.into_client_request()?;
+                    tcp_tls(&request, *mode, stream, tls_connector.clone()).await
+                }
+            }
+        };
+        let stream = match tls_handshake_timeout {
+            Some(timeout) => tokio::time::timeout(*timeout, handshake)
+                .await
+                .map_err(|_| {
+                    std::io::Error::new(std::io::ErrorKind::TimedOut, TlsHandshakeTimeout(*timeout))
+                })??,
+            None => handshake.await?,
+        };
+        let alpn_protocol = match &stream {
+            MaybeTlsStream::Rustls(stream) => {
+                stream.get_ref().1.alpn_protocol().map(<[u8]>::to_vec)
+            }
+            _ => None,
+        };
+        let connection = SocketConnection::new(stream)
+            .with_peer_addr(peer_addr)
+            .with_local_addr(local_addr)
+            .with_alpn_protocol(alpn_protocol);
+        #[cfg(unix)]
+        let connection = connection.with_raw_fd(Some(raw_fd));
+        Ok(connection)
+    }
+
+    /// Reconnect with server.
+    ///
+    /// Make a new connection with server. Use the new read and write halves
+    /// to replace the writer, read and heartbeat tasks, then replay any frames
+    /// queued while reconnecting.
+    async fn reconnect(&mut self) -> Result<(), Error> {
+        tracing::debug!("Reconnecting client");
+
+        let timeout = Duration::from_secs(self.reconnect_timeout_secs);
+        let reconnection_lock = self.reconnection_lock.clone();
+        tokio::time::timeout(timeout, async {
+            let state_guard = {
+                let guard = reconnection_lock.lock().await;
+                if self.is_disconnect_requested()
+                    || !self
+                        .connection_state
+                        .store_unless_closing(ConnectionState::Reconnecting)
+                {
+                    return Err(reconnect_cancelled());
+                }
+                guard
+            };
+
+            // Clean up existing tasks
+            self.shutdown(false, None).await;
+
+            let Some(connector) = &self.connector else {
+                return Err(Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "No connector to reconnect with",
+                )));
+            };
+            let heartbeat = &self.config.heartbeat;
+            let framing = self.config.send_framing();
+            // Create a fresh connection
+            let SocketConnection {
+                reader,
+                mut writer,
+                peer_addr,
+                local_addr,
+                url,
+                alpn_protocol,
+                #[cfg(unix)]
+                raw_fd,
+            } = connector().await?;
+            Self::send_on_reconnect(&self.config, &mut writer).await?;
+
+            // Close may have been requested while connecting, no tasks are spawned then
+            if self.is_disconnect_requested() {
+                return Err(reconnect_cancelled());
+            }
+            *self.addrs.lock().unwrap() = ConnectionAddrs {
+                peer: peer_addr,
+                local: local_addr,
+                url,
+                alpn_protocol,
+                #[cfg(unix)]
+                raw_fd,
+            };
+            // Spawn new read task, sharing the existing dispatch so the handler is not cloned
+            self.read_task = Self::spawn_read_task(
+                reader,
+                &self.config,
+                &self.dispatch,
+                &self.last_heartbeat_response,
+                &self.counters,
+                &self.read_pause,
+                &self.read_buffers,
+                &self.exit_reason,
+            );
+            self.writer_task = Some(Self::spawn_writer_task(
+                writer,
+                self.writer_rx.clone(),
+                self.flush_rx.clone(),
+                self.config.coalesce_limits(),
+                self.config.auto_flush,
+                !self.config.tcp_options.is_abortive_close(),
+                Some(WriteFailureHook {
+                    read_task: self.read_task.abort_handle(),
+                    exit_reason: self.exit_reason.clone(),
+                }),
+            ));
+            self.shutdown_done.store(false, Ordering::SeqCst);
+
+            // Optionally spawn new heartbeat task
+            self.heartbeat_task = heartbeat.as_ref().map(|heartbeat| {
+                Self::spawn_heartbeat_task(
+                    self.connection_state.clone(),
+                    heartbeat.clone(),
+                    self.writer_tx.clone(),
+                    self.config.heartbeat_append_suffix.then_some(framing),
+                    Self::heartbeat_watchdog(
+                        &self.config,
+                        &self.read_task,
+                        &self.last_heartbeat_response,
+                        &self.exit_reason,
+                    ),
+                    (!self.config.heartbeat_always_send).then(|| self.counters.clone()),
+                    self.config.heartbeat_payload.clone(),
+                )
+            });
+
+            drop(state_guard);
+
+            match &self.reconnect_queue {
+                Some(queue) => queue.flush(&self.writer_tx, &self.connection_state).await?,
+                None => {
+                    self.connection_state
+                        .store_unless_closing(ConnectionState::Active);
+                }
+            }
+            self.counters.record_reconnect();
+
+            tracing::debug!("Reconnect succeeded");
+            Ok(())
+        })
+        .await
+        .map_err(|_| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("reconnection timed out after {}s", timeout.as_secs()),
+            ))
+        })?
+    }
+
+    /// Reconnects, giving up with a timeout error once `deadline` passes.
+    async fn reconnect_until(&mut self, deadline: Option<Instant>) -> Result<(), Error> {
+        let Some(deadline) = deadline else {
+            return self.reconnect().await;
+        };
+        match tokio::time::timeout_at(deadline, self.reconnect()).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "Reconnect deadline exceeded",
+            ))),
+        }
+    }
+
+    fn is_disconnect_requested(&self) -> bool {
+        self.disconnect_mode.load(Ordering::SeqCst)
+    }
+
+    fn is_fatal(&self, error: &SocketError) -> bool {
+        match &self.config.is_fatal_error {
+            Some(is_fatal_error) => is_fatal_error(error),
+            None => error.is_fatal(),
+        }
+    }
+
+    /// Returns whether the server closed the connection cleanly and the client
+    /// should close rather than reconnect.
+    fn is_closed_by_server(&self) -> bool {
+        !self.config.reconnect_on_eof
+            && *self.exit_reason.lock().unwrap() == Some(ReadExitReason::Eof)
+    }
+
+    /// Shuts down the tasks of the current connection.
+    ///
+    /// Subsequent calls return immediately until a new connection is established.
+    async fn shutdown(&mut self, drain_writes: bool, drain_timeout: Option<Duration>) {
+        if self.shutdown_done.swap(true, Ordering::SeqCst) {
+            tracing::debug!("Already shut down");
+            return;
+        }
+        shutdown(
+            &mut self.read_task,
+            self.heartbeat_task.take(),
+            self.writer_task.take(),
+            drain_writes,
+            drain_timeout,
+            self.config.shutdown_linger(),
+            self.config.shutdown_timeout(),
+        )
+        .await;
+    }
+
+    /// Check if the client is still alive.
+    ///
+    /// The client is connected if the read task has not finished. It is expected
+    /// that in case of any failure client or server side. The read task will be
+    /// shutdown. There might be some delay between the connection being closed
+    /// and the client detecting it.
+    #[inline]
+    #[must_use]
+    pub fn is_alive(&self) -> bool {
+        !self.read_task.is_finished()
+    }
+
+    fn peer_addr(&self) -> Option<SocketAddr> {
+        self.addrs.lock().unwrap().peer
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    fn spawn_read_task(
+        mut reader: SocketReader,
+        config: &SocketConfig,
+        handler: &HandlerDispatch,
+        last_heartbeat_response: &Arc<AtomicU64>,
+        counters: &Arc<SocketCounters>,
+        read_pause: &Arc<ReadPause>,
+        read_buffers: &Arc<ReadBufferPool>,
+        exit_reason: &SharedExitReason,
+    ) -> tokio::task::JoinHandle<()> {
+        tracing::debug!("Started task 'read'");
+        let exit_reason = exit_reason.clone();
+
+        // Heartbeat responses are only tracked when the watchdog is enabled
+        let last_heartbeat_response = config
+            .heartbeat_timeout_secs
+            .map(|_| last_heartbeat_response.clone());
+        if let Some(ref last_response) = last_heartbeat_response {
+            last_response.store(get_atomic_clock_realtime().get_time_ms(), Ordering::Release);
+        }
+        let heartbeat_response = config.heartbeat_response.clone();
+
+        let handler = handler.clone();
+        let handler_errors = handler.errors();
+        let errors_exceeded = move || handler_errors.as_ref().is_some_and(|e| e.exceeded());
+        if let Some(errors) = handler.errors() {
+            errors.clear();
+        }
+        let mut decoder = PooledDecoder {
+            decoder: FrameDecoder::new(&config.framing)
+                .with_max_frame_size(config.max_message_size)
+                .with_buffer(read_buffers.take()),
+            pool: read_buffers.clone(),
+        };
+        let batch_size = config.handler_batch_size;
+        let yield_every = config.handler_yield_every;
+        let receive_position = config.handler_receive_position;
+        let on_overflow = config.on_overflow.clone();
+        let on_recv = config.on_recv.clone();
+        let on_recv_error = config.on_recv_error.clone();
+        let deliver_unterminated_on_eof = config.deliver_unterminated_on_eof;
+        let read_idle_timeout = config.read_idle_timeout_secs.map(Duration::from_secs);
+        let counters = counters.clone();
+        let read_pause = read_pause.clone();
+
+        tokio::task::spawn(
+            async move {
+                let mut batch: Vec<Bytes> = Vec::new();
+                let mut delivered = 0_usize;
+                let mut positions = PositionTracker::default();
+                handler.reset().await;
+
+                let reason = loop {
+                    let read = reader.read_buf(decoder.read_buf());
+                    let result = match read_idle_timeout {
+                        Some(timeout) => match tokio::time::timeout(timeout, read).await {
+                            Ok(result) => result,
+                            Err(_) => {
+                                tracing::warn!(
+                                    "No data received for {}s, dropping connection",
+                                    timeout.as_secs()
+                                );
+                                break ReadExitReason::Io(std::io::ErrorKind::TimedOut);
+                            }
+                        },
+                        None => read.await,
+                    };
+
+                    match result {
+                        // Connection has been terminated or vector buffer is complete
+                        Ok(0) => {
+                            tracing::debug!("Connection closed by server");
+                            if deliver_unterminated_on_eof {
+                                let position = receive_position.then(|| positions.next(0));
+                                if let Some(data) = decoder.take_remaining().and_then(|data| {
+                                    transform_received(
+                                        data,
+                                        on_recv.as_ref(),
+                                        on_recv_error.as_ref(),
+                                    )
+                                }) {
+                                    tracing::trace!("Delivering {} unterminated bytes", data.len());
+                                    counters.record_received_frames(1);
+                                    let ts_recv = get_atomic_clock_realtime().get_time_ns();
+                                    match batch_size {
+                                        Some(_) => {
+                                            handler
+                                                .handle_batch(vec![data.freeze()], ts_recv)
+                                                .await;
+                                        }
+                                        None => {
+                                            handler.handle(data.freeze(), ts_recv, position).await;
+                                        }
+                                    }
+                                }
+                            }
+                            break ReadExitReason::Eof;
+                        }
+                        Err(e) => {
+                            tracing::debug!("Connection ended: {e}");
+                            break ReadExitReason::Io(e.kind());
+                        }
+                        // Received bytes of data
+                        Ok(bytes) => {
+                            tracing::trace!("Received <binary> {bytes} bytes");
+                            counters.record_received_bytes(bytes);
+                            positions.record_received(bytes);
+                            // All frames completed by this read share its timestamp
+                            let ts_recv = get_atomic_clock_realtime().get_time_ns();
+
+                            // Hold the data until resumed, no further reads are made meanwhile
+                            if read_pause.is_paused() {
+                                tracing::debug!("Reading paused");
+                                read_pause.wait_resumed().await;
+                                tracing::debug!("Reading resumed");
+                            }
+
+                            // While received data has a line break
+                            // split it off and pass it to the handler
+                            let result = loop {
+                                match decoder.decode() {
+                                    Ok(Some(data)) => {
+                                        let position = positions.next(decoder.buffered_len());
+                                        let position = receive_position.then_some(position);
+                                        let Some(data) = transform_received(
+                                            data,
+                                            on_recv.as_ref(),
+                                            on_recv_error.as_ref(),
+                                        ) else {
+                                            continue;
+                                        };
+                                        counters.record_received_frames(1);
+                                        if let Some(ref last_response) = last_heartbeat_response {
+                                            if heartbeat_response
+                                                .as_ref()
+                                                .is_none_or(|is_response| is_response(&data))
+                                            {
+                                                last_response.store(
+                                                    get_atomic_clock_realtime().get_time_ms(),
+                                                    Ordering::Release,
+                                                );
+                                            }
+                                        }
+
+                                        match batch_size {
+                                            Some(size) => {
+                                                batch.push(data.freeze());
+                                                if batch.len() >= size {
+                                                    handler
+                                                        .handle_batch(
+                                                            std::mem::take(&mut batch),
+                                                            ts_recv,
+                                                        )
+                                                        .await;
+                                                }
+                                            }
+                                            None => {
+                                                handler
+                                                    .handle(data.freeze(), ts_recv, position)
+                                                    .await;
+                                            }
+                                        }
+                                        if errors_exceeded() {
+                                            break Ok(());
+                                        }
+
+                                        // Each Python handler call holds the GIL only for its own
+                                        // duration, yielding also lets waiting Python threads take it
+                                        if let Some(every) = yield_every {
+                                            delivered += 1;
+                                            if delivered % every == 0 {
+                                                tokio::task::yield_now().await;
+                                            }
+                                        }
+                                    }
+                                    Ok(None) => break Ok(()),
+                                    Err(e) => break Err(e),
+                                }
+                            };
+
+                            // Deliver any remaining complete frames from this read, unless
+                            // the connection is dropped for handler errors
+                            if !batch.is_empty() && !errors_exceeded() {
+                                handler
+                                    .handle_batch(std::mem::take(&mut batch), ts_recv)
+                                    .await;
+                            }
+
+                            if errors_exceeded() {
+                                tracing::error!(
+                                    "Handler errors exceeded limit, dropping connection"
+                                );
+                                break ReadExitReason::HandlerErrors;
+                            }
+                            if let Err(e) = result {
+                                tracing::error!("Dropping connection: {e}");
+                                if let FrameError::TooLarge { size, .. } = e {
+                                    if let Some(ref on_overflow) = on_overflow {
+                                        on_overflow(size);
+                                    }
+                                }
+                                break ReadExitReason::Io(std::io::ErrorKind::InvalidData);
+                            }
+                        }
+                    };
+                };
+                *exit_reason.lock().unwrap() = Some(reason);
+            }
+            .in_current_span(),
+        )
+    }
+
+    fn spawn_writer_task<W>(
+        mut writer: W,
+        writer_rx: SharedFrameReceiver,
+        flush_rx: SharedFlushReceiver,
+        coalesce: Option<CoalesceLimits>,
+        auto_flush: bool,
+        graceful_close: bool,
+        failure_hook: Option<WriteFailureHook>,
+    ) -> WriterTask
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        tracing::debug!("Started task 'writer'");
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+        let handle = tokio::task::spawn(async move {
+            // Only one writer task consumes from the channels at a time
+            let mut writer_rx = writer_rx.lock().await;
+            let mut flush_rx = flush_rx.lock().await;
+            let mut pending: Option<Vec<u8>> = None;
+
+            loop {
+                let mut buf = match pending.take() {
+                    Some(frame) => frame,
+                    None => tokio::select! {
+                        biased;
+                        drain = &mut shutdown_rx => {
+                            if let Ok(Some(timeout)) = drain {
+                                Self::drain_queued_frames(&mut writer, &mut writer_rx, timeout)
+                                    .await;
+                            }
+                            break;
+                        }
+                        Some(reply_tx) = flush_rx.recv() => {
+                            let result = Self::write_queued_and_flush(&mut writer, &mut writer_rx).await;
+                            if let (Err(e), Some(hook)) = (&result, &failure_hook) {
+                                hook.on_error(e);
+                            }
+                            let failed = result.is_err();
+                            let _ = reply_tx.send(result);
+                            if failed {
+                                break;
+                            }
+                            continue;
+                        }
+                        frame = writer_rx.recv() => match frame {
+                            Some(frame) => frame,
+                            None => break,
+                        },
+                    },
+                };
+
+                // Combine any immediately available frames into the same write
+                if let Some(limits) = coalesce {
+                    let mut frames = 1;
+                    while frames < limits.max_frames && buf.len() < limits.max_bytes {
+                        let Ok(frame) = writer_rx.try_recv() else {
+                            break;
+                        };
+                        if buf.len() + frame.len() > limits.max_bytes {
+                            pending = Some(frame);
+                            break;
+                        }
+                        buf.extend_from_slice(&frame);
+                        frames += 1;
+                    }
+                    tracing::trace!("Writing {frames} coalesced frame(s)");
+                }
+
+                if let Err(e) = Self::write_all_with_retry(&mut writer, &buf).await {
+                    tracing::error!("Failed to write frame: {e}");
+                    if let Some(ref hook) = failure_hook {
+                        hook.on_error(&e);
+                    }
+                    break;
+                }
+                if auto_flush {
+                    if let Err(e) = writer.flush().await {
+                        tracing::error!("Failed to flush writer: {e}");
+                        if let Some(ref hook) = failure_hook {
+                            hook.on_error(&e);
+                        }
+                        break;
+                    }
+                }
+            }
+            drop(writer_rx);
+            drop(flush_rx);
+
+            // Final close of writer, an abortive close resets the connection once dropped
+            if !graceful_close {
+                tracing::debug!("Skipped writer shutdown for abortive close");
+                return;
+            }
+            match writer.shutdown().await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotConnected => {
+                    tracing::debug!("Writer already shut down: {e}");
+                }
+                Err(e) => tracing::error!("Error on shutdown: {e}"),
+            }
+        }.in_current_span());
+
+        WriterTask {
+            handle,
+            shutdown_tx,
+        }
+    }
+
+    /// Writes all of `buf`, retrying writes which fail with `WouldBlock` or `Interrupted`
+    /// a bounded number of times with a short backoff.
+    async fn write_all_with_retry<W>(writer: &mut W, mut buf: &[u8]) -> std::io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut retries = 0;
+        while !buf.is_empty() {
+            match writer.write(buf).await {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    buf = &buf[n..];
+                    retries = 0;
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted
+                    ) && retries < WRITE_RETRY_ATTEMPTS =>
+                {
+                    retries += 1;
+                    tracing::debug!("Retrying write ({retries}/{WRITE_RETRY_ATTEMPTS}): {e}");
+                    tokio::time::sleep(WRITE_RETRY_DELAY * retries).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes all frames still queued in the channel as a single buffer.
+    fn take_queued_frames(writer_rx: &mut mpsc::Receiver<Vec<u8>>) -> Vec<u8> {
+        let mut buf = Vec::new();
+        while let Ok(frame) = writer_rx.try_recv() {
+            buf.extend_from_slice(&frame);
+        }
+        buf
+    }
+
+    /// Writes all frames queued ahead of a flush request, then flushes the writer.
+    async fn write_queued_and_flush<W>(
+        writer: &mut W,
+        writer_rx: &mut mpsc::Receiver<Vec<u8>>,
+    ) -> std::io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let buf = Self::take_queued_frames(writer_rx);
+        Self::write_all_with_retry(writer, &buf).await?;
+        writer.flush().await
+    }
+
+    /// Writes all frames still queued in the channel as a single write, for up to `timeout`.
+    async fn drain_queued_frames<W>(
+        writer: &mut W,
+        writer_rx: &mut mpsc::Receiver<Vec<u8>>,
+        timeout: Duration,
+    ) where
+        W: AsyncWrite + Unpin,
+    {
+        let buf = Self::take_queued_frames(writer_rx);
+        if buf.is_empty() {
+            return;
+        }
+
+        tracing::debug!("Draining {} queued bytes before close", buf.len());
+        match tokio::time::timeout(timeout, Self::write_all_with_retry(writer, &buf)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::error!("Failed to write queued frames: {e}"),
+            Err(_) => tracing::warn!("Draining queued frames timed out after {timeout:?}"),
+        }
+    }
+
+    /// Returns the watchdog which tears down the read task when heartbeats go unanswered.
+    fn heartbeat_watchdog(
+        config: &SocketConfig,
+        read_task: &tokio::task::JoinHandle<()>,
+        last_heartbeat_response: &Arc<AtomicU64>,
+        exit_reason: &SharedExitReason,
+    ) -> Option<HeartbeatWatchdog> {
+        config
+            .heartbeat_timeout_secs
+            .map(|timeout_secs| HeartbeatWatchdog {
+                timeout_ms: timeout_secs.saturating_mul(1_000),
+                last_response_ms: last_heartbeat_response.clone(),
+                read_task: read_task.abort_handle(),
+                exit_reason: exit_reason.clone(),
+            })
+    }
+
+    fn spawn_heartbeat_task(
+        connection_state: Arc<ConnectionStateCell>,
+        heartbeat: (Duration, Vec<u8>),
+        writer_tx: FrameSender,
+        framing: Option<&Framing>,
+        watchdog: Option<HeartbeatWatchdog>,
+        activity: Option<Arc<SocketCounters>>,
+        payload: Option<HeartbeatPayload>,
+    ) -> tokio::task::JoinHandle<()> {
+        tracing::debug!("Started task 'heartbeat'");
+        let (interval, message) = heartbeat;
+
+        // Without framing the heartbeat payload is sent verbatim
+        let framing = framing.cloned();
+        let encode = move |data: Vec<u8>| match framing {
+            Some(ref framing) => framing
+                .encode(&data)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e)),
+            None => Ok(data),
+        };
+        let message = encode(message);
+
+        tokio::task::spawn(
+            async move {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(e) => {
+                        tracing::error!("Failed to encode heartbeat: {e}");
+                        return;
+                    }
+                };
+
+                loop {
+                    // Sending resets the timer, so only sleep out the rest of the idle gap
+                    let delay = match activity.as_ref().and_then(|a| a.since_last_send()) {
+                        Some(idle) if idle < interval => interval - idle,
+                        _ => interval,
+                    };
+                    tokio::time::sleep(delay).await;
+
+                    match connection_state.load() {
+                        ConnectionState::Active => {
+                            if let Some(ref watchdog) = watchdog {
+                                let last_response =
+                                    watchdog.last_response_ms.load(Ordering::Acquire);
+                                let elapsed = get_atomic_clock_realtime()
+                                    .get_time_ms()
+                                    .saturating_sub(last_response);
+                                if elapsed >= watchdog.timeout_ms {
+                                    tracing::warn!(
+                                    "No heartbeat response for {elapsed}ms, dropping connection"
+                                );
+                                    watchdog.read_task.abort();
+                                    *watchdog.exit_reason.lock().unwrap() =
+                                        Some(ReadExitReason::Io(std::io::ErrorKind::TimedOut));
+                                    break;
+                                }
+                            }
+
+                            // Skip the heartbeat if a send landed during the sleep
+                            if let Some(idle) = activity.as_ref().and_then(|a| a.since_last_send())
+                            {
+                                if idle < interval {
+                                    tracing::trace!("Skipped heartbeat, sent {idle:?} ago");
+                                    continue;
+                                }
+                            }
+
+                            let message = match payload {
+                                Some(ref payload) => match payload().and_then(&encode) {
+                                    Ok(message) => message,
+                                    Err(e) => {
+                                        tracing::error!("Failed to build heartbeat: {e}");
+                                        continue;
+                                    }
+                                },
+                                None => message.clone(),
+                            };
+
+                            if writer_tx.send(message).await.is_err() {
+                                tracing::error!("Failed to send heartbeat: writer task closed");
+                                break;
+                            }
+                            tracing::trace!("Sent heartbeat");
+                        }
+                        ConnectionState::Closed => break,
+                        _ => continue, // Reconnecting or disconnecting
+                    }
+                }
+            }
+            .in_current_span(),
+        )
+    }
+}
+
+/// Tracks the position of each frame decoded on a connection.
+#[derive(Debug, Default)]
+struct PositionTracker {
+    received: u64,
+    consumed: u64,
+    seq: u64,
+}
+
+impl PositionTracker {
+    fn record_received(&mut self, bytes: usize) {
+        self.received += bytes as u64;
+    }
+
+    /// Returns the position of the frame just decoded, given the bytes still buffered after it.
+    fn next(&mut self, buffered: usize) -> FramePosition {
+        let position = FramePosition {
+            seq: self.seq,
+            offset: self.consumed,
+        };
+        self.seq += 1;
+        self.consumed = self.received - buffered as u64;
+        position
+    }
+}
+
+/// Applies the `on_recv` hook, if any, to a received frame.
+///
+/// Returns `None` if the hook fails, after passing the error to `on_error`.
+fn transform_received(
+    data: BytesMut,
+    on_recv: Option<&RecvTransform>,
+    on_error: Option<&RecvErrorCallback>,
+) -> Option<BytesMut> {
+    let Some(on_recv) = on_recv else {
+        return Some(data);
+    };
+    match on_recv(&data) {
+        Ok(transformed) => Some(BytesMut::from(transformed.as_slice())),
+        Err(e) => {
+            tracing::warn!("Skipping received message, `on_recv` hook failed: {e}");
+            if let Some(on_error) = on_error {
+                on_error(&data, &e);
+            }
+            None
+        }
+    }
+}
+
+/// Waits until `limiter`, if any, lets `frames` frames through.
+async fn acquire_send_permits(limiter: Option<&SendRateLimiter>, frames: usize) {
+    if let Some(limiter) = limiter {
+        for _ in 0..frames {
+            limiter.until_key_ready(&()).await;
+        }
+    }
+}
+
+/// Returns `duration` in whole milliseconds, saturating at `u64::MAX`.
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// The identifier of the next client created without a configured `client_id`.
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Returns the span of the client tasks, generating a `client_id` if none is configured.
+fn client_span(config: &mut SocketConfig) -> tracing::Span {
+    let client_id = config
+        .client_id
+        .get_or_insert_with(|| NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed).to_string());
+    tracing::info_span!("socket_client", client = %client_id)
+}
+
+/// Shutdown socket connection.
+///
+/// The client should be explicitly shutdown before dropping. Closing the
+/// connection is an async call, so the drop method can only spawn a best-effort
+/// close of the writer on the runtime the client was created on.
+///
+/// With `drain_writes` frames still queued for the writer are written before it closes.
+/// With a `drain_timeout` the write side is half-closed first and the read task
+/// keeps delivering frames until the server closes its side or the timeout elapses.
+async fn shutdown(
+    read_task: &mut tokio::task::JoinHandle<()>,
+    heartbeat_task: Option<tokio::task::JoinHandle<()>>,
+    writer_task: Option<WriterTask>,
+    drain_writes: bool,
+    drain_timeout: Option<Duration>,
+    linger: Duration,
+    timeout: Duration,
+) {
+    tracing::debug!("Closing");
+
+    if let Some(task) = heartbeat_task {
+        if !task.is_finished() {
+            task.abort();
+            tracing::debug!("Aborted heartbeat task");
+        }
+    }
+
+    close_writer(writer_task, drain_writes, timeout).await;
+
+    match drain_timeout {
+        Some(drain_timeout) if !read_task.is_finished() => {
+            tracing::debug!("Draining reads for up to {drain_timeout:?}");
+            if tokio::time::timeout(drain_timeout, &mut *read_task)
+                .await
+                .is_err()
+            {
+                tracing::warn!("Drain timed out after {drain_timeout:?}");
+            }
+        }
+        // Give the read task a chance to observe EOF after the writer closed
+        _ if !linger.is_zero() && !read_task.is_finished() => {
+            let _ = tokio::time::timeout(linger, &mut *read_task).await;
+        }
+        _ => {}
+    }
+
+    // Abort read task
+    if !read_task.is_finished() {
+        read_task.abort();
+        tracing::debug!("Aborted read task");
+    }
+
+    tracing::debug!("Closed");
+}
+
+/// Signals the writer task to close the writer, aborting it if it does not finish in time.
+///
+/// With `drain_writes` queued frames are written for up to half of `timeout` first, so the
+/// writer is still left time to close.
+async fn close_writer(writer_task: Option<WriterTask>, drain_writes: bool, timeout: Duration) {
+    let Some(WriterTask {
+        handle,
+        shutdown_tx,
+    }) = writer_task
+    else {
+        return;
+    };
+
+    let writer_abort = handle.abort_handle();
+    let drain_timeout = drain_writes.then_some(timeout / 2);
+    if tokio::time::timeout(timeout, async {
+        let _ = shutdown_tx.send(drain_timeout);
+        if let Err(e) = handle.await {
+            tracing::error!("Writer task failed: {e}");
+        }
+    })
+    .await
+    .is_err()
+    {
+        tracing::error!("Shutdown timed out after {timeout:?}");
+    }
+    writer_abort.abort();
+}
+
+impl Drop for SocketClientInner {
+    fn drop(&mut self) {
+        // Cancel heart beat task
+        if let Some(ref handle) = self.heartbeat_task.take() {
+            if !handle.is_finished() {
+                handle.abort();
+            }
+        }
+
+        let Some(writer_task) = self.writer_task.take() else {
+            if !self.read_task.is_finished() {
+                self.read_task.abort();
+            }
+            return;
+        };
+
+        // Best-effort graceful close so the server observes a FIN, the read task
+        // is only aborted once the writer is closed
+        let read_task = self.read_task.abort_handle();
+        let timeout = self.config.shutdown_timeout();
+        match &self.runtime {
+            Some(runtime) => {
+                tracing::debug!("Client dropped without close, closing writer");
+                runtime.spawn(
+                    async move {
+                        close_writer(Some(writer_task), false, timeout).await;
+                        read_task.abort();
+                    }
+                    .instrument(self.span.clone()),
+                );
+            }
+            None => {
+                writer_task.handle.abort();
+                read_task.abort();
+            }
+        }
+    }
+}
+
+impl SocketSender {
+    fn is_active(&self) -> bool {
+        let disconnect = self.disconnect_mode.load(Ordering::SeqCst);
+        self.connection_state.load() == ConnectionState::Active && !disconnect
+    }
+
+    fn is_closed(&self) -> bool {
+        self.connection_state.load() == ConnectionState::Closed
+    }
+
+    /// See [`SocketClient::send_bytes`].
+    pub(crate) async fn send_bytes(&self, data: &[u8]) -> Result<(), SocketError> {
+        if self.is_closed() {
+            return Err(SocketError::Closed);
+        }
+
+        let frame = self.encode(data)?;
+        self.send_encoded(frame, 1).await
+    }
+
+    /// See [`SocketClient::send_bytes_flushed`].
+    pub(crate) async fn send_bytes_flushed(&self, data: &[u8]) -> Result<(), SocketError> {
+        self.send_bytes(data).await?;
+        self.flush().await
+    }
+
+    /// See [`SocketClient::send_many`].
+    pub(crate) async fn send_many(&self, frames: &[&[u8]]) -> Result<(), SocketError> {
+        if self.is_closed() {
+            return Err(SocketError::Closed);
+        }
+        if frames.is_empty() {
+            return Ok(());
+        }
+
+        let mut buf = Vec::new();
+        for data in frames {
+            buf.extend_from_slice(&self.encode(data)?);
+        }
+        self.send_encoded(buf, frames.len()).await
+    }
+
+    /// See [`SocketClient::flush`].
+    pub(crate) async fn flush(&self) -> Result<(), SocketError> {
+        if self.is_closed() {
+            return Err(SocketError::Closed);
+        }
+
+        request_flush_after_queued(
+            &self.flush_tx,
+            self.reconnect_queue.as_deref(),
+            &self.connection_state,
+            self.flush_timeout,
+        )
+        .await
+    }
+
+    /// See [`SocketClient::try_send_bytes`].
+    pub fn try_send_bytes(&self, data: &[u8]) -> Result<(), SocketError> {
+        if self.is_closed() {
+            return Err(SocketError::Closed);
+        }
+        if !self.is_active() {
+            return Err(SocketError::NotConnected);
+        }
+
+        let frame = self.encode(data)?;
+        // Reserve the channel slot first so a busy writer does not consume a rate limit token
+        let permit = self.writer_tx.try_reserve().map_err(|e| match e {
+            mpsc::error::TrySendError::Full(()) => SocketError::Busy,
+            mpsc::error::TrySendError::Closed(()) => SocketError::Closed,
+        })?;
+        if let Some(ref limiter) = self.send_rate_limiter {
+            limiter
+                .check_key(&())
+                .map_err(|_| SocketError::RateLimited)?;
+        }
+        let len = frame.len();
+        permit.send(frame);
+        self.counters.record_sent(len, 1);
+        Ok(())
+    }
+
+    /// Encodes `data` as a frame, after applying the `on_send` hook if any.
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>, SocketError> {
+        let transformed;
+        let data = match &self.on_send {
+            Some(on_send) => {
+                transformed = on_send(data)?;
+                &transformed
+            }
+            None => data,
+        };
+        self.framing
+            .encode(data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e).into())
+    }
+
+    /// Passes encoded bytes holding `frame_count` frames to the writer task.
+    pub(crate) async fn send_encoded(
+        &self,
+        mut frame: Vec<u8>,
+        frame_count: usize,
+    ) -> Result<(), SocketError> {
+        acquire_send_permits(self.send_rate_limiter.as_deref(), frame_count).await;
+        if let Some(ref queue) = self.reconnect_queue {
+            match queue
+                .push_if_reconnecting(&self.connection_state, frame)
+                .await?
+            {
+                Some(unqueued) => frame = unqueued,
+                None => return Ok(()),
+            }
+        }
+
+        if !self.is_active() {
+            self.wait_until_active_for_send().await?;
+        }
+
+        let len = frame.len();
+        self.writer_tx
+            .send(frame)
+            .await
+            .map_err(|_| SocketError::Closed)?;
+        self.counters.record_sent(len, frame_count);
+        Ok(())
+    }
+
+    /// Waits up to `send_wait_active_timeout_ms` for the client to become active.
+    async fn wait_until_active_for_send(&self) -> Result<(), SocketError> {
+        let timeout = self.send_wait_active_timeout;
+        if timeout.is_zero() {
+            return Err(SocketError::NotConnected);
+        }
+
+        tracing::debug!("Waiting for client to become active before sending ({timeout:?})...");
+        SocketClient::await_active(&self.connection_state, &self.disconnect_mode, timeout).await
+    }
+}
+
+/// Handler of a client created with [`SocketClient::connect_stream`], forwarding each
+/// message to its [`SocketStream`].
+///
+/// It runs on a handler queue worker, so blocking while the stream is full stops the
+/// client reading once the queue is full too.
+struct StreamHandler {
+    tx: mpsc::Sender<Bytes>,
+}
+
+impl MessageHandler for StreamHandler {
+    fn handle(&self, data: &[u8]) {
+        self.handle_bytes(Bytes::copy_from_slice(data));
+    }
+
+    fn handle_bytes(&self, data: Bytes) {
+        // The receiver was dropped if the stream is no longer consumed
+        let _ = self.tx.blocking_send(data);
+    }
+
+    fn handle_bytes_with_ts(&self, data: Bytes, _ts_recv: UnixNanos) {
+        self.handle_bytes(data);
+    }
+}
+
+/// Stream of the messages received by a client created with [`SocketClient::connect_stream`].
+///
+/// The stream ends once the client is closed and all received messages were yielded.
+#[derive(Debug)]
+pub struct SocketStream {
+    rx: mpsc::Receiver<Bytes>,
+}
+
+impl futures::Stream for SocketStream {
+    type Item = Bytes;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Sink sending each item as a frame through a [`SocketClient`], see [`SocketClient::sink`].
+///
+/// At most one frame is in flight, it is completed by the next `poll_ready` or `poll_flush`
+/// which stay pending while the client waits to become active, or while a reconnecting client
+/// waits for space in the reconnect queue.
+pub struct SocketSink<'a> {
+    client: &'a SocketClient,
+    pending: Option<BoxFuture<'a, Result<(), SocketError>>>,
+}
+
+impl std::fmt::Debug for SocketSink<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(SocketSink))
+            .field("pending", &self.pending.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SocketSink<'_> {
+    fn poll_pending(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), SocketError>> {
+        let Some(pending) = self.pending.as_mut() else {
+            return std::task::Poll::Ready(Ok(()));
+        };
+        let result = std::task::ready!(pending.as_mut().poll(cx));
+        self.pending = None;
+        std::task::Poll::Ready(result)
+    }
+}
+
+impl futures::Sink<Vec<u8>> for SocketSink<'_> {
+    type Error = SocketError;
+
+    fn poll_ready(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::ready!(self.poll_pending(cx))?;
+        if self.client.is_closed() {
+            return std::task::Poll::Ready(Err(SocketError::Closed));
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn start_send(mut self: std::pin::Pin<&mut Self>, item: Vec<u8>) -> Result<(), Self::Error> {
+        let client = self.client;
+        let frame = client.sender.encode(&item)?;
+        self.pending = Some(Box::pin(client.sender.send_encoded(frame, 1)));
+        Ok(())
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.poll_pending(cx)
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+}
+
+/// Socket client with automatic reconnection.
+///
+/// Dropping the client without closing it aborts the controller task, the
+/// connection is then closed on a best-effort basis.
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
+)]
+pub struct SocketClient {
+    pub(crate) sender: SocketSender,
+    pub(crate) controller_task: tokio::task::JoinHandle<()>,
+    pub(crate) disconnect_mode: Arc<AtomicBool>,
+    pub(crate) drain_timeout_ms: Arc<AtomicU64>,
+    pub(crate) connection_state: Arc<ConnectionStateCell>,
+    pub(crate) closed_notify: Arc<Notify>,
+    pub(crate) counters: Arc<SocketCounters>,
+    pub(crate) read_pause: Arc<ReadPause>,
+    /// Weak so the queue workers exit, and a [`SocketStream`] ends, once the client is closed.
+    pub(crate) handler_queue: Option<std::sync::Weak<HandlerQueue>>,
+    pub(crate) exit_reason: SharedExitReason,
+    pub(crate) shutdown_timeout: Duration,
+    pub(crate) addrs: SharedConnectionAddrs,
+    pub(crate) reconnect_tx: ReconnectRequestSender,
+}
+
+impl Drop for SocketClient {
+    fn drop(&mut self) {
+        // Dropping the controller drops the inner client which closes the connection
+        if !self.controller_task.is_finished() {
+            self.controller_task.abort();
+        }
+    }
+}
+
+/// The state a [`SocketClient`] sends frames with, cloned into the futures of the Python
+/// bindings so they send exactly like the client.
+#[derive(Clone)]
+pub(crate) struct SocketSender {
+    writer_tx: FrameSender,
+    flush_tx: FlushRequestSender,
+    connection_state: Arc<ConnectionStateCell>,
+    disconnect_mode: Arc<AtomicBool>,
+    reconnect_queue: Option<Arc<ReconnectQueue>>,
+    counters: Arc<SocketCounters>,
+    framing: Framing,
+    on_send: Option<SendTransform>,
+    send_rate_limiter: Option<Arc<SendRateLimiter>>,
+    send_wait_active_timeout: Duration,
+    flush_timeout: Duration,
+}
+
+impl SocketClient {
+    pub async fn connect(
+        config: SocketConfig,
+        post_connection: Option<ConnectionCallback>,
+        post_reconnection: Option<ConnectionCallback>,
+        post_disconnection: Option<ConnectionCallback>,
+    ) -> Result<Self, SocketError> {
+        config.validate()?;
+
+        let cancellation_token = config.cancellation_token.clone();
+        let inner = tokio::select! {
+            biased;
+            () = cancelled(cancellation_token.as_ref()) => {
+                tracing::debug!("Connect cancelled");
+                return Err(SocketError::Cancelled);
+            }
+            result = SocketClientInner::connect_url(config) => result?,
+        };
+        Ok(Self::from_inner(
+            inner,
+            post_connection,
+            post_reconnection,
+            post_disconnection,
+        ))
+    }
+
+    /// Connects a socket client whose received messages are yielded by a [`SocketStream`].
+    ///
+    /// The stream replaces the push-based config `handler`, which is not called. Messages
+    /// are buffered for up to `handler_queue_capacity` (default 1024) in the stream and
+    /// again in the handler queue, beyond which a slow consumer applies the
+    /// `handler_queue_overflow` policy, with the default blocking further reads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `handler_concurrency` is above 1, which would reorder the stream,
+    /// or under the same conditions as [`SocketClient::connect`].
+    pub async fn connect_stream(
+        config: SocketConfig,
+        post_connection: Option<ConnectionCallback>,
+        post_reconnection: Option<ConnectionCallback>,
+        post_disconnection: Option<ConnectionCallback>,
+    ) -> Result<(Self, SocketStream), SocketError> {
+        if config.handler_concurrency > 1 {
+            return Err(invalid_config(
+                "`handler_concurrency` above 1 cannot be combined with a stream",
+            )
+            .into());
+        }
+        let capacity = config
+            .handler_queue_capacity
+            .unwrap_or(DEFAULT_STREAM_CAPACITY);
+        let (tx, rx) = mpsc::channel(capacity);
+        let config = SocketConfig {
+            handler: Arc::new(StreamHandler { tx }),
+            handler_queue_capacity: Some(capacity),
+            ..config
+        };
+        let client = Self::connect(
+            config,
+            post_connection,
+            post_reconnection,
+            post_disconnection,
+        )
+        .await?;
+        Ok((client, SocketStream { rx }))
+    }
+
+    /// Creates a socket client over an existing stream.
+    ///
+    /// The stream drives the same read, write, heartbeat and controller tasks
+    /// as a dialed connection. Reconnects are delegated to the config `connector`,
+    /// without one the client closes once the stream terminates.
+    pub async fn from_stream<S>(
+        stream: S,
+        config: SocketConfig,
+        post_connection: Option<ConnectionCallback>,
+        post_reconnection: Option<ConnectionCallback>,
+        post_disconnection: Option<ConnectionCallback>,
+    ) -> Result<Self, SocketError>
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        config.validate()?;
+
+        let connector = config.connector.clone();
+        let mut connection = SocketConnection::new(stream);
+        SocketClientInner::send_on_connect(&config, &mut connection.writer).await?;
+        let inner = SocketClientInner::from_parts(config, connection, connector);
+        Ok(Self::from_inner(
+            inner,
+            post_connection,
+            post_reconnection,
+            post_disconnection,
+        ))
+    }
+
+    fn from_inner(
+        inner: SocketClientInner,
+        post_connection: Option<ConnectionCallback>,
+        post_reconnection: Option<ConnectionCallback>,
+        post_disconnection: Option<ConnectionCallback>,
+    ) -> Self {
+        let disconnect_mode = inner.disconnect_mode.clone();
+        let drain_timeout_ms = Arc::new(AtomicU64::new(0));
+        let connection_state = inner.connection_state.clone();
+        let closed_notify = Arc::new(Notify::new());
+        let counters = inner.counters.clone();
+        let read_pause = inner.read_pause.clone();
+        let handler_queue = inner.dispatch.queue().as_ref().map(Arc::downgrade);
+        let exit_reason = inner.exit_reason.clone();
+        let sender = SocketSender {
+            writer_tx: inner.writer_tx.clone(),
+            flush_tx: inner.flush_tx.clone(),
+            connection_state: connection_state.clone(),
+            disconnect_mode: disconnect_mode.clone(),
+            reconnect_queue: inner.reconnect_queue.clone(),
+            counters: counters.clone(),
+            framing: inner.config.send_framing().clone(),
+            on_send: inner.config.on_send.clone(),
+            send_rate_limiter: inner
+                .config
+                .send_rate_limit
+                .map(|quota| Arc::new(SendRateLimiter::new_with_quota(Some(quota), vec![]))),
+            send_wait_active_timeout: Duration::from_millis(
+                inner
+                    .config
+                    .send_wait_active_timeout_ms
+                    .unwrap_or(DEFAULT_SEND_WAIT_ACTIVE_TIMEOUT_MS),
+            ),
+            // A flush may have to wait for a reconnect in progress to complete
+            flush_timeout: Duration::from_secs(inner.reconnect_timeout_secs),
+        };
+        let shutdown_timeout = inner.config.shutdown_timeout();
+        let addrs = inner.addrs.clone();
+        let on_established = inner.config.on_established.clone();
+        let (reconnect_tx, reconnect_rx) = mpsc::unbounded_channel();
+        let peer_addr = inner.peer_addr();
+
+        let controller_task = Self::spawn_controller_task(
+            inner,
+            disconnect_mode.clone(),
+            drain_timeout_ms.clone(),
+            closed_notify.clone(),
+            reconnect_rx,
+            post_reconnection,
+            post_disconnection,
+        );
+
+        if let Some(handler) = post_connection {
+            handler(ConnectionInfo {
+                attempt: 0,
+                peer_addr,
+                elapsed: Duration::ZERO,
+            });
+        }
+        if let Some(on_established) = on_established {
+            on_established(true);
+        }
+
+        Self {
+            sender,
+            controller_task,
+            disconnect_mode,
+            drain_timeout_ms,
+            connection_state,
+            closed_notify,
+            counters,
+            read_pause,
+            handler_queue,
+            exit_reason,
+            shutdown_timeout,
+            addrs,
+            reconnect_tx,
+        }
+    }
+
+    /// Returns the current connection state.
+    #[inline]
+    #[must_use]
+    pub fn state(&self) -> ConnectionState {
+        self.connection_state.load()
+    }
+
+    /// Returns the address of the remote peer of the current connection, if known.
+    #[must_use]
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.addrs.lock().unwrap().peer
+    }
+
+    /// Returns the local address of the current connection, if known.
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.addrs.lock().unwrap().local
+    }
+
+    /// Returns the URL of the endpoint currently connected to, if known.
+    ///
+    /// With `fallback_urls` configured this identifies which endpoint is active.
+    #[must_use]
+    pub fn active_url(&self) -> Option<String> {
+        self.addrs.lock().unwrap().url.clone()
+    }
+
+    /// Returns the protocol the server selected from `alpn_protocols` for the current
+    /// connection, if any.
+    #[must_use]
+    pub fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        self.addrs.lock().unwrap().alpn_protocol.clone()
+    }
+
+    /// Returns the file descriptor of the current connection's socket, if known, to apply
+    /// socket options the config does not expose.
+    ///
+    /// The descriptor remains owned by the client: it must not be closed, and it becomes
+    /// invalid once the connection is closed or replaced by a reconnect, after which the
+    /// number may be reused by an unrelated file.
+    #[cfg(unix)]
+    #[must_use]
+    pub fn raw_fd(&self) -> Option<RawFd> {
+        self.addrs.lock().unwrap().raw_fd
+    }
+
+    /// Returns a receiver which is notified of every connection state transition.
+    ///
+    /// The receiver always holds the latest state, intermediate states may be
+    /// skipped if the receiver does not keep up with the transitions.
+    #[must_use]
+    pub fn state_changes(&self) -> watch::Receiver<ConnectionState> {
+        self.connection_state.subscribe()
+    }
+
+    /// Check if the client connection is active.
+    ///
+    /// Returns `true` if the client is connected and has not been signalled to disconnect.
+    /// The client will automatically retry connection based on its configuration.
+    #[inline]
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.sender.is_active()
+    }
+
+    /// Check if the client is reconnecting.
+    ///
+    /// Returns `true` if the client lost connection and is attempting to reestablish it.
+    /// The client will automatically retry connection based on its configuration.
+    #[inline]
+    #[must_use]
+    pub fn is_reconnecting(&self) -> bool {
+        self.state() == ConnectionState::Reconnecting
+    }
+
+    /// Check if the client is disconnecting.
+    ///
+    /// Returns `true` if the client is in disconnect mode.
+    #[inline]
+    #[must_use]
+    pub fn is_disconnecting(&self) -> bool {
+        self.disconnect_mode.load(Ordering::SeqCst)
+    }
+
+    /// Check if the client is closed.
+    ///
+    /// Returns `true` if the client has been explicitly disconnected or reached
+    /// maximum reconnection attempts. In this state, the client cannot be reused
+    /// and a new client must be created for further connections.
+    #[inline]
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Close the client.
+    ///
+    /// Controller task will periodically check the disconnect mode
+    /// and shutdown the client if it is not alive. The controller notifies
+    /// on completion so this resolves as soon as the client is closed.
+    pub async fn close(&self) {
+        let _ = self.close_with_timeout(self.shutdown_timeout).await;
+    }
+
+    /// Shuts down the client, reporting whether the teardown completed.
+    ///
+    /// Performs the same teardown as [`SocketClient::close`] for callers which need
+    /// to know if it finished, e.g. a supervisor logging failed shutdowns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SocketError::Timeout`] if the teardown does not complete within the
+    /// configured `shutdown_timeout_secs`, in which case the connection tasks are aborted
+    /// and the client is closed regardless.
+    pub async fn shutdown(&self) -> Result<(), SocketError> {
+        self.close_with_timeout(self.shutdown_timeout).await
+    }
+
+    /// Close the client after draining any data still in flight from the server.
+    ///
+    /// The write side is half-closed so the server sees EOF, while the read task
+    /// keeps passing complete frames to the handler until the server closes the
+    /// connection or `drain_timeout` elapses.
+    pub async fn close_graceful(&self, drain_timeout: Duration) {
+        let drain_timeout_ms = u64::try_from(drain_timeout.as_millis())
+            .unwrap_or(u64::MAX)
+            .max(1);
+        self.drain_timeout_ms
+            .store(drain_timeout_ms, Ordering::SeqCst);
+        let _ = self
+            .close_with_timeout(self.shutdown_timeout + drain_timeout)
+            .await;
+    }
+
+    async fn close_with_timeout(&self, timeout: Duration) -> Result<(), SocketError> {
+        self.disconnect_mode.store(true, Ordering::SeqCst);
+        self.connection_state.mark_disconnecting();
+
+        match tokio::time::timeout(timeout, async {
+            // Register interest before checking state so a notification
+            // sent between the check and the await is not missed
+            let notified = self.closed_notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if !self.is_closed() {
+                notified.await;
+            }
+
+            if !self.controller_task.is_finished() {
+                self.controller_task.abort();
+                tracing::debug!("Aborted controller task");
+            }
+        })
+        .await
+        {
+            Ok(()) => {
+                tracing::debug!("Controller task finished");
+                Ok(())
+            }
+            Err(_) => {
+                tracing::error!("Timeout waiting for controller task to finish");
+                // Aborting drops the inner client, so no task of the connection keeps running
+                // once a caller replaces the client
+                self.controller_task.abort();
+                self.connection_state.store(ConnectionState::Closed);
+                if let Some(ref queue) = self.sender.reconnect_queue {
+                    queue.clear().await;
+                }
+                self.closed_notify.notify_waiters();
+                Err(SocketError::Timeout(format!(
+                    "Shutdown did not complete within {timeout:?}"
+                )))
+            }
+        }
+    }
+
+    /// Forces a fresh connection, replacing the current one.
+    ///
+    /// The client moves to [`ConnectionState::Reconnecting`] and the controller
+    /// reconnects immediately. Concurrent requests are served by the same attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not active or the reconnect attempt fails,
+    /// in which case the client keeps retrying as it would after losing the connection.
+    pub async fn reconnect_now(&self) -> Result<(), SocketError> {
+        Self::request_reconnect(&self.connection_state, &self.reconnect_tx).await
+    }
+
+    pub(crate) async fn request_reconnect(
+        connection_state: &ConnectionStateCell,
+        reconnect_tx: &ReconnectRequestSender,
+    ) -> Result<(), SocketError> {
+        if !connection_state.mark_reconnecting() {
+            return Err(match connection_state.load() {
+                ConnectionState::Closed => SocketError::Closed,
+                _ => SocketError::NotConnected,
+            });
+        }
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        reconnect_tx
+            .send(reply_tx)
+            .map_err(|_| SocketError::Closed)?;
+        reply_rx.await.map_err(|_| SocketError::Closed)?
+    }
+
+    /// Sends `data` as a single frame.
+    ///
+    /// The frame is passed to the writer task, waiting only if the writer channel
+    /// is full. If a reconnect queue is configured and the client is reconnecting,
+    /// the frame is queued and sent once the connection has been re-established.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is closed, does not become active in time,
+    /// the frame cannot be encoded or queued, or the writer task has closed.
+    pub async fn send_bytes(&self, data: &[u8]) -> Result<(), SocketError> {
+        self.sender.send_bytes(data).await
+    }
+
+    /// Sends `data` as a single frame, completing once it has been written and flushed.
+    ///
+    /// [`SocketClient::send_bytes`] completes as soon as the frame is handed to the writer
+    /// task, which may not have written it yet. This instead also waits until the frame,
+    /// and every frame sent before it, has been written to the OS socket buffer and the
+    /// writer flushed, at the cost of a round trip through the writer task. The peer may
+    /// still not have received the data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`SocketClient::send_bytes`] and
+    /// [`SocketClient::flush`].
+    pub async fn send_bytes_flushed(&self, data: &[u8]) -> Result<(), SocketError> {
+        self.sender.send_bytes_flushed(data).await
+    }
+
+    /// Sends each of `frames` as a frame, contiguously on the wire.
+    ///
+    /// All frames are encoded into one buffer which is passed to the writer task
+    /// as a single write, so frames from other senders cannot interleave.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`SocketClient::send_bytes`],
+    /// in which case none of the frames are sent.
+    pub async fn send_many(&self, frames: &[&[u8]]) -> Result<(), SocketError> {
+        self.sender.send_many(frames).await
+    }
+
+    /// Returns a [`SocketSink`] sending each item as a frame, like [`SocketClient::send_bytes`].
+    ///
+    /// Closing the sink does not close the client.
+    #[must_use]
+    pub fn sink(&self) -> SocketSink<'_> {
+        SocketSink {
+            client: self,
+            pending: None,
+        }
+    }
+
+    /// Waits until all frames sent before this call are written and the writer is flushed.
+    ///
+    /// While the client is reconnecting the flush completes on the next connection, after
+    /// any frames queued meanwhile have been written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is closed, if writing or flushing fails, or
+    /// [`SocketError::Timeout`] if the flush does not complete within the reconnect timeout.
+    pub async fn flush(&self) -> Result<(), SocketError> {
+        self.sender.flush().await
+    }
+
+    /// Sends `data` as a frame without awaiting.
+    ///
+    /// Unlike [`SocketClient::send_bytes`], this never waits for the client to become
+    /// active, for space in the writer channel or for the send rate limit. Ordering
+    /// relative to other senders is best-effort under contention.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SocketError::NotConnected`] if the client is not active,
+    /// [`SocketError::Busy`] if the writer channel is full, [`SocketError::RateLimited`]
+    /// if the send rate limit is exhausted, or an error if the client is closed or the
+    /// frame cannot be encoded.
+    pub fn try_send_bytes(&self, data: &[u8]) -> Result<(), SocketError> {
+        self.sender.try_send_bytes(data)
+    }
+
+    /// Waits up to `timeout` for the client to become active.
+    ///
+    /// Resolves immediately if the client is already active, otherwise on the next
+    /// state change to active, e.g. once a reconnect completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SocketError::Timeout`] if the client is not active within `timeout`,
+    /// or [`SocketError::Closed`] if the client closes first.
+    pub async fn wait_until_active(&self, timeout: Duration) -> Result<(), SocketError> {
+        Self::await_active(&self.connection_state, &self.disconnect_mode, timeout).await
+    }
+
+    pub(crate) async fn await_active(
+        connection_state: &ConnectionStateCell,
+        disconnect_mode: &AtomicBool,
+        timeout: Duration,
+    ) -> Result<(), SocketError> {
+        let mut state_rx = connection_state.subscribe();
+        let active = tokio::time::timeout(timeout, async {
+            loop {
+                match state_rx
+                    .wait_for(|state| {
+                        matches!(state, ConnectionState::Active | ConnectionState::Closed)
+                    })
+                    .await
+                    .map(|state| *state)
+                {
+                    Ok(ConnectionState::Active) if !disconnect_mode.load(Ordering::SeqCst) => {
+                        return Ok(());
+                    }
+                    Ok(ConnectionState::Active) => {
+                        // Disconnect requested, the state moves on shortly
+                        state_rx.changed().await.map_err(|_| SocketError::Closed)?;
+                    }
+                    _ => return Err(SocketError::Closed),
+                }
+            }
+        })
+        .await;
+
+        match active {
+            Ok(result) => {
+                tracing::debug!("Client now active");
+                result
+            }
+            Err(_) => Err(SocketError::Timeout(format!(
+                "Client did not become active within {timeout:?}"
+            ))),
+        }
+    }
+
+    /// Stops reading from the socket until [`SocketClient::resume_reading`] is called.
+    ///
+    /// Frames already read are held back from the handler, and the kernel receive
+    /// window fills so the peer is slowed down. Heartbeats continue to be sent, but
+    /// heartbeat responses are not read while paused. The pause persists across
+    /// reconnects.
+    pub fn pause_reading(&self) {
+        self.read_pause.pause();
+    }
+
+    /// Resumes reading from the socket after [`SocketClient::pause_reading`].
+    pub fn resume_reading(&self) {
+        self.read_pause.resume();
+    }
+
+    /// Returns whether reading is paused.
+    #[must_use]
+    pub fn is_reading_paused(&self) -> bool {
+        self.read_pause.is_paused()
+    }
+
+    /// Returns why the read task of the most recently lost connection stopped.
+    ///
+    /// Returns `None` if no connection has been lost yet.
+    #[must_use]
+    pub fn last_exit_reason(&self) -> Option<ReadExitReason> {
+        *self.exit_reason.lock().unwrap()
+    }
+
+    /// Returns the number of items waiting in the handler queue.
+    ///
+    /// An item is a single frame, or a batch when `handler_batch_size` is configured.
+    /// Returns zero when the handler is called by the read task, or once the client is closed.
+    #[must_use]
+    pub fn handler_queue_depth(&self) -> usize {
+        self.handler_queue
+            .as_ref()
+            .and_then(std::sync::Weak::upgrade)
+            .map_or(0, |queue| queue.depth())
+    }
+
+    /// Returns a snapshot of the traffic counters.
+    #[must_use]
+    pub fn stats(&self) -> SocketStats {
+        SocketStats {
+            handler_queue_depth: self.handler_queue_depth() as u64,
+            ..self.counters.snapshot()
+        }
+    }
+
+    /// Returns the seconds elapsed since bytes were last received, across reconnects.
+    ///
+    /// Returns `None` if nothing has been received yet.
+    #[must_use]
+    pub fn seconds_since_last_recv(&self) -> Option<f64> {
+        self.counters
+            .since_last_recv()
+            .map(|elapsed| elapsed.as_secs_f64())
+    }
+
+    /// Returns the seconds elapsed since a message was last sent, across reconnects.
+    ///
+    /// Returns `None` if nothing has been sent yet.
+    #[must_use]
+    pub fn seconds_since_last_send(&self) -> Option<f64> {
+        self.counters
+            .since_last_send()
+            .map(|elapsed| elapsed.as_secs_f64())
+    }
+
+    /// Returns the state of the reconnect circuit breaker.
+    ///
+    /// Always [`CircuitState::Closed`] unless a `circuit_breaker` is configured.
+    #[must_use]
+    pub fn circuit_state(&self) -> CircuitState {
+        self.counters.circuit_state()
+    }
+
+    /// Returns the number of successful reconnects over the lifetime of the client.
+    #[must_use]
+    pub fn reconnect_count(&self) -> u64 {
+        self.counters.reconnect_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the instant the current connection was established.
+    ///
+    /// Set on the initial connect and refreshed on each successful reconnect,
+    /// returns `None` while the client is not active.
+    #[must_use]
+    pub fn connected_since(&self) -> Option<Instant> {
+        if !self.is_active() {
+            return None;
+        }
+        self.counters.connected_since()
+    }
+
+    /// Returns the time elapsed since the current connection was established.
+    ///
+    /// Returns `None` while the client is not active.
+    #[must_use]
+    pub fn uptime(&self) -> Option<Duration> {
+        self.connected_since().map(|since| since.elapsed())
+    }
+
+    fn spawn_controller_task(
+        mut inner: SocketClientInner,
+        disconnect_mode: Arc<AtomicBool>,
+        drain_timeout_ms: Arc<AtomicU64>,
+        closed_notify: Arc<Notify>,
+        mut reconnect_rx: ReconnectRequestReceiver,
+        post_reconnection: Option<ConnectionCallback>,
+        post_disconnection: Option<ConnectionCallback>,
+    ) -> tokio::task::JoinHandle<()> {
+        let span = inner.span.clone();
+        tokio::task::spawn(async move {
+            let check_interval =
+                Duration::from_millis(inner.config.controller_check_interval_ms.unwrap_or(10));
+            let max_reconnection_tries = inner.config.max_reconnection_tries;
+            let max_reconnect_duration = inner
+                .config
+                .max_reconnect_duration_secs
+                .map(Duration::from_secs);
+            let mut backoff = ExponentialBackoff::new(inner.config.backoff);
+            let mut circuit_breaker = inner.config.circuit_breaker.map(CircuitBreaker::new);
+            let mut retry_counter: u64 = 0;
+            let mut disconnected_at: Option<Instant> = None;
+            let cancellation_token = inner.config.cancellation_token.clone();
+            let connection_state = inner.connection_state.clone();
+            let cancel = || {
+                tracing::debug!("Client cancelled");
+                disconnect_mode.store(true, Ordering::SeqCst);
+                connection_state.mark_disconnecting();
+            };
+
+            let mut reconnect_requests = Vec::new();
+
+            loop {
+                tokio::select! {
+                    () = tokio::time::sleep(check_interval) => {}
+                    () = cancelled(cancellation_token.as_ref()), if !disconnect_mode.load(Ordering::SeqCst) => cancel(),
+                    Some(reply_tx) = reconnect_rx.recv() => {
+                        reconnect_requests.push(reply_tx);
+                        while let Ok(reply_tx) = reconnect_rx.try_recv() {
+                            reconnect_requests.push(reply_tx);
+                        }
+                    }
+                }
+
+                // Check if client needs to disconnect
+                let disconnect = disconnect_mode.load(Ordering::SeqCst);
+                let alive = inner.is_alive();
+                let reconnect_requested = !reconnect_requests.is_empty();
+                if !alive && disconnected_at.is_none() {
+                    disconnected_at = Some(Instant::now());
+                }
+                match (disconnect, alive) {
+                    (false, false) if inner.connector.is_none() || inner.is_closed_by_server() => {
+                        if inner.connector.is_none() {
+                            tracing::debug!("Stream terminated and reconnection is disabled");
+                        } else {
+                            tracing::debug!("Connection closed by server, not reconnecting");
+                        }
+                        inner.shutdown(false, None).await;
+                        if let Some(ref handler) = post_disconnection {
+                            let info = ConnectionInfo {
+                                attempt: retry_counter,
+                                peer_addr: inner.peer_addr(),
+                                elapsed: disconnected_at.map_or(Duration::ZERO, |t| t.elapsed()),
+                            };
+                            handler(info);
+                        }
+                        break;
+                    }
+                    (false, true) if reconnect_requested && inner.connector.is_none() => {
+                        inner
+                            .connection_state
+                            .store_unless_closing(ConnectionState::Active);
+                        reply_reconnect_requests(&mut reconnect_requests, || {
+                            Err(SocketError::Io(std::io::Error::new(
+                                std::io::ErrorKind::Unsupported,
+                                "No connector to reconnect with",
+                            )))
+                        });
+                    }
+                    (false, _) if !alive || reconnect_requested => {
+                        let deadline = max_reconnect_duration
+                            .zip(disconnected_at)
+                            .map(|(duration, at)| at + duration);
+                        let attempt = retry_counter + 1;
+                        if let Some(ref on_reconnect_attempt) = inner.config.on_reconnect_attempt {
+                            on_reconnect_attempt(attempt);
+                        }
+                        let attempt_span = tracing::info_span!("reconnect_attempt", attempt);
+                        let started = Instant::now();
+                        let result = tokio::select! {
+                            biased;
+                            () = cancelled(cancellation_token.as_ref()) => {
+                                cancel();
+                                Err(reconnect_cancelled())
+                            }
+                            result = inner.reconnect_until(deadline).instrument(attempt_span.clone()) => result,
+                        };
+                        let elapsed_ms = duration_ms(started.elapsed());
+                        match result {
+                            Ok(()) => {
+                                reply_reconnect_requests(&mut reconnect_requests, || Ok(()));
+                                attempt_span.in_scope(|| {
+                                    tracing::debug!(attempt, elapsed_ms, "Reconnected successfully");
+                                });
+                                let info = ConnectionInfo {
+                                    attempt,
+                                    peer_addr: inner.peer_addr(),
+                                    elapsed: disconnected_at
+                                        .take()
+                                        .map_or(Duration::ZERO, |t| t.elapsed()),
+                                };
+                                retry_counter = 0;
+                                backoff.reset();
+                                if let Some(ref mut breaker) = circuit_breaker {
+                                    breaker.record_success();
+                                    inner.counters.record_circuit_state(breaker.state());
+                                }
+
+                                if let Some(ref handler) = post_reconnection {
+                                    handler(info);
+                                }
+                                if let Some(ref on_established) = inner.config.on_established {
+                                    on_established(false);
+                                }
+                            }
+                            Err(_) if disconnect_mode.load(Ordering::SeqCst) => {
+                                tracing::debug!("Reconnect cancelled by close");
+                            }
+                            Err(e) => {
+                                let error = SocketError::from(e);
+                                if inner.is_fatal(&error) {
+                                    attempt_span.in_scope(|| {
+                                        tracing::error!(
+                                            attempt,
+                                            elapsed_ms,
+                                            "Reconnect failed with fatal error {error}"
+                                        );
+                                    });
+                                    reply_reconnect_requests(&mut reconnect_requests, || {
+                                        Err(error.clone())
+                                    });
+                                    if let Some(ref on_fatal) = inner.config.on_fatal {
+                                        on_fatal(&error);
+                                    }
+                                    break;
+                                }
+
+                                retry_counter += 1;
+                                let expired =
+                                    deadline.is_some_and(|deadline| Instant::now() >= deadline);
+                                let exhausted = expired
+                                    || max_reconnection_tries
+                                        .is_some_and(|max| retry_counter >= max);
+                                reply_reconnect_requests(&mut reconnect_requests, || {
+                                    Err(if exhausted {
+                                        SocketError::MaxRetriesExceeded
+                                    } else {
+                                        error.clone()
+                                    })
+                                });
+
+                                let cooldown = circuit_breaker
+                                    .as_mut()
+                                    .filter(|_| !exhausted)
+                                    .and_then(|breaker| breaker.record_failure(Instant::now().into_std()));
+                                let delay = (!exhausted)
+                                    .then(|| cooldown.unwrap_or_else(|| backoff.next_delay()));
+                                let delay_ms = delay.map(duration_ms);
+                                attempt_span.in_scope(|| {
+                                    if let Some(max) = max_reconnection_tries {
+                                        tracing::warn!(
+                                            attempt,
+                                            elapsed_ms,
+                                            delay_ms,
+                                            "Reconnect failed {error}. Retry {retry_counter}/{max}"
+                                        );
+                                    } else {
+                                        tracing::warn!(
+                                            attempt,
+                                            elapsed_ms,
+                                            delay_ms,
+                                            "Reconnect failed {error}. Retry {retry_counter} (infinite)"
+                                        );
+                                    }
+                                });
+
+                                if exhausted {
+                                    if expired {
+                                        tracing::error!("Reached max reconnect duration");
+                                    } else {
+                                        tracing::error!("Reached max reconnection tries");
+                                    }
+                                    if let Some(ref on_give_up) = inner.config.on_give_up {
+                                        on_give_up(&error, retry_counter);
+                                    }
+                                    break;
+                                }
+
+                                if let Some(cooldown) = cooldown {
+                                    tracing::warn!(
+                                        "Circuit breaker open, suspending reconnects for {cooldown:?}"
+                                    );
+                                    inner.counters.record_circuit_state(CircuitState::Open);
+                                }
+
+                                let delay = delay.unwrap_or_default();
+                                tracing::debug!("Waiting {delay:?} before next reconnect attempt");
+                                let wake_at = Instant::now() + delay;
+                                let wake_at =
+                                    deadline.map_or(wake_at, |deadline| wake_at.min(deadline));
+                                tokio::select! {
+                                    () = cancelled(cancellation_token.as_ref()) => cancel(),
+                                    () = tokio::time::sleep_until(wake_at) => {}
+                                }
+
+                                if let Some(ref mut breaker) = circuit_breaker {
+                                    if breaker.state() == CircuitState::Open {
+                                        tracing::debug!(
+                                            "Circuit breaker half-open, allowing a trial reconnect"
+                                        );
+                                        breaker.half_open();
+                                        inner.counters.record_circuit_state(breaker.state());
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    (true, true) => {
+                        tracing::debug!("Shutting down inner client");
+                        let drain_timeout = match drain_timeout_ms.load(Ordering::SeqCst) {
+                            0 => None,
+                            ms => Some(Duration::from_millis(ms)),
+                        };
+                        inner
+                            .shutdown(inner.config.drain_on_close, drain_timeout)
+                            .await;
+                        if let Some(ref handler) = post_disconnection {
+                            let info = ConnectionInfo {
+                                attempt: retry_counter,
+                                peer_addr: inner.peer_addr(),
+                                elapsed: disconnected_at.map_or(Duration::ZERO, |t| t.elapsed()),
+                            };
+                            handler(info);
+                        }
+                        break;
+                    }
+                    (true, false) => {
+                        tracing::debug!("Inner client is disconnected");
+                        tracing::debug!("Shutting down inner client to clean up running tasks");
+                        inner.shutdown(false, None).await;
+                        break;
+                    }
+                    _ => (),
+                }
+                reply_reconnect_requests(&mut reconnect_requests, || Err(SocketError::Closed));
+            }
+            inner.connection_state.store(ConnectionState::Closed);
+            if let Some(ref queue) = inner.reconnect_queue {
+                queue.clear().await;
+            }
+            closed_notify.notify_waiters();
+        }.instrument(span))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+#[cfg(target_os = "linux")] // Only run network tests on Linux (CI stability)
+mod tests {
+    use std::{ffi::CString, net::TcpListener, num::NonZeroU32};
+
+    use futures::StreamExt;
+    use pyo3::{prelude::*, prepare_freethreaded_python, types::PyDict};
+    use rstest::rstest;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+        task,
+        time::{sleep, Duration},
+    };
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::framing::Endian;
+    use crate::python::socket::py_connection_callback;
+
+    #[rstest]
+    #[case(ConnectionState::Active, 0, "ACTIVE")]
+    #[case(ConnectionState::Reconnecting, 1, "RECONNECTING")]
+    #[case(ConnectionState::Disconnecting, 2, "DISCONNECTING")]
+    #[case(ConnectionState::Closed, 3, "CLOSED")]
+    fn test_connection_state_conversions(
+        #[case] state: ConnectionState,
+        #[case] value: u8,
+        #[case] display: &str,
+    ) {
+        assert_eq!(u8::from(state), value);
+        assert_eq!(ConnectionState::from(value), state);
+        assert_eq!(state.to_string(), display);
+    }
+
+    fn create_handler() -> PyObject {
+        let code_raw = r#"
+class Counter:
+    def __init__(self):
+        self.count = 0
+        self.check = False
+
+    def handler(self, bytes):
+        msg = bytes.decode()
+        if msg == 'ping':
+            self.count += 1
+        elif msg == 'heartbeat message':
+            self.check = True
+
+    def get_check(self):
+        return self.check
+
+    def get_count(self):
+        return self.count
+
+counter = Counter()
+"#;
+        let code = CString::new(code_raw).unwrap();
+        let filename = CString::new("test".to_string()).unwrap();
+        let module = CString::new("test".to_string()).unwrap();
+        Python::with_gil(|py| {
+            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
+            let counter = pymod.getattr("counter").unwrap().into_py(py);
+            let handler = counter.getattr(py, "handler").unwrap().into_py(py);
+            handler
+        })
+    }
+
+    type ReceivedFrames = Arc<std::sync::Mutex<Vec<Vec<u8>>>>;
+
+    fn create_rust_handler() -> (Arc<dyn MessageHandler>, ReceivedFrames) {
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let handler = move |data: &[u8]| received_clone.lock().unwrap().push(data.to_vec());
+        (Arc::new(handler), received)
+    }
+
+    fn test_config(port: u16, handler: Arc<dyn MessageHandler>) -> SocketConfig {
+        SocketConfig {
+            url: format!("127.0.0.1:{port}"),
+            mode: Mode::Plain,
+            framing: Framing::Delimiter(b"\r\n".to_vec()),
+            handler,
+            heartbeat: None,
+            reconnect_timeout_secs: None,
+            max_reconnection_tries: None,
+            backoff: BackoffPolicy::default(),
+            controller_check_interval_ms: None,
+            handler_batch_size: None,
+            max_message_size: None,
+            on_overflow: None,
+            handler_queue_capacity: None,
+            handler_queue_overflow: HandlerQueuePolicy::Block,
+            reconnect_queue_capacity: None,
+            reconnect_queue_overflow: QueueOverflowPolicy::Error,
+            max_coalesce_bytes: None,
+            max_coalesce_frames: None,
+            connector: None,
+            deliver_unterminated_on_eof: false,
+            read_idle_timeout_secs: None,
+            heartbeat_timeout_secs: None,
+            heartbeat_response: None,
+            heartbeat_always_send: false,
+            tcp_options: TcpOptions::default(),
+            connect_timeout_secs: None,
+            client_cert_pem: None,
+            client_key_pem: None,
+            root_ca_pem: None,
+            danger_accept_invalid_certs: false,
+            tls_sni: None,
+            proxy: None,
+            fallback_urls: Vec::new(),
+            url_provider: None,
+            on_connect_send: None,
+            send_wait_active_timeout_ms: None,
+            shutdown_linger_ms: None,
+            reconnect_on_eof: true,
+            is_fatal_error: None,
+            on_fatal: None,
+            on_give_up: None,
+            max_reconnect_duration_secs: None,
+            send_framing: None,
+            handler_receive_ts: false,
+            handler_queue_high_water: None,
+            handler_queue_low_water: None,
+            on_backpressure: None,
+            drain_on_close: true,
+            cancellation_token: None,
+            auto_flush: true,
+            client_id: None,
+            shutdown_timeout_secs: None,
+            send_rate_limit: None,
+            on_send: None,
+            on_recv: None,
+            on_recv_error: None,
+            heartbeat_payload: None,
+            heartbeat_append_suffix: true,
+            on_established: None,
+            resolver: None,
+            bind_address: None,
+            tls_handshake_timeout_secs: None,
+            alpn_protocols: Vec::new(),
+            pinned_cert_sha256: None,
+            read_buffer_capacity: None,
+            handler_yield_every: None,
+            circuit_breaker: None,
+            on_reconnect_attempt: None,
+            handler_receive_position: false,
+            handler_concurrency: 1,
+            on_reconnect_send: None,
+            max_handler_errors: None,
+            on_handler_error: None,
+        }
+    }
+
+    fn bind_test_server() -> (u16, TcpListener) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind ephemeral port");
+        let port = listener.local_addr().unwrap().port();
+        // Required for `tokio::net::TcpListener::from_std`, otherwise `accept` blocks the runtime
+        listener.set_nonblocking(true).unwrap();
+        (port, listener)
+    }
+
+    async fn run_echo_server(mut socket: TcpStream) {
+        let mut buf = Vec::new();
+        loop {
+            match socket.read_buf(&mut buf).await {
+                Ok(0) => {
+                    break;
+                }
+                Ok(_n) => {
+                    while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
+                        let mut line = buf.drain(..idx + 2).collect::<Vec<u8>>();
+                        // Remove trailing \r\n
+                        line.truncate(line.len() - 2);
+
+                        if line == b"close" {
+                            let _ = socket.shutdown().await;
+                            return;
+                        }
+
+                        let mut echo_data = line;
+                        echo_data.extend_from_slice(b"\r\n");
+                        if socket.write_all(&echo_data).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Server read error: {e}");
+                    break;
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_basic_send_receive() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let config = SocketConfig {
+            max_reconnection_tries: Some(1),
+            ..test_config(port, Arc::new(create_handler()))
+        };
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Client connect failed unexpectedly");
+
+        client.send_bytes(b"Hello").await.unwrap();
+        client.send_bytes(b"World").await.unwrap();
+
+        // Wait a bit for the server to echo them back
+        sleep(Duration::from_millis(100)).await;
+
+        client.send_bytes(b"close").await.unwrap();
+        server_task.await.unwrap();
+        assert!(!client.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_fail_exhausted() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        drop(listener); // We drop it immediately -> no server is listening
+
+        let config = SocketConfig {
+            max_reconnection_tries: Some(2),
+            ..test_config(port, Arc::new(create_handler()))
+        };
+
+        let client_res = SocketClient::connect(config, None, None, None).await;
+        assert!(
+            client_res.is_err(),
+            "Should fail quickly with no server listening"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_python_handler_shared_across_reconnects() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let handler = Arc::new(create_handler());
+        let client = SocketClient::connect(test_config(port, handler.clone()), None, None, None)
+            .await
+            .unwrap();
+        let handler_refs = Arc::strong_count(&handler);
+
+        let get_count = || {
+            Python::with_gil(|py| {
+                handler
+                    .getattr(py, "__self__")
+                    .unwrap()
+                    .call_method0(py, "get_count")
+                    .unwrap()
+                    .extract::<u64>(py)
+                    .unwrap()
+            })
+        };
+        for expected in 1..=3 {
+            if expected > 1 {
+                client.reconnect_now().await.unwrap();
+            }
+            client.send_bytes(b"ping").await.unwrap();
+            tokio::time::timeout(Duration::from_secs(2), async {
+                while get_count() < expected {
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("Handler stopped receiving frames after reconnect");
+        }
+
+        assert_eq!(client.reconnect_count(), 2);
+        assert_eq!(Arc::strong_count(&handler), handler_refs);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_user_disconnect() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.try_read(&mut buf);
+
+            loop {
+                sleep(Duration::from_secs(1)).await;
+            }
+        });
+
+        let config = test_config(port, Arc::new(create_handler()));
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(client.state(), ConnectionState::Active);
+        client.close().await;
+        assert!(client.is_closed());
+        assert_eq!(client.state(), ConnectionState::Closed);
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received2 = received.clone();
+
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+
+            let mut buf = Vec::new();
+            loop {
+                match socket.try_read_buf(&mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
+                            let mut line = buf.drain(..idx + 2).collect::<Vec<u8>>();
+                            line.truncate(line.len() - 2);
+                            received2.lock().await.push(line);
+                        }
+                    }
+                    Err(_) => {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                }
+            }
+        });
+
+        // Heartbeat every 1 second
+        let heartbeat = Some((Duration::from_secs(1), b"ping".to_vec()));
+
+        let config = SocketConfig {
+            heartbeat,
+            ..test_config(port, Arc::new(create_handler()))
+        };
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // Wait ~3 seconds to collect some heartbeats
+        sleep(Duration::from_secs(3)).await;
+
+        {
+            let lock = received.lock().await;
+            let pings = lock
+                .iter()
+                .filter(|line| line == &&b"ping".to_vec())
+                .count();
+            assert!(
+                pings >= 2,
+                "Expected at least 2 heartbeat pings; got {pings}"
+            );
+        }
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_python_threads_progress_during_delivery() {
+        prepare_freethreaded_python();
+
+        const FRAMES: usize = 2_000;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let burst: Vec<u8> = (0..FRAMES)
+                .flat_map(|i| format!("msg{i}\r\n").into_bytes())
+                .collect();
+            socket.write_all(&burst).await.unwrap();
+            sleep(Duration::from_secs(10)).await;
+        });
+
+        let code_raw = r#"
+import threading
+import time
+
+class Progress:
+    def __init__(self):
+        self.ticks = 0
+        self.seen = []
+        self.stop = False
+        self.thread = threading.Thread(target=self.run, daemon=True)
+
+    def run(self):
+        while not self.stop:
+            self.ticks += 1
+            time.sleep(0.0001)
+
+    def handler(self, data):
+        self.seen.append(self.ticks)
+
+progress = Progress()
+progress.thread.start()
+"#;
+        let code = CString::new(code_raw).unwrap();
+        let filename = CString::new("test".to_string()).unwrap();
+        let module = CString::new("test".to_string()).unwrap();
+        let (progress, handler) = Python::with_gil(|py| {
+            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
+            let progress = pymod.getattr("progress").unwrap().into_py(py);
+            let handler = progress.getattr(py, "handler").unwrap();
+            (progress, Arc::new(handler))
+        });
+
+        let config = SocketConfig {
+            handler_yield_every: Some(16),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let seen_len = || {
+            Python::with_gil(|py| {
+                progress
+                    .getattr(py, "seen")
+                    .unwrap()
+                    .bind(py)
+                    .len()
+                    .unwrap()
+            })
+        };
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while seen_len() < FRAMES {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let (first, last) = Python::with_gil(|py| {
+            progress.setattr(py, "stop", true).unwrap();
+            progress
+                .getattr(py, "thread")
+                .unwrap()
+                .call_method0(py, "join")
+                .unwrap();
+            let seen: Vec<u64> = progress.getattr(py, "seen").unwrap().extract(py).unwrap();
+            (seen[0], seen[FRAMES - 1])
+        });
+        assert!(
+            last > first,
+            "Python thread made no progress while frames were delivered"
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_python_handler_error() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let code_raw = r#"
+def handler(bytes_data):
+    txt = bytes_data.decode()
+    if "ERR" in txt:
+        raise ValueError("Simulated error in handler")
+    return
+"#;
+        let code = CString::new(code_raw).unwrap();
+        let filename = CString::new("test".to_string()).unwrap();
+        let module = CString::new("test".to_string()).unwrap();
+
+        let handler = Python::with_gil(|py| {
+            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
+            let func = pymod.getattr("handler").unwrap();
+            Arc::new(func.into_py(py))
+        });
+
+        let config = SocketConfig {
+            max_reconnection_tries: Some(1),
+            ..test_config(port, handler)
+        };
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Client connect failed unexpectedly");
+
+        client.send_bytes(b"hello").await.unwrap();
+        sleep(Duration::from_millis(100)).await;
+
+        client.send_bytes(b"ERR").await.unwrap();
+        sleep(Duration::from_secs(1)).await;
+
+        assert!(client.is_active());
+
+        client.close().await;
+
+        assert!(client.is_closed());
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_max_handler_errors_drops_connection() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let accepted_clone = accepted.clone();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                // Only the first connection receives frames
+                if accepted_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                    socket
+                        .write_all(b"a\r\nb\r\nc\r\nd\r\ne\r\n")
+                        .await
+                        .unwrap();
+                }
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let code_raw = r#"
+def handler(bytes_data):
+    raise ValueError("boom")
+"#;
+        let code = CString::new(code_raw).unwrap();
+        let filename = CString::new("test".to_string()).unwrap();
+        let module = CString::new("test".to_string()).unwrap();
+        let handler = Python::with_gil(|py| {
+            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
+            let func = pymod.getattr("handler").unwrap();
+            Arc::new(func.into_py(py))
+        });
+
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let errors_clone = errors.clone();
+        let config = SocketConfig {
+            max_handler_errors: Some(3),
+            on_handler_error: Some(Arc::new(move |error: &str| {
+                errors_clone.lock().unwrap().push(error.to_string());
+            })),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while accepted.load(Ordering::SeqCst) < 2 || !client.is_active() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect after repeated handler errors");
+
+        // Frames after the third error are not delivered to the broken handler
+        let errors = errors.lock().unwrap().clone();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().all(|e| e.contains("ValueError: boom")));
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    fn test_handler_error_reported_only_for_failed_call() {
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let errors_clone = errors.clone();
+        let handler: Arc<dyn MessageHandler> = Arc::new(|data: &[u8]| {
+            if data == b"bad" {
+                report_handler_error("bad frame");
+            }
+        });
+        let config = SocketConfig {
+            max_handler_errors: Some(2),
+            on_handler_error: Some(Arc::new(move |error: &str| {
+                errors_clone.lock().unwrap().push(error.to_string());
+            })),
+            ..test_config(0, handler)
+        };
+        let target = HandlerTarget::new(&config);
+        let counters = target.errors.clone().unwrap();
+
+        let ts = UnixNanos::default();
+        target.handle(Bytes::from_static(b"bad"), ts, None);
+        target.handle(Bytes::from_static(b"good"), ts, None);
+        target.handle(Bytes::from_static(b"bad"), ts, None);
+        assert!(!counters.exceeded());
+        target.handle(Bytes::from_static(b"bad"), ts, None);
+        assert!(counters.exceeded());
+        assert_eq!(errors.lock().unwrap().len(), 3);
+
+        // A report outside of a handler call is not attributed to the next call
+        report_handler_error("stray");
+        counters.clear();
+        target.handle(Bytes::from_static(b"good"), ts, None);
+        assert!(!counters.exceeded());
+        assert_eq!(errors.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_zero_intervals_rejected() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let config = SocketConfig {
+            controller_check_interval_ms: Some(0),
+            ..test_config(port, Arc::new(create_handler()))
+        };
+
+        let result = SocketClient::connect(config.clone(), None, None, None).await;
+        assert!(result.is_err(), "Zero check interval should be rejected");
+
+        let config = SocketConfig {
+            controller_check_interval_ms: None,
+            backoff: BackoffPolicy {
+                delay_initial: Duration::ZERO,
+                ..BackoffPolicy::default()
+            },
+            ..config
+        };
+        let result = SocketClient::connect(config, None, None, None).await;
+        assert!(
+            result.is_err(),
+            "Zero initial reconnect delay should be rejected"
+        );
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn test_custom_check_interval_honored() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (_socket, _) = listener.accept().await.unwrap();
+            loop {
+                sleep(Duration::from_secs(1)).await;
+            }
+        });
+
+        let config = SocketConfig {
+            controller_check_interval_ms: Some(500),
+            ..test_config(port, Arc::new(create_handler()))
+        };
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // The controller only observes the disconnect request on its first wakeup,
+        // which happens one check interval after the client was created.
+        let start = tokio::time::Instant::now();
+        client.close().await;
+        let elapsed = start.elapsed();
+
+        assert!(client.is_closed());
+        assert!(
+            elapsed >= Duration::from_millis(400),
+            "Controller woke up earlier than the check interval: {elapsed:?}"
+        );
+        assert!(
+            elapsed < Duration::from_millis(1_500),
+            "Controller woke up much later than the check interval: {elapsed:?}"
+        );
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_close_returns_promptly_after_controller_exits() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (_socket, _) = listener.accept().await.unwrap();
+            loop {
+                sleep(Duration::from_secs(1)).await;
+            }
+        });
+
+        let config = test_config(port, Arc::new(create_handler()));
+
+        let client = Arc::new(
+            SocketClient::connect(config, None, None, None)
+                .await
+                .unwrap(),
+        );
+
+        let client_clone = client.clone();
+        let close_task = task::spawn(async move {
+            client_clone.close().await;
+            tokio::time::Instant::now()
+        });
+
+        // Observe when the controller marks the client closed
+        while !client.is_closed() {
+            tokio::task::yield_now().await;
+        }
+        let closed_at = tokio::time::Instant::now();
+        let returned_at = close_task.await.unwrap();
+
+        let lag = returned_at.saturating_duration_since(closed_at);
+        assert!(
+            lag < Duration::from_millis(5),
+            "close() returned {lag:?} after the controller exited"
+        );
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_observe_reconnect() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+
+            // Drop the first connection to force a reconnect
+            let (socket, _) = listener.accept().await.unwrap();
+            drop(socket);
+
+            let (socket, _) = listener.accept().await.unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let config = test_config(port, Arc::new(create_handler()));
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        let mut state_rx = client.state_changes();
+        let mut observed = Vec::new();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while observed.last() != Some(&ConnectionState::Active) {
+                state_rx.changed().await.unwrap();
+                observed.push(*state_rx.borrow_and_update());
+            }
+        })
+        .await
+        .expect("Timed out waiting for reconnect");
+
+        assert_eq!(
+            observed,
+            vec![ConnectionState::Reconnecting, ConnectionState::Active]
+        );
+
+        client.close().await;
+        assert_eq!(*state_rx.borrow(), ConnectionState::Closed);
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_rust_handler_counts_frames() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = test_config(port, handler);
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        for i in 0..5 {
+            client
+                .send_bytes(format!("msg{i}").as_bytes())
+                .await
+                .unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 5 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let expected: Vec<Vec<u8>> = (0..5).map(|i| format!("msg{i}").into_bytes()).collect();
+        assert_eq!(*received.lock().unwrap(), expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_handler_receives_frames_without_copying() {
+        #[derive(Default)]
+        struct BytesRecorder(std::sync::Mutex<Vec<Bytes>>);
+
+        impl MessageHandler for BytesRecorder {
+            fn handle(&self, _data: &[u8]) {
+                panic!("handler should be called with bytes");
+            }
+
+            fn handle_bytes(&self, data: Bytes) {
+                self.0.lock().unwrap().push(data);
+            }
+        }
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            socket
+                .write_all(b"first\r\nsecond\r\nthird\r\n")
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(1)).await;
+        });
+
+        let recorder = Arc::new(BytesRecorder::default());
+        let client = SocketClient::connect(test_config(port, recorder.clone()), None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while recorder.0.lock().unwrap().len() < 3 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let frames = recorder.0.lock().unwrap().clone();
+        assert_eq!(frames, [&b"first"[..], b"second", b"third"]);
+        // Frames of a single read are adjacent slices of the read buffer, apart from the suffix
+        for pair in frames.windows(2) {
+            let next = pair[0].as_ptr().wrapping_add(pair[0].len() + 2);
+            assert_eq!(
+                pair[1].as_ptr(),
+                next,
+                "frame was copied out of the read buffer"
+            );
+        }
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_handler_batches() {
+        #[derive(Default)]
+        struct BatchRecorder {
+            batches: std::sync::Mutex<Vec<usize>>,
+            frames: std::sync::Mutex<Vec<Vec<u8>>>,
+        }
+
+        impl MessageHandler for BatchRecorder {
+            fn handle(&self, data: &[u8]) {
+                self.handle_batch(&[Bytes::copy_from_slice(data)]);
+            }
+
+            fn handle_batch(&self, frames: &[Bytes]) {
+                self.batches.lock().unwrap().push(frames.len());
+                self.frames
+                    .lock()
+                    .unwrap()
+                    .extend(frames.iter().map(|data| data.to_vec()));
+            }
+        }
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+
+            // Send a burst of lines in a single write
+            let burst: Vec<u8> = (0..100)
+                .flat_map(|i| format!("line{i}\r\n").into_bytes())
+                .collect();
+            socket.write_all(&burst).await.unwrap();
+
+            loop {
+                sleep(Duration::from_secs(1)).await;
+            }
+        });
+
+        let recorder = Arc::new(BatchRecorder::default());
+        let config = SocketConfig {
+            handler_batch_size: Some(10),
+            ..test_config(port, recorder.clone())
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while recorder.frames.lock().unwrap().len() < 100 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let batches = recorder.batches.lock().unwrap().clone();
+        assert!(batches.iter().all(|&len| len <= 10));
+        assert!(
+            batches.len() < 100,
+            "Expected batched delivery, got {} handler calls",
+            batches.len()
+        );
+
+        let expected: Vec<Vec<u8>> = (0..100).map(|i| format!("line{i}").into_bytes()).collect();
+        assert_eq!(*recorder.frames.lock().unwrap(), expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_max_message_size_drops_connection() {
+        const MAX_MESSAGE_SIZE: usize = 64;
+
+        let (port, listener) = bind_test_server();
+        let (eof_tx, eof_rx) = tokio::sync::oneshot::channel();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket
+                .write_all(&[b'x'; MAX_MESSAGE_SIZE + 1])
+                .await
+                .unwrap();
+
+            let mut buf = [0u8; 64];
+            let n = socket.read(&mut buf).await.unwrap();
+            let _ = eof_tx.send(n);
+
+            // Hold any reconnections open
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        let overflows = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let overflows_clone = overflows.clone();
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            max_message_size: Some(MAX_MESSAGE_SIZE),
+            on_overflow: Some(Arc::new(move |size| {
+                overflows_clone.lock().unwrap().push(size);
+            })),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let n = tokio::time::timeout(Duration::from_secs(2), eof_rx)
+            .await
+            .expect("Timed out waiting for connection to drop")
+            .unwrap();
+
+        assert_eq!(n, 0, "Expected EOF on the oversized connection");
+        assert_eq!(*overflows.lock().unwrap(), vec![MAX_MESSAGE_SIZE + 1]);
+        assert!(received.lock().unwrap().is_empty());
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_length_prefixed_framing_round_trip() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            // Echo the raw bytes back a few at a time so frames span reads
+            let mut buf = [0u8; 3];
+            while let Ok(n) = socket.read(&mut buf).await {
+                if n == 0 || socket.write_all(&buf[..n]).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            framing: Framing::LengthPrefixed {
+                header_bytes: 4,
+                endian: Endian::Big,
+                includes_header: false,
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let payloads = [b"ping\r\n".to_vec(), Vec::new(), vec![0xAB; 1_000]];
+        for payload in &payloads {
+            client.send_bytes(payload).await.unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < payloads.len() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        assert_eq!(*received.lock().unwrap(), payloads);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(9)]
+    fn test_invalid_length_prefix_rejected(#[case] header_bytes: usize) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            framing: Framing::LengthPrefixed {
+                header_bytes,
+                endian: Endian::Little,
+                includes_header: false,
+            },
+            ..test_config(0, handler)
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[rstest]
+    #[case(
+        BackoffPolicy { delay_max: Duration::from_millis(1), ..BackoffPolicy::default() },
+        "`backoff.delay_max` must not be below `backoff.delay_initial`"
+    )]
+    #[case(
+        BackoffPolicy { factor: f64::NAN, ..BackoffPolicy::default() },
+        "`backoff.factor` must be finite and at least 1"
+    )]
+    #[case(
+        BackoffPolicy { factor: f64::INFINITY, ..BackoffPolicy::default() },
+        "`backoff.factor` must be finite and at least 1"
+    )]
+    #[case(
+        BackoffPolicy { factor: 0.5, ..BackoffPolicy::default() },
+        "`backoff.factor` must be finite and at least 1"
+    )]
+    #[case(
+        BackoffPolicy { jitter: Some(-0.1), ..BackoffPolicy::default() },
+        "`backoff.jitter` must be between 0 and 1"
+    )]
+    #[case(
+        BackoffPolicy { jitter: Some(1.5), ..BackoffPolicy::default() },
+        "`backoff.jitter` must be between 0 and 1"
+    )]
+    fn test_invalid_backoff_rejected(#[case] backoff: BackoffPolicy, #[case] expected: &str) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            backoff,
+            ..test_config(0, handler)
+        };
+
+        let err = config.validate().unwrap_err();
+
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[rstest]
+    fn test_handler_concurrency_with_raw_framing_rejected() {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            framing: Framing::Raw,
+            handler_queue_capacity: Some(16),
+            handler_concurrency: 4,
+            ..test_config(0, handler)
+        };
+
+        let err = config.validate().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "`handler_concurrency` above 1 cannot be combined with `Framing::Raw`"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_queue_replays_frames_in_order() {
+        let (port, listener) = bind_test_server();
+        let (restart_tx, restart_rx) = tokio::sync::oneshot::channel::<()>();
+        let server_task = task::spawn(async move {
+            // Accept then take the server down entirely
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            drop(socket);
+            drop(listener);
+
+            restart_rx.await.unwrap();
+            let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{port}"))
+                .await
+                .unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(50),
+                delay_max: Duration::from_millis(50),
+                factor: 1.0,
+                jitter: None,
+            },
+            reconnect_queue_capacity: Some(10),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !client.is_reconnecting() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client never started reconnecting");
+
+        for msg in [b"first".as_slice(), b"second", b"third"] {
+            client.send_bytes(msg).await.unwrap();
+        }
+        restart_tx.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while !client.is_active() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect");
+        client.send_bytes(b"fourth").await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 4 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for replayed frames");
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                b"first".to_vec(),
+                b"second".to_vec(),
+                b"third".to_vec(),
+                b"fourth".to_vec()
+            ]
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(QueueOverflowPolicy::Error, Err(()), vec![b"a".to_vec(), b"b".to_vec()])]
+    #[case(QueueOverflowPolicy::DropOldest, Ok(()), vec![b"b".to_vec(), b"c".to_vec()])]
+    #[tokio::test]
+    async fn test_reconnect_queue_overflow_policy(
+        #[case] policy: QueueOverflowPolicy,
+        #[case] expected_result: Result<(), ()>,
+        #[case] expected_frames: Vec<Vec<u8>>,
+    ) {
+        let queue = ReconnectQueue::new(2, policy);
+        let state = ConnectionStateCell::new(ConnectionState::Reconnecting);
+
+        for frame in [b"a", b"b"] {
+            assert_eq!(
+                queue
+                    .push_if_reconnecting(&state, frame.to_vec())
+                    .await
+                    .unwrap(),
+                None
+            );
+        }
+        let result = queue.push_if_reconnecting(&state, b"c".to_vec()).await;
+
+        assert_eq!(result.map(|_| ()).map_err(|_| ()), expected_result);
+        assert_eq!(
+            queue
+                .frames
+                .lock()
+                .await
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>(),
+            expected_frames
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_queue_block_waits_for_flush() {
+        let queue = Arc::new(ReconnectQueue::new(1, QueueOverflowPolicy::Block));
+        let state = Arc::new(ConnectionStateCell::new(ConnectionState::Reconnecting));
+        queue
+            .push_if_reconnecting(&state, b"a".to_vec())
+            .await
+            .unwrap();
+
+        let blocked = {
+            let queue = queue.clone();
+            let state = state.clone();
+            task::spawn(async move { queue.push_if_reconnecting(&state, b"b".to_vec()).await })
+        };
+        sleep(Duration::from_millis(50)).await;
+        assert!(!blocked.is_finished());
+
+        // Flushing activates the connection, so the blocked frame is handed back to send directly
+        queue.frames.lock().await.clear();
+        state.store(ConnectionState::Active);
+        queue.flushed.notify_waiters();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), blocked)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.unwrap(), Some(b"b".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_senders_preserve_ordering() {
+        const SENDERS: usize = 4;
+        const MESSAGES: usize = 200;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = Arc::new(
+            SocketClient::connect(test_config(port, handler), None, None, None)
+                .await
+                .unwrap(),
+        );
+
+        let senders: Vec<_> = (0..SENDERS)
+            .map(|sender| {
+                let client = client.clone();
+                task::spawn(async move {
+                    for seq in 0..MESSAGES {
+                        let msg = format!("{sender}:{seq}");
+                        client.send_bytes(msg.as_bytes()).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        for sender in senders {
+            sender.await.unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while received.lock().unwrap().len() < SENDERS * MESSAGES {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for echoed frames");
+
+        let mut next_seq = [0; SENDERS];
+        for frame in received.lock().unwrap().iter() {
+            let frame = String::from_utf8(frame.clone()).unwrap();
+            let (sender, seq) = frame.split_once(':').expect("Corrupted frame");
+            let sender: usize = sender.parse().unwrap();
+            assert_eq!(seq.parse::<usize>().unwrap(), next_seq[sender]);
+            next_seq[sender] += 1;
+        }
+        assert_eq!(next_seq, [MESSAGES; SENDERS]);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_writer_task_replaced_after_reconnect() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+
+            // Drop the first connection to force a reconnect
+            let (socket, _) = listener.accept().await.unwrap();
+            drop(socket);
+
+            let (socket, _) = listener.accept().await.unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        let mut state_changes = client.state_changes();
+
+        tokio::time::timeout(Duration::from_secs(3), async {
+            state_changes
+                .wait_for(|state| *state == ConnectionState::Reconnecting)
+                .await
+                .unwrap();
+            state_changes
+                .wait_for(|state| *state == ConnectionState::Active)
+                .await
+                .unwrap();
+        })
+        .await
+        .expect("Client did not reconnect");
+
+        client.send_bytes(b"after reconnect").await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo from the new connection");
+        assert_eq!(*received.lock().unwrap(), vec![b"after reconnect".to_vec()]);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    /// Writer which records each write call.
+    #[derive(Clone, Default)]
+    struct RecordingWriter {
+        writes: Arc<std::sync::Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl AsyncWrite for RecordingWriter {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.writes.lock().unwrap().push(buf.to_vec());
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    async fn write_queued_frames(frames: usize, coalesce: Option<CoalesceLimits>) -> Vec<Vec<u8>> {
+        let (writer_tx, writer_rx) = mpsc::channel(WRITER_CHANNEL_CAPACITY);
+        for i in 0..frames {
+            writer_tx
+                .send(format!("{i:03}\r\n").into_bytes())
+                .await
+                .unwrap();
+        }
+
+        let writer = RecordingWriter::default();
+        let WriterTask {
+            handle,
+            shutdown_tx,
+        } = SocketClientInner::spawn_writer_task(
+            writer.clone(),
+            Arc::new(Mutex::new(writer_rx)),
+            Arc::new(Mutex::new(mpsc::unbounded_channel().1)),
+            coalesce,
+            false,
+            true,
+            None,
+        );
+
+        // Wait for the channel to drain before shutting down
+        while writer_tx.capacity() < WRITER_CHANNEL_CAPACITY {
+            sleep(Duration::from_millis(1)).await;
+        }
+        shutdown_tx.send(None).unwrap();
+        handle.await.unwrap();
+
+        let writes = writer.writes.lock().unwrap().clone();
+        writes
+    }
+
+    /// Writer which fails writes with the queued errors before accepting data.
+    #[derive(Clone, Default)]
+    struct FlakyWriter {
+        errors: Arc<std::sync::Mutex<VecDeque<std::io::ErrorKind>>>,
+        written: Arc<std::sync::Mutex<Vec<u8>>>,
+        stall_flush: Arc<AtomicBool>,
+    }
+
+    impl AsyncWrite for FlakyWriter {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            if let Some(kind) = self.errors.lock().unwrap().pop_front() {
+                return std::task::Poll::Ready(Err(kind.into()));
+            }
+            self.written.lock().unwrap().extend_from_slice(buf);
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            if self.stall_flush.load(Ordering::SeqCst) {
+                return std::task::Poll::Pending;
+            }
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[rstest]
+    #[case::interrupted_once(vec![std::io::ErrorKind::Interrupted], true, None)]
+    #[case::would_block_retried(vec![std::io::ErrorKind::WouldBlock; 3], true, None)]
+    #[case::would_block_exhausted(
+        vec![std::io::ErrorKind::WouldBlock; 4],
+        false,
+        Some(ReadExitReason::Io(std::io::ErrorKind::WouldBlock))
+    )]
+    #[case::timed_out(
+        vec![std::io::ErrorKind::TimedOut],
+        false,
+        Some(ReadExitReason::Io(std::io::ErrorKind::TimedOut))
+    )]
+    #[case::broken_pipe(
+        vec![std::io::ErrorKind::BrokenPipe],
+        false,
+        Some(ReadExitReason::Io(std::io::ErrorKind::BrokenPipe))
+    )]
+    #[case::connection_reset(
+        vec![std::io::ErrorKind::ConnectionReset],
+        false,
+        Some(ReadExitReason::Io(std::io::ErrorKind::ConnectionReset))
+    )]
+    #[tokio::test]
+    async fn test_writer_transient_errors(
+        #[case] errors: Vec<std::io::ErrorKind>,
+        #[case] delivered: bool,
+        #[case] expected_reason: Option<ReadExitReason>,
+    ) {
+        let writer = FlakyWriter::default();
+        writer.errors.lock().unwrap().extend(errors);
+        let read_task = task::spawn(std::future::pending::<()>());
+        let exit_reason = SharedExitReason::default();
+        let (writer_tx, writer_rx) = mpsc::channel(WRITER_CHANNEL_CAPACITY);
+        let WriterTask {
+            handle,
+            shutdown_tx,
+        } = SocketClientInner::spawn_writer_task(
+            writer.clone(),
+            Arc::new(Mutex::new(writer_rx)),
+            Arc::new(Mutex::new(mpsc::unbounded_channel().1)),
+            None,
+            false,
+            true,
+            Some(WriteFailureHook {
+                read_task: read_task.abort_handle(),
+                exit_reason: exit_reason.clone(),
+            }),
+        );
+
+        writer_tx.send(b"order\r\n".to_vec()).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while writer.written.lock().unwrap().is_empty() && !handle.is_finished() {
+                sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("Writer neither delivered the frame nor gave up");
+        let _ = shutdown_tx.send(None);
+        handle.await.unwrap();
+
+        let expected: &[u8] = if delivered { b"order\r\n" } else { b"" };
+        assert_eq!(*writer.written.lock().unwrap(), expected);
+        assert_eq!(*exit_reason.lock().unwrap(), expected_reason);
+        // Any error stopping the writer tears down the read task for a reconnect
+        if expected_reason.is_none() {
+            assert!(!read_task.is_finished());
+            read_task.abort();
+        }
+        assert!(read_task.await.unwrap_err().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_writer_failure_reconnects() {
+        let writers = Arc::new(std::sync::Mutex::new(Vec::<FlakyWriter>::new()));
+        let server_streams = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let connector: SocketConnector = {
+            let writers = writers.clone();
+            let server_streams = server_streams.clone();
+            Arc::new(move || {
+                let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                server_streams.lock().unwrap().push(server_stream);
+                // Only the first connection fails its write
+                let writer = FlakyWriter::default();
+                let mut writers = writers.lock().unwrap();
+                if writers.is_empty() {
+                    writer
+                        .errors
+                        .lock()
+                        .unwrap()
+                        .push_back(std::io::ErrorKind::TimedOut);
+                }
+                writers.push(writer.clone());
+                Box::pin(async move {
+                    Ok(SocketConnection {
+                        reader: Box::new(client_stream),
+                        writer: Box::new(writer),
+                        peer_addr: None,
+                        local_addr: None,
+                        url: None,
+                        alpn_protocol: None,
+                        #[cfg(unix)]
+                        raw_fd: None,
+                    })
+                })
+            })
+        };
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            connector: Some(connector),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        client.send_bytes(b"lost").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while writers.lock().unwrap().len() < 2 || !client.is_active() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect after the writer failed");
+
+        client.send_bytes(b"after").await.unwrap();
+        let writer = writers.lock().unwrap()[1].clone();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while writer.written.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Frame not written on the new connection");
+        assert_eq!(*writer.written.lock().unwrap(), b"after\r\n");
+        assert_eq!(client.stats().reconnect_count, 1);
+
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_writes_not_coalesced_by_default() {
+        let writes = write_queued_frames(100, None).await;
+
+        assert_eq!(writes.len(), 100);
+    }
+
+    #[rstest]
+    #[case(None, Some(1_000), 1)]
+    #[case(None, Some(10), 20)]
+    #[case(Some(60), None, 17)] // 5 byte frames, 12 per write
+    #[tokio::test]
+    async fn test_writes_coalesced(
+        #[case] max_bytes: Option<usize>,
+        #[case] max_frames: Option<usize>,
+        #[case] expected_writes: usize,
+    ) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            max_coalesce_bytes: max_bytes,
+            max_coalesce_frames: max_frames,
+            ..test_config(0, handler)
+        };
+
+        let writes = write_queued_frames(200, config.coalesce_limits()).await;
+
+        assert_eq!(writes.len(), expected_writes);
+        assert!(writes
+            .iter()
+            .all(|write| max_bytes.is_none_or(|max| write.len() <= max)));
+
+        let expected: Vec<u8> = (0..200)
+            .flat_map(|i| format!("{i:03}\r\n").into_bytes())
+            .collect();
+        assert_eq!(writes.concat(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_from_stream_over_duplex() {
+        let (client_stream, server_stream) = tokio::io::duplex(1_024);
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_secs(1), b"heartbeat".to_vec())),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::from_stream(client_stream, config, None, None, None)
+            .await
+            .unwrap();
+        let (mut server_reader, mut server_writer) = tokio::io::split(server_stream);
+
+        server_writer.write_all(b"hello\r\n").await.unwrap();
+        client.send_bytes(b"ping").await.unwrap();
+
+        let mut buf = Vec::new();
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while !buf.ends_with(b"heartbeat\r\n") {
+                server_reader.read_buf(&mut buf).await.unwrap();
+            }
+        })
+        .await
+        .expect("Timed out waiting for heartbeat");
+
+        assert!(buf.starts_with(b"ping\r\n"));
+        assert_eq!(*received.lock().unwrap(), vec![b"hello".to_vec()]);
+
+        // Injected streams cannot reconnect, so the client closes once the stream terminates
+        drop(server_reader);
+        drop(server_writer);
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not close after the stream terminated");
+    }
+
+    #[tokio::test]
+    async fn test_connector_retried_until_success() {
+        const FAILURES: usize = 3;
+
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (server_tx, mut server_rx) = tokio::sync::mpsc::unbounded_channel();
+        let connector: SocketConnector = {
+            let attempts = attempts.clone();
+            Arc::new(move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                let server_tx = server_tx.clone();
+                Box::pin(async move {
+                    // The initial connection succeeds, then the first reconnects fail
+                    if (1..=FAILURES).contains(&attempt) {
+                        return Err(Error::Io(std::io::Error::new(
+                            std::io::ErrorKind::ConnectionRefused,
+                            "Connection refused",
+                        )));
+                    }
+                    let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                    server_tx.send(server_stream).unwrap();
+                    Ok(SocketConnection::new(client_stream))
+                })
+            })
+        };
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            connector: Some(connector),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // Terminate the initial connection to force a reconnect
+        drop(server_rx.recv().await.unwrap());
+
+        let mut server_stream = tokio::time::timeout(Duration::from_secs(3), server_rx.recv())
+            .await
+            .expect("Connector never succeeded")
+            .unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), FAILURES + 2);
+
+        server_stream.write_all(b"reconnected\r\n").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No message over the reconnected stream");
+
+        assert!(client.is_active());
+        assert_eq!(*received.lock().unwrap(), vec![b"reconnected".to_vec()]);
+
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_close_graceful_delivers_final_message() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+
+            // Read until the client half-closes its side
+            let mut buf = Vec::new();
+            while socket.read_buf(&mut buf).await.unwrap() > 0 {}
+
+            // Reply after longer than the non-graceful shutdown waits
+            sleep(Duration::from_millis(300)).await;
+            socket.write_all(b"final\r\n").await.unwrap();
+            socket.shutdown().await.unwrap();
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        client.close_graceful(Duration::from_secs(2)).await;
+
+        assert!(client.is_closed());
+        assert_eq!(*received.lock().unwrap(), vec![b"final".to_vec()]);
+        server_task.await.unwrap();
+    }
+
+    #[rstest]
+    #[case(false, vec![b"first".to_vec()])]
+    #[case(true, vec![b"first".to_vec(), b"last".to_vec()])]
+    #[tokio::test]
+    async fn test_deliver_unterminated_on_eof(
+        #[case] deliver_unterminated_on_eof: bool,
+        #[case] expected: Vec<Vec<u8>>,
+    ) {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            socket.write_all(b"first\r\nlast").await.unwrap();
+            socket.shutdown().await.unwrap();
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            deliver_unterminated_on_eof,
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        server_task.await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !client.is_reconnecting() && !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client never observed the connection closing");
+
+        assert_eq!(*received.lock().unwrap(), expected);
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_idle_timeout_triggers_reconnect() {
+        let (port, listener) = bind_test_server();
+        let (accepted_tx, mut accepted_rx) = tokio::sync::mpsc::unbounded_channel();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let mut sockets = Vec::new();
+            loop {
+                // Hold each connection open without ever writing to it
+                let (socket, _) = listener.accept().await.unwrap();
+                sockets.push(socket);
+                accepted_tx.send(()).unwrap();
+            }
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            read_idle_timeout_secs: Some(1),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        accepted_rx.recv().await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), accepted_rx.recv())
+            .await
+            .expect("Client did not reconnect after going idle")
+            .unwrap();
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_timeout_triggers_reconnect() {
+        let (port, listener) = bind_test_server();
+        let (accepted_tx, mut accepted_rx) = tokio::sync::mpsc::unbounded_channel();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                // Read and ignore every heartbeat
+                let (mut socket, _) = listener.accept().await.unwrap();
+                accepted_tx.send(()).unwrap();
+                task::spawn(async move {
+                    let mut buf = Vec::new();
+                    while socket.read_buf(&mut buf).await.unwrap_or(0) > 0 {}
+                });
+            }
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_secs(1), b"ping".to_vec())),
+            heartbeat_timeout_secs: Some(2),
+            heartbeat_response: Some(Arc::new(|data: &[u8]| data == b"pong")),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        accepted_rx.recv().await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), accepted_rx.recv())
+            .await
+            .expect("Client did not reconnect after unanswered heartbeats")
+            .unwrap();
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_suppressed_while_sending() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = Vec::new();
+            while socket.read_buf(&mut buf).await.unwrap_or(0) > 0 {}
+            buf
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_secs(1), b"ping".to_vec())),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // Keep sending more often than the heartbeat interval
+        for _ in 0..12 {
+            client.send_bytes(b"data").await.unwrap();
+            sleep(Duration::from_millis(200)).await;
+        }
+        client.close().await;
+
+        let received = server_task.await.unwrap();
+        assert!(received.starts_with(b"data\r\n"));
+        assert_eq!(memchr::memmem::find(&received, b"ping"), None);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_deferred_by_recent_sends() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let accepted_at = Instant::now();
+            let mut lines = tokio::io::BufReader::new(socket).lines();
+            let mut heartbeats = Vec::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim_end() == "ping" {
+                    heartbeats.push(accepted_at.elapsed());
+                }
+            }
+            heartbeats
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_secs(1), b"ping".to_vec())),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // The send at 1.2s pushes the first heartbeat out to 2.2s
+        sleep(Duration::from_millis(500)).await;
+        client.send_bytes(b"data").await.unwrap();
+        sleep(Duration::from_millis(700)).await;
+        client.send_bytes(b"data").await.unwrap();
+        sleep(Duration::from_millis(2_300)).await;
+        client.close().await;
+
+        let heartbeats = server_task.await.unwrap();
+        assert!(!heartbeats.is_empty(), "no heartbeat sent");
+        assert!(
+            heartbeats[0] >= Duration::from_millis(2_100)
+                && heartbeats[0] < Duration::from_millis(2_600),
+            "first heartbeat at {:?}",
+            heartbeats[0]
+        );
+        for gap in heartbeats.windows(2) {
+            assert!(gap[1] - gap[0] >= Duration::from_millis(900));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_payload_callback() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut lines = tokio::io::BufReader::new(socket).lines();
+            let mut heartbeats = Vec::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                heartbeats.push(line.parse::<u64>().unwrap());
+            }
+            heartbeats
+        });
+
+        let counter = Arc::new(AtomicU64::new(0));
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_secs(1), b"ping".to_vec())),
+            heartbeat_payload: Some(Arc::new(move || {
+                let seq = counter.fetch_add(1, Ordering::Relaxed) + 1;
+                Ok(seq.to_string().into_bytes())
+            })),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        sleep(Duration::from_millis(3_500)).await;
+        client.close().await;
+
+        let heartbeats = server_task.await.unwrap();
+        assert_eq!(heartbeats, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_sent_verbatim_without_suffix() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = Vec::new();
+            while socket.read_buf(&mut buf).await.unwrap_or(0) > 0 {}
+            buf
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_secs(1), vec![0x09])),
+            heartbeat_append_suffix: false,
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        sleep(Duration::from_millis(1_500)).await;
+        client.close().await;
+
+        let received = server_task.await.unwrap();
+        assert_eq!(received, vec![0x09]);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_sub_second_interval() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut lines = tokio::io::BufReader::new(socket).lines();
+            let mut count = 0;
+            while let Ok(Some(line)) = lines.next_line().await {
+                assert_eq!(line, "ping");
+                count += 1;
+            }
+            count
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            heartbeat: Some((Duration::from_millis(200), b"ping".to_vec())),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        sleep(Duration::from_millis(1_000)).await;
+        client.close().await;
+
+        let count = server_task.await.unwrap();
+        assert!(count >= 3, "received {count} heartbeats");
+    }
+
+    #[tokio::test]
+    async fn test_post_connection_receives_initial_info() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let infos = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let infos_clone = infos.clone();
+        let post_connection: ConnectionCallback = Arc::new(move |info| {
+            infos_clone.lock().unwrap().push(info);
+        });
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(
+            test_config(port, handler),
+            Some(post_connection),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *infos.lock().unwrap(),
+            vec![ConnectionInfo {
+                attempt: 0,
+                peer_addr: Some(SocketAddr::from(([127, 0, 0, 1], port))),
+                elapsed: Duration::ZERO,
+            }]
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_connection_callbacks_receive_info() {
+        prepare_freethreaded_python();
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            // Drop the first connection to force a reconnect
+            drop(listener.accept().await.unwrap());
+            let (socket, _) = listener.accept().await.unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let code_raw = r#"
+events = []
+
+def on_reconnection(info):
+    events.append(("reconnection", info))
+
+def on_disconnection():
+    events.append(("disconnection", None))
+"#;
+        let code = CString::new(code_raw).unwrap();
+        let filename = CString::new("test".to_string()).unwrap();
+        let module = CString::new("test".to_string()).unwrap();
+        let (pymod, post_reconnection, post_disconnection) = Python::with_gil(|py| {
+            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
+            let post_reconnection = pymod.getattr("on_reconnection").unwrap().into_py(py);
+            let post_disconnection = pymod.getattr("on_disconnection").unwrap().into_py(py);
+            (pymod.into_py(py), post_reconnection, post_disconnection)
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                ..BackoffPolicy::default()
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(
+            config,
+            None,
+            Some(py_connection_callback(
+                post_reconnection,
+                "post_reconnection",
+            )),
+            Some(py_connection_callback(
+                post_disconnection,
+                "post_disconnection",
+            )),
+        )
+        .await
+        .unwrap();
+
+        let event_count =
+            || Python::with_gil(|py| pymod.getattr(py, "events").unwrap().bind(py).len().unwrap());
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while event_count() == 0 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect");
+        client.close().await;
+
+        Python::with_gil(|py| {
+            let events = pymod.getattr(py, "events").unwrap();
+            let events: Vec<(String, Option<Py<PyDict>>)> = events.extract(py).unwrap();
+            assert_eq!(events.len(), 2);
+
+            let (kind, info) = &events[0];
+            assert_eq!(kind, "reconnection");
+            let info = info.as_ref().unwrap().bind(py);
+            let attempt: u64 = info
+                .get_item("attempt")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            let peer_addr: String = info
+                .get_item("peer_addr")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            let elapsed: f64 = info
+                .get_item("elapsed_secs")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(attempt, 1);
+            assert_eq!(peer_addr, format!("127.0.0.1:{port}"));
+            assert!(elapsed >= 0.0);
+
+            assert_eq!(events[1].0, "disconnection");
+            assert!(events[1].1.is_none());
+        });
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_peer_and_local_addr() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, client_addr) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            (socket, client_addr)
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        let (_socket, client_addr) = server_task.await.unwrap();
+
+        assert_eq!(
+            client.peer_addr(),
+            Some(SocketAddr::from(([127, 0, 0, 1], port)))
+        );
+        assert_eq!(client.local_addr(), Some(client_addr));
+
+        client.close().await;
+    }
+
+    #[rstest]
+    #[case(TcpOptions::default())]
+    #[case(TcpOptions {
+        nodelay: false,
+        keepalive: Some(Duration::from_secs(30)),
+        recv_buffer_size: Some(64 * 1024),
+        send_buffer_size: Some(64 * 1024),
+        dscp: None,
+        linger: Some(Duration::from_secs(1)),
+    })]
+    #[tokio::test]
+    async fn test_tcp_options_applied(#[case] options: TcpOptions) {
+        let (port, listener) = bind_test_server();
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let _server = listener.accept().await.unwrap();
+
+        options.apply(&stream).unwrap();
+
+        let socket = SockRef::from(&stream);
+        assert_eq!(stream.nodelay().unwrap(), options.nodelay);
+        assert_eq!(socket.keepalive().unwrap(), options.keepalive.is_some());
+        if let Some(keepalive) = options.keepalive {
+            assert_eq!(socket.keepalive_time().unwrap(), keepalive);
+        }
+        if let Some(size) = options.recv_buffer_size {
+            // Linux doubles the requested size to allow for bookkeeping overhead
+            assert!(socket.recv_buffer_size().unwrap() >= size);
+        }
+        if options.linger.is_some() {
+            assert_eq!(socket.linger().unwrap(), options.linger);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout() {
+        // A listener which never accepts drops further handshakes once its backlog is full
+        let listener =
+            socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::STREAM, None).unwrap();
+        listener
+            .bind(&std::net::SocketAddr::from(([127, 0, 0, 1], 0)).into())
+            .unwrap();
+        listener.listen(0).unwrap();
+        let addr = listener.local_addr().unwrap().as_socket().unwrap();
+        let _backlog: Vec<_> = (0..4)
+            .filter_map(|_| {
+                std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(100)).ok()
+            })
+            .collect();
+
+        let config = SocketConfig {
+            url: addr.to_string(),
+            connect_timeout_secs: Some(1),
+            ..test_config(0, create_rust_handler().0)
+        };
+
+        let start = std::time::Instant::now();
+        let result = SocketClient::connect(config, None, None, None).await;
+        let elapsed = start.elapsed();
+
+        match result {
+            Err(SocketError::Timeout(_)) => {}
+            Err(e) => panic!("Unexpected error: {e}"),
+            Ok(_) => panic!("Connect should not succeed"),
+        }
+        assert!(elapsed < Duration::from_secs(3), "Took {elapsed:?}");
+    }
+
+    const TLS_CA_PEM: &[u8] = include_bytes!("../test_data/tls/ca.pem");
+    const TLS_SERVER_PEM: &[u8] = include_bytes!("../test_data/tls/server.pem");
+    const TLS_SERVER_KEY: &[u8] = include_bytes!("../test_data/tls/server.key");
+
+    /// Binds a TLS server presenting a certificate issued by the test CA, which records
+    /// the SNI of the last handshake and sends a greeting once it completes.
+    ///
+    /// The server selects the `feed/1` protocol when a client offers it with ALPN.
+    async fn bind_tls_server() -> (
+        u16,
+        Arc<std::sync::Mutex<Option<String>>>,
+        task::JoinHandle<()>,
+    ) {
+        use rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+
+        install_cryptographic_provider();
+        let certs = CertificateDer::pem_slice_iter(TLS_SERVER_PEM)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = PrivateKeyDer::from_pem_slice(TLS_SERVER_KEY).unwrap();
+        let mut config = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .unwrap();
+        config.alpn_protocols = vec![b"feed/1".to_vec()];
+        let config = Arc::new(config);
+        let server_name = Arc::new(std::sync::Mutex::new(None));
+        let last_server_name = server_name.clone();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server_task = task::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                let config = config.clone();
+                let server_name = server_name.clone();
+                task::spawn(async move {
+                    let acceptor = tokio_rustls::LazyConfigAcceptor::new(
+                        rustls::server::Acceptor::default(),
+                        socket,
+                    );
+                    let Ok(start) = acceptor.await else { return };
+                    let sni = start.client_hello().server_name().map(str::to_string);
+                    *server_name.lock().unwrap() = sni;
+                    if let Ok(mut stream) = start.into_stream(config).await {
+                        stream.write_all(b"hello\r\n").await.unwrap();
+                        let mut buf = Vec::new();
+                        let _ = stream.read_buf(&mut buf).await;
+                    }
+                });
+            }
+        });
+        (port, last_server_name, server_task)
+    }
+
+    #[rstest]
+    #[case(None, false, false)]
+    #[case(Some(TLS_CA_PEM), false, true)]
+    #[case(None, true, true)]
+    #[tokio::test]
+    async fn test_tls_custom_trust(
+        #[case] root_ca_pem: Option<&[u8]>,
+        #[case] danger_accept_invalid_certs: bool,
+        #[case] expect_connected: bool,
+    ) {
+        let (port, _, server_task) = bind_tls_server().await;
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            mode: Mode::Tls,
+            root_ca_pem: root_ca_pem.map(<[u8]>::to_vec),
+            danger_accept_invalid_certs,
+            ..test_config(port, handler)
+        };
+
+        let result = SocketClient::connect(config, None, None, None).await;
+        assert_eq!(result.is_ok(), expect_connected);
+
+        if let Ok(client) = result {
+            tokio::time::timeout(Duration::from_secs(2), async {
+                while received.lock().unwrap().is_empty() {
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("No message over the TLS connection");
+            assert_eq!(*received.lock().unwrap(), vec![b"hello".to_vec()]);
+            client.close().await;
+        }
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_tls_handshake_timeout() {
+        // The server accepts the TCP connection but never answers the TLS handshake
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(10)).await;
+            drop(socket);
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            mode: Mode::Tls,
+            connect_timeout_secs: Some(5),
+            tls_handshake_timeout_secs: Some(1),
+            ..test_config(port, handler)
+        };
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(3),
+            SocketClient::connect(config, None, None, None),
+        )
+        .await
+        .expect("TLS handshake timeout did not fire");
+
+        assert!(matches!(
+            result,
+            Err(SocketError::TlsHandshakeTimeout(timeout)) if timeout == Duration::from_secs(1)
+        ));
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(vec![b"h2".to_vec(), b"feed/1".to_vec()], Some(b"feed/1".to_vec()))]
+    #[case(Vec::new(), None)]
+    #[tokio::test]
+    async fn test_tls_alpn_protocol_negotiated(
+        #[case] alpn_protocols: Vec<Vec<u8>>,
+        #[case] expected: Option<Vec<u8>>,
+    ) {
+        let (port, _, server_task) = bind_tls_server().await;
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            mode: Mode::Tls,
+            root_ca_pem: Some(TLS_CA_PEM.to_vec()),
+            alpn_protocols,
+            ..test_config(port, handler)
+        };
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Failed to connect over TLS");
+        assert_eq!(client.alpn_protocol(), expected);
+
+        // The protocol is negotiated again on every reconnect
+        client.reconnect_now().await.unwrap();
+        assert_eq!(client.alpn_protocol(), expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    #[tokio::test]
+    async fn test_tls_certificate_pinning(#[case] matching: bool) {
+        use rustls::pki_types::{pem::PemObject, CertificateDer};
+
+        let leaf = CertificateDer::pem_slice_iter(TLS_SERVER_PEM)
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut pin: [u8; 32] = ring::digest::digest(&ring::digest::SHA256, leaf.as_ref())
+            .as_ref()
+            .try_into()
+            .unwrap();
+        if !matching {
+            pin[0] ^= 0xff;
+        }
+
+        let (port, _, server_task) = bind_tls_server().await;
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            mode: Mode::Tls,
+            root_ca_pem: Some(TLS_CA_PEM.to_vec()),
+            pinned_cert_sha256: Some(vec![pin]),
+            ..test_config(port, handler)
+        };
+
+        let result = SocketClient::connect(config, None, None, None).await;
+        match result {
+            Ok(client) => {
+                assert!(matching, "connected despite the pin mismatch");
+                client.close().await;
+            }
+            Err(e) => {
+                assert!(!matching, "failed with a matching pin: {e}");
+                assert!(matches!(e, SocketError::Tls(_)), "{e}");
+                assert!(
+                    e.to_string().contains("matches no pinned certificate"),
+                    "{e}"
+                );
+            }
+        }
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_tls_sni_override() {
+        let (port, server_name, server_task) = bind_tls_server().await;
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            mode: Mode::Tls,
+            root_ca_pem: Some(TLS_CA_PEM.to_vec()),
+            tls_sni: Some("server.test".to_string()),
+            ..test_config(port, handler)
+        };
+
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Failed to connect with SNI override");
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No message over the TLS connection");
+
+        assert_eq!(server_name.lock().unwrap().as_deref(), Some("server.test"));
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case("127.0.0.1")]
+    #[case("not a hostname")]
+    #[case("")]
+    fn test_invalid_tls_sni_rejected(#[case] tls_sni: &str) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            tls_sni: Some(tls_sni.to_string()),
+            ..test_config(0, handler)
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    /// Runs a minimal SOCKS5 proxy without authentication, counting tunnels made.
+    async fn run_socks5_proxy(listener: TcpListener, tunnels: Arc<AtomicU64>) {
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        loop {
+            let (mut inbound, _) = listener.accept().await.unwrap();
+            let tunnels = tunnels.clone();
+            task::spawn(async move {
+                let mut greeting = [0u8; 3];
+                inbound.read_exact(&mut greeting).await.unwrap();
+                inbound.write_all(&[5, 0]).await.unwrap();
+
+                // Only IPv4 targets are needed here
+                let mut request = [0u8; 10];
+                inbound.read_exact(&mut request).await.unwrap();
+                assert_eq!(request[..4], [5, 1, 0, 1]);
+                let ip = std::net::Ipv4Addr::new(request[4], request[5], request[6], request[7]);
+                let port = u16::from_be_bytes([request[8], request[9]]);
+                let mut outbound = TcpStream::connect((ip, port)).await.unwrap();
+                inbound
+                    .write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])
+                    .await
+                    .unwrap();
+                tunnels.fetch_add(1, Ordering::SeqCst);
+                let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_through_socks5_proxy() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                task::spawn(run_echo_server(socket));
+            }
+        });
+        let (proxy_port, proxy_listener) = bind_test_server();
+        let tunnels = Arc::new(AtomicU64::new(0));
+        let proxy_task = task::spawn(run_socks5_proxy(proxy_listener, tunnels.clone()));
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            proxy: Some(ProxyConfig {
+                scheme: crate::proxy::ProxyScheme::Socks5,
+                host: "127.0.0.1".to_string(),
+                port: proxy_port,
+                username: None,
+                password: None,
+            }),
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(50),
+                ..BackoffPolicy::default()
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Failed to connect through proxy");
+        assert_eq!(
+            client.peer_addr(),
+            Some(SocketAddr::from(([127, 0, 0, 1], proxy_port)))
+        );
+
+        client.send_bytes(b"Hello").await.unwrap();
+        // Closing the server side makes the client reconnect, re-dialing the proxy
+        client.send_bytes(b"close").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while tunnels.load(Ordering::SeqCst) < 2 || !client.is_active() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect through the proxy");
+
+        client.send_bytes(b"World").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo through the proxy");
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"Hello".to_vec(), b"World".to_vec()]
+        );
+
+        client.close().await;
+        proxy_task.abort();
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_unix_socket_send_receive() {
+        let path =
+            std::env::temp_dir().join(format!("nautilus-socket-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            while socket.read_buf(&mut buf).await.unwrap() > 0 {
+                // Echo complete frames back
+                if let Some(idx) = buf.windows(2).rposition(|w| w == b"\r\n") {
+                    let frames: Vec<u8> = buf.drain(..idx + 2).collect();
+                    socket.write_all(&frames).await.unwrap();
+                }
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            url: format!("{UNIX_SOCKET_SCHEME}{}", path.display()),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Failed to connect to Unix socket");
+        assert_eq!(client.peer_addr(), None);
+
+        client.send_bytes(b"Hello").await.unwrap();
+        client.send_bytes(b"World").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo over the Unix socket");
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"Hello".to_vec(), b"World".to_vec()]
+        );
+
+        client.close().await;
+        server_task.abort();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[rstest]
+    #[case("unix://", Mode::Plain)]
+    #[case("unix:///tmp/feed.sock", Mode::Tls)]
+    fn test_invalid_unix_socket_config_rejected(#[case] url: &str, #[case] mode: Mode) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            url: url.to_string(),
+            mode,
+            ..test_config(0, handler)
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_now_resumes_traffic() {
+        let (port, listener) = bind_test_server();
+        let accepted = Arc::new(AtomicU64::new(0));
+        let accepted_clone = accepted.clone();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                accepted_clone.fetch_add(1, Ordering::SeqCst);
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        client.send_bytes(b"Hello").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo before manual reconnect");
+
+        client
+            .reconnect_now()
+            .await
+            .expect("Manual reconnect failed");
+        assert!(client.is_active());
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+
+        client.send_bytes(b"World").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo after manual reconnect");
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"Hello".to_vec(), b"World".to_vec()]
+        );
+
+        client.close().await;
+        assert!(client.reconnect_now().await.is_err());
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_fallback_url() {
+        let (down_port, down_listener) = bind_test_server();
+        drop(down_listener);
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, _received) = create_rust_handler();
+        let config = SocketConfig {
+            fallback_urls: vec![format!("127.0.0.1:{port}")],
+            ..test_config(down_port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .expect("Failed to connect to fallback URL");
+
+        assert_eq!(client.active_url(), Some(format!("127.0.0.1:{port}")));
+        assert_eq!(
+            client.peer_addr(),
+            Some(SocketAddr::from(([127, 0, 0, 1], port)))
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_url_provider_called_before_each_connect() {
+        // The first server drops its connection immediately to force a reconnect
+        let (first_port, first_listener) = bind_test_server();
+        let first_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(first_listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_millis(50)).await;
+            drop(socket);
+        });
+        let (second_port, second_listener) = bind_test_server();
+        let second_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(second_listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut handshake = [0u8; 7];
+            socket.read_exact(&mut handshake).await.unwrap();
+            assert_eq!(&handshake, b"token\r\n");
+            run_echo_server(socket).await;
+        });
+
+        let calls = Arc::new(AtomicU64::new(0));
+        let calls_clone = calls.clone();
+        let url_provider: UrlProvider = Arc::new(move || {
+            let port = match calls_clone.fetch_add(1, Ordering::SeqCst) {
+                0 => first_port,
+                _ => second_port,
+            };
+            Ok(Endpoint {
+                url: format!("127.0.0.1:{port}"),
+                handshake: Some(b"token\r\n".to_vec()),
+            })
+        });
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            url_provider: Some(url_provider),
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(50),
+                ..BackoffPolicy::default()
+            },
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.active_url(), Some(format!("127.0.0.1:{first_port}")));
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while client.active_url() != Some(format!("127.0.0.1:{second_port}"))
+                || !client.is_active()
+            {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect to the provided URL");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        client.send_bytes(b"Hello").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo from the provided URL");
+
+        client.close().await;
+        first_task.abort();
+        second_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_resolves_changed_address() {
+        // The first server drops its connection immediately to force a reconnect
+        let (first_port, first_listener) = bind_test_server();
+        let first_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(first_listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_millis(50)).await;
+            drop(socket);
+        });
+        let (second_port, second_listener) = bind_test_server();
+        let second_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(second_listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        // The stub fails over to the second server after the first lookup
+        let lookups = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let lookups_clone = lookups.clone();
+        let resolver: Resolver = Arc::new(move |addr: &str| {
+            let mut lookups = lookups_clone.lock().unwrap();
+            let port = if lookups.is_empty() {
+                first_port
+            } else {
+                second_port
+            };
+            lookups.push(addr.to_string());
+            Box::pin(async move { Ok(vec![SocketAddr::from(([127, 0, 0, 1], port))]) })
+        });
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            url: "venue.test:443".to_string(),
+            resolver: Some(resolver),
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(50),
+                ..BackoffPolicy::default()
+            },
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(client.peer_addr().unwrap().port(), first_port);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while client.peer_addr().map(|addr| addr.port()) != Some(second_port)
+                || !client.is_active()
+            {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect to the resolved address");
+        assert_eq!(*lookups.lock().unwrap(), vec!["venue.test:443"; 2]);
+
+        client.close().await;
+        first_task.abort();
+        second_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_url_provider_error_fails_connect() {
+        let url_provider: UrlProvider =
+            Arc::new(|| Err(std::io::Error::other("Token refresh failed")));
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            url_provider: Some(url_provider),
+            ..test_config(0, handler)
+        };
+
+        assert!(SocketClient::connect(config, None, None, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_on_connect_send_precedes_user_traffic() {
+        let (port, listener) = bind_test_server();
+        let lines = Arc::new(std::sync::Mutex::new(Vec::<(usize, Vec<u8>)>::new()));
+        let lines_clone = lines.clone();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            for connection in 0.. {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let lines = lines_clone.clone();
+                task::spawn(async move {
+                    let mut buf = Vec::new();
+                    while socket.read_buf(&mut buf).await.unwrap_or(0) > 0 {
+                        while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
+                            let line: Vec<u8> = buf.drain(..idx + 2).take(idx).collect();
+                            lines.lock().unwrap().push((connection, line));
+                        }
+                    }
+                });
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            on_connect_send: Some(vec![b"login".to_vec(), b"subscribe".to_vec()]),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        client.send_bytes(b"first").await.unwrap();
+        client.reconnect_now().await.unwrap();
+        client.send_bytes(b"second").await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while lines.lock().unwrap().len() < 6 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Server did not receive all frames");
+
+        let expected: Vec<(usize, Vec<u8>)> = vec![
+            (0, b"login".to_vec()),
+            (0, b"subscribe".to_vec()),
+            (0, b"first".to_vec()),
+            (1, b"login".to_vec()),
+            (1, b"subscribe".to_vec()),
+            (1, b"second".to_vec()),
+        ];
+        assert_eq!(*lines.lock().unwrap(), expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_on_reconnect_send_precedes_post_reconnection_traffic() {
+        let (port, listener) = bind_test_server();
+        let lines = Arc::new(std::sync::Mutex::new(Vec::<(usize, Vec<u8>)>::new()));
+        let lines_clone = lines.clone();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            for connection in 0.. {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let lines = lines_clone.clone();
+                task::spawn(async move {
+                    let mut buf = Vec::new();
+                    while socket.read_buf(&mut buf).await.unwrap_or(0) > 0 {
+                        while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
+                            let line: Vec<u8> = buf.drain(..idx + 2).take(idx).collect();
+                            lines.lock().unwrap().push((connection, line));
+                        }
+                    }
+                });
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            on_connect_send: Some(vec![b"login".to_vec()]),
+            on_reconnect_send: Some(vec![b"resubscribe".to_vec(), b"snapshot".to_vec()]),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        client.send_bytes(b"first").await.unwrap();
+        client.reconnect_now().await.unwrap();
+        client.send_bytes(b"second").await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while lines.lock().unwrap().len() < 6 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Server did not receive all frames");
+
+        // The resubscribe frames only go out on the reconnection, ahead of user traffic
+        let expected: Vec<(usize, Vec<u8>)> = vec![
+            (0, b"login".to_vec()),
+            (0, b"first".to_vec()),
+            (1, b"login".to_vec()),
+            (1, b"resubscribe".to_vec()),
+            (1, b"snapshot".to_vec()),
+            (1, b"second".to_vec()),
+        ];
+        assert_eq!(*lines.lock().unwrap(), expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_traffic() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(client.stats(), SocketStats::default());
+
+        client.send_bytes(b"Hello").await.unwrap();
+        client.send_bytes(b"World").await.unwrap();
+        client.reconnect_now().await.unwrap();
+        client.send_bytes(b"Again").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 3 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for echoes");
+
+        assert_eq!(
+            client.stats(),
+            SocketStats {
+                bytes_sent: 21,
+                bytes_received: 21,
+                frames_sent: 3,
+                frames_received: 3,
+                reconnect_count: 1,
+                handler_frames_dropped: 0,
+                ..client.stats()
+            }
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_seconds_since_last_activity() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(client.seconds_since_last_send(), None);
+        assert_eq!(client.seconds_since_last_recv(), None);
+
+        client.send_bytes(b"Hello").await.unwrap();
+        let since_send = client.seconds_since_last_send().unwrap();
+        assert!(
+            since_send < 0.1,
+            "Expected a recent send, was {since_send}s"
+        );
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo received");
+        assert!(client.seconds_since_last_recv().unwrap() < 0.5);
+
+        sleep(Duration::from_millis(200)).await;
+        assert!(client.seconds_since_last_send().unwrap() >= since_send + 0.2);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_send_errors_are_classified() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            // Hold the connection open without reading
+            sleep(Duration::from_secs(10)).await;
+            drop(socket);
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        // Reconnecting without a queue makes the send wait for the client, then time out
+        client.connection_state.store(ConnectionState::Reconnecting);
+        assert!(matches!(
+            client.send_bytes(b"Hello").await,
+            Err(SocketError::Timeout(_))
+        ));
+        assert!(matches!(
+            client.reconnect_now().await,
+            Err(SocketError::NotConnected)
+        ));
+        client.connection_state.store(ConnectionState::Active);
+
+        client.close().await;
+        assert!(matches!(
+            client.send_bytes(b"Hello").await,
+            Err(SocketError::Closed)
+        ));
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_connect_refused_is_io_error() {
+        let (port, listener) = bind_test_server();
+        drop(listener);
+        let (handler, _) = create_rust_handler();
+
+        match SocketClient::connect(test_config(port, handler), None, None, None).await {
+            Err(SocketError::Io(e)) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::ConnectionRefused);
+            }
+            Err(e) => panic!("Unexpected error: {e}"),
+            Ok(_) => panic!("Connect should not succeed"),
+        }
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(50)]
+    #[tokio::test]
+    async fn test_send_wait_active_timeout(#[case] timeout_ms: u64) {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(10)).await;
+            drop(socket);
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            send_wait_active_timeout_ms: Some(timeout_ms),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        client.connection_state.store(ConnectionState::Reconnecting);
+
+        let start = std::time::Instant::now();
+        let result = client.send_bytes(b"Hello").await;
+        let elapsed = start.elapsed();
+
+        match timeout_ms {
+            0 => assert!(matches!(result, Err(SocketError::NotConnected))),
+            _ => assert!(matches!(result, Err(SocketError::Timeout(_)))),
+        }
+        assert!(elapsed < Duration::from_millis(500), "Took {elapsed:?}");
+
+        // A send waiting on the reconnect proceeds as soon as the client is active
+        let state = client.connection_state.clone();
+        task::spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            state.store(ConnectionState::Active);
+        });
+        if timeout_ms > 0 {
+            client.send_bytes(b"Hello").await.unwrap();
+        }
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_cloned_sender_checks_active_and_closed() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            send_wait_active_timeout_ms: Some(0),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        // As used by the futures of the Python bindings
+        let sender = client.sender.clone();
+
+        client.connection_state.store(ConnectionState::Reconnecting);
+        assert!(matches!(
+            sender.send_bytes(b"Hello").await,
+            Err(SocketError::NotConnected)
+        ));
+        assert!(matches!(
+            sender.send_bytes_flushed(b"Hello").await,
+            Err(SocketError::NotConnected)
+        ));
+        assert!(matches!(
+            sender.send_many(&[b"Hello"]).await,
+            Err(SocketError::NotConnected)
+        ));
+        client.connection_state.store(ConnectionState::Active);
+
+        client.close().await;
+        assert!(matches!(
+            sender.send_bytes(b"Hello").await,
+            Err(SocketError::Closed)
+        ));
+        assert!(matches!(sender.flush().await, Err(SocketError::Closed)));
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_send_many_is_contiguous() {
+        const SENDERS: usize = 8;
+        const BATCHES: usize = 20;
+
+        let (port, listener) = bind_test_server();
+        let lines = Arc::new(std::sync::Mutex::new(Vec::<Vec<u8>>::new()));
+        let lines_clone = lines.clone();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = Vec::new();
+            while socket.read_buf(&mut buf).await.unwrap_or(0) > 0 {
+                while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
+                    let line: Vec<u8> = buf.drain(..idx + 2).take(idx).collect();
+                    lines_clone.lock().unwrap().push(line);
+                }
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = Arc::new(
+            SocketClient::connect(test_config(port, handler), None, None, None)
+                .await
+                .unwrap(),
+        );
+        let tasks: Vec<_> = (0..SENDERS)
+            .map(|sender| {
+                let client = client.clone();
+                task::spawn(async move {
+                    for batch in 0..BATCHES {
+                        let frames: Vec<Vec<u8>> = (0..3)
+                            .map(|i| format!("{sender}:{batch}:{i}").into_bytes())
+                            .collect();
+                        let frames: Vec<&[u8]> = frames.iter().map(Vec::as_slice).collect();
+                        client.send_many(&frames).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while lines.lock().unwrap().len() < SENDERS * BATCHES * 3 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Server did not receive all frames");
+
+        let lines = lines.lock().unwrap().clone();
+        for batch in lines.chunks(3) {
+            let prefix = |line: &Vec<u8>| {
+                let line = String::from_utf8(line.clone()).unwrap();
+                line.rsplit_once(':')
+                    .map(|(p, i)| (p.to_string(), i.to_string()))
+                    .unwrap()
+            };
+            let (first, _) = prefix(&batch[0]);
+            for (i, line) in batch.iter().enumerate() {
+                assert_eq!(prefix(line), (first.clone(), i.to_string()));
+            }
+        }
+        assert_eq!(client.stats().frames_sent, (SENDERS * BATCHES * 3) as u64);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_try_send_bytes_returns_busy_when_writer_blocked() {
+        let (port, listener) = bind_test_server();
+        // Accept but never read, so the writer eventually blocks on a full socket buffer
+        let server_task = task::spawn(async move {
+            let (_socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        let payload = vec![b'x'; 64 * 1024];
+        let mut result = Ok(());
+        for _ in 0..100_000 {
+            result = client.try_send_bytes(&payload);
+            if result.is_err() {
+                break;
+            }
+        }
+        assert!(matches!(result, Err(SocketError::Busy)), "{result:?}");
+
+        server_task.abort();
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_reading() {
+        let (port, listener) = bind_test_server();
+        let (server_tx, mut server_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            while let Some(data) = server_rx.recv().await {
+                socket.write_all(&data).await.unwrap();
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        client.pause_reading();
+        assert!(client.is_reading_paused());
+        for i in 0..5 {
+            server_tx.send(format!("msg{i}\r\n").into_bytes()).unwrap();
+        }
+        sleep(Duration::from_millis(200)).await;
+        assert!(received.lock().unwrap().is_empty());
+
+        client.resume_reading();
+        assert!(!client.is_reading_paused());
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 5 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Frames not delivered after resuming");
+
+        let expected: Vec<Vec<u8>> = (0..5).map(|i| format!("msg{i}").into_bytes()).collect();
+        assert_eq!(*received.lock().unwrap(), expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(HandlerQueuePolicy::Block)]
+    #[case(HandlerQueuePolicy::Drop)]
+    #[tokio::test]
+    async fn test_handler_queue_decouples_slow_handler(#[case] policy: HandlerQueuePolicy) {
+        const FRAMES: usize = 20;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            for i in 0..FRAMES {
+                socket
+                    .write_all(format!("msg{i:02}\r\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (inner_handler, received) = create_rust_handler();
+        let handler = move |data: &[u8]| {
+            std::thread::sleep(Duration::from_millis(20));
+            inner_handler.handle(data);
+        };
+        let config = SocketConfig {
+            handler_queue_capacity: Some(4),
+            handler_queue_overflow: policy,
+            ..test_config(port, Arc::new(handler))
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // Frames queue up behind the slow handler while the socket stays responsive
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while client.handler_queue_depth() == 0 {
+                sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("Handler queue did not fill");
+        assert!(client.is_active());
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while client.stats().frames_received < FRAMES as u64 || client.handler_queue_depth() > 0
+            {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Handler queue did not drain");
+        // Allow the handler to finish the last frame taken from the queue
+        sleep(Duration::from_millis(50)).await;
+
+        let delivered = received.lock().unwrap().clone();
+        let dropped = client.stats().handler_frames_dropped as usize;
+        assert_eq!(delivered.len() + dropped, FRAMES);
+        match policy {
+            HandlerQueuePolicy::Block => {
+                let expected: Vec<Vec<u8>> = (0..FRAMES)
+                    .map(|i| format!("msg{i:02}").into_bytes())
+                    .collect();
+                assert_eq!(delivered, expected);
+            }
+            HandlerQueuePolicy::Drop => assert!(dropped > 0),
+        }
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case::ordered(1)]
+    #[case::concurrent(4)]
+    #[tokio::test]
+    async fn test_handler_concurrency(#[case] workers: usize) {
+        const FRAMES: usize = 8;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            for i in 0..FRAMES {
+                socket
+                    .write_all(format!("msg{i}\r\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let (inner_handler, received) = create_rust_handler();
+        let handler = {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            move |data: &[u8]| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(50));
+                inner_handler.handle(data);
+                running.fetch_sub(1, Ordering::SeqCst);
+            }
+        };
+        let config = SocketConfig {
+            handler_queue_capacity: Some(FRAMES),
+            handler_concurrency: workers,
+            ..test_config(port, Arc::new(handler))
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < FRAMES {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let mut delivered = received.lock().unwrap().clone();
+        let expected: Vec<Vec<u8>> = (0..FRAMES)
+            .map(|i| format!("msg{i}").into_bytes())
+            .collect();
+        if workers == 1 {
+            assert_eq!(max_running.load(Ordering::SeqCst), 1);
+            assert_eq!(delivered, expected);
+        } else {
+            assert!(
+                max_running.load(Ordering::SeqCst) > 1,
+                "Frames were not handled concurrently"
+            );
+            delivered.sort();
+            assert_eq!(delivered, expected);
+        }
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_handler_reset_is_barrier_across_workers() {
+        const FRAMES: usize = 8;
+
+        #[derive(Default)]
+        struct ResetHandler {
+            running: AtomicUsize,
+            events: std::sync::Mutex<Vec<String>>,
+        }
+
+        impl MessageHandler for ResetHandler {
+            fn handle(&self, data: &[u8]) {
+                self.running.fetch_add(1, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(20));
+                let event = String::from_utf8(data.to_vec()).unwrap();
+                self.events.lock().unwrap().push(event);
+                self.running.fetch_sub(1, Ordering::SeqCst);
+            }
+
+            fn reset(&self) {
+                let running = self.running.load(Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(50));
+                self.events.lock().unwrap().push(format!("reset{running}"));
+            }
+        }
+
+        let handler = Arc::new(ResetHandler::default());
+        let config = SocketConfig {
+            handler_queue_capacity: Some(4 * FRAMES),
+            handler_concurrency: 4,
+            ..test_config(0, handler.clone())
+        };
+        let dispatch = HandlerDispatch::new(&config, &Arc::new(SocketCounters::default()));
+
+        for i in 0..FRAMES {
+            dispatch
+                .handle(format!("old{i}").into(), UnixNanos::default(), None)
+                .await;
+        }
+        dispatch.reset().await;
+        for i in 0..FRAMES {
+            dispatch
+                .handle(format!("new{i}").into(), UnixNanos::default(), None)
+                .await;
+        }
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while handler.events.lock().unwrap().len() < 2 * FRAMES + 1 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let events = handler.events.lock().unwrap().clone();
+        let reset = events.iter().position(|e| e.starts_with("reset")).unwrap();
+        assert_eq!(
+            events[reset], "reset0",
+            "Reset ran while frames were handled"
+        );
+        assert!(
+            events[..reset].iter().all(|e| e.starts_with("old")),
+            "{events:?}"
+        );
+        assert!(
+            events[reset + 1..].iter().all(|e| e.starts_with("new")),
+            "{events:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handler_metrics_record_latency_and_depth() {
+        const FRAMES: usize = 10;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            for i in 0..FRAMES {
+                socket
+                    .write_all(format!("msg{i}\r\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (inner_handler, received) = create_rust_handler();
+        let handler = move |data: &[u8]| {
+            std::thread::sleep(Duration::from_millis(20));
+            inner_handler.handle(data);
+        };
+        let config = SocketConfig {
+            handler_queue_capacity: Some(FRAMES),
+            ..test_config(port, Arc::new(handler))
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < FRAMES {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Frames not handled");
+
+        let stats = client.stats();
+        assert_eq!(stats.handler_queue_depth, 0);
+        assert!(stats.handler_queue_max_depth > 1, "{stats:?}");
+        assert!(
+            (20_000..200_000).contains(&stats.handler_latency_avg_us),
+            "{stats:?}"
+        );
+        assert!(stats.handler_latency_max_us >= stats.handler_latency_avg_us);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_zero_shutdown_linger_speeds_up_close() {
+        async fn close_duration(shutdown_linger_ms: Option<u64>) -> Duration {
+            let (port, listener) = bind_test_server();
+            // Keep the connection open so the read task never observes EOF
+            let server_task = task::spawn(async move {
+                let (_socket, _) = tokio::net::TcpListener::from_std(listener)
+                    .unwrap()
+                    .accept()
+                    .await
+                    .unwrap();
+                sleep(Duration::from_secs(60)).await;
+            });
+
+            let (handler, _) = create_rust_handler();
+            let config = SocketConfig {
+                shutdown_linger_ms,
+                ..test_config(port, handler)
+            };
+            let client = SocketClient::connect(config, None, None, None)
+                .await
+                .unwrap();
+
+            let start = tokio::time::Instant::now();
+            client.close().await;
+            let elapsed = start.elapsed();
+            assert!(client.is_closed());
+            server_task.abort();
+            elapsed
+        }
+
+        let default_linger = close_duration(None).await;
+        let zero_linger = close_duration(Some(0)).await;
+
+        assert!(
+            default_linger >= Duration::from_millis(100),
+            "{default_linger:?}"
+        );
+        assert!(
+            zero_linger + Duration::from_millis(50) < default_linger,
+            "{zero_linger:?} vs {default_linger:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_close_during_reconnect_leaves_no_active_connection() {
+        let (server_tx, mut server_rx) = tokio::sync::mpsc::unbounded_channel();
+        let connector: SocketConnector = Arc::new(move || {
+            let server_tx = server_tx.clone();
+            Box::pin(async move {
+                sleep(Duration::from_millis(10)).await;
+                let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                let _ = server_tx.send(server_stream);
+                Ok(SocketConnection::new(client_stream))
+            })
+        });
+
+        for delay_ms in 0..20 {
+            let (handler, _) = create_rust_handler();
+            let config = SocketConfig {
+                connector: Some(connector.clone()),
+                max_reconnection_tries: None,
+                shutdown_linger_ms: Some(0),
+                ..test_config(0, handler)
+            };
+            let client = SocketClient::connect(config, None, None, None)
+                .await
+                .unwrap();
+
+            // Terminate the connection, then close at varying points of the reconnect
+            drop(server_rx.recv().await.unwrap());
+            sleep(Duration::from_millis(delay_ms)).await;
+            client.close().await;
+            assert!(client.is_closed(), "Not closed with delay {delay_ms}ms");
+
+            // Every connection established by a reconnect has been dropped by the client
+            sleep(Duration::from_millis(20)).await;
+            while let Ok(mut server_stream) = server_rx.try_recv() {
+                let mut buf = Vec::new();
+                let read = tokio::time::timeout(
+                    Duration::from_secs(1),
+                    server_stream.read_to_end(&mut buf),
+                )
+                .await;
+                assert!(
+                    matches!(read, Ok(Ok(_))),
+                    "Connection lingering with delay {delay_ms}ms"
+                );
+            }
+            assert!(client.is_closed());
+        }
+    }
+
+    #[rstest]
+    #[case(false, ReadExitReason::Eof, false)]
+    #[case(true, ReadExitReason::Io(std::io::ErrorKind::ConnectionReset), true)]
+    #[tokio::test]
+    async fn test_server_close_reason_decides_reconnect(
+        #[case] reset: bool,
+        #[case] expected_reason: ReadExitReason,
+        #[case] expect_reconnect: bool,
+    ) {
+        let (port, listener) = bind_test_server();
+        let (accepted_tx, mut accepted_rx) = tokio::sync::mpsc::unbounded_channel();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            if reset {
+                // A zero linger makes the close send RST instead of FIN
+                socket.set_linger(Some(Duration::ZERO)).unwrap();
+            }
+            drop(socket);
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                accepted_tx.send(socket).unwrap();
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            reconnect_on_eof: false,
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while client.last_exit_reason().is_none() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Connection close not observed");
+
+        let reconnected = tokio::time::timeout(Duration::from_secs(1), accepted_rx.recv())
+            .await
+            .is_ok();
+        assert_eq!(reconnected, expect_reconnect);
+        assert_eq!(client.last_exit_reason(), Some(expected_reason));
+        if expect_reconnect {
+            tokio::time::timeout(Duration::from_secs(2), async {
+                while !client.is_active() {
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("Client did not reconnect");
+        } else {
+            tokio::time::timeout(Duration::from_secs(1), async {
+                while !client.is_closed() {
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("Client did not close");
+        }
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(None, true)]
+    #[case(Some(Arc::new(|_: &SocketError| false) as FatalErrorClassifier), false)]
+    #[tokio::test]
+    async fn test_fatal_reconnect_error_closes_without_retry(
+        #[case] is_fatal_error: Option<FatalErrorClassifier>,
+        #[case] expect_fatal: bool,
+    ) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (server_tx, mut server_rx) = tokio::sync::mpsc::unbounded_channel();
+        let connector: SocketConnector = {
+            let attempts = attempts.clone();
+            Arc::new(move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                let server_tx = server_tx.clone();
+                Box::pin(async move {
+                    if attempt > 0 {
+                        return Err(Error::Io(std::io::Error::new(
+                            std::io::ErrorKind::PermissionDenied,
+                            "Permission denied",
+                        )));
+                    }
+                    let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                    server_tx.send(server_stream).unwrap();
+                    Ok(SocketConnection::new(client_stream))
+                })
+            })
+        };
+
+        let fatal_errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let fatal_errors_clone = fatal_errors.clone();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            max_reconnection_tries: None,
+            connector: Some(connector),
+            is_fatal_error,
+            on_fatal: Some(Arc::new(move |error: &SocketError| {
+                fatal_errors_clone.lock().unwrap().push(error.to_string());
+            })),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        // Terminate the initial connection to force a reconnect
+        drop(server_rx.recv().await.unwrap());
+        sleep(Duration::from_millis(200)).await;
+
+        if expect_fatal {
+            assert_eq!(attempts.load(Ordering::SeqCst), 2);
+            assert!(client.is_closed());
+            assert_eq!(fatal_errors.lock().unwrap().len(), 1);
+        } else {
+            assert!(attempts.load(Ordering::SeqCst) > 2);
+            assert!(!client.is_closed());
+            assert!(fatal_errors.lock().unwrap().is_empty());
+        }
+
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_on_give_up_called_when_retries_exhausted() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            // Drop the listener as well so every reconnect is refused
+            drop(listener);
+            drop(socket);
+        });
+
+        let give_ups = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let give_ups_clone = give_ups.clone();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            max_reconnection_tries: Some(2),
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            on_give_up: Some(Arc::new(move |error: &SocketError, attempts: u64| {
+                give_ups_clone
+                    .lock()
+                    .unwrap()
+                    .push((error.to_string(), attempts));
+            })),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not give up");
+
+        let give_ups = give_ups.lock().unwrap().clone();
+        assert_eq!(give_ups.len(), 1);
+        assert_eq!(give_ups[0].1, 2);
+
+        client.close().await;
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_on_established_called_on_connect_and_reconnect() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            // Drop the first connection to force a reconnect
+            drop(listener.accept().await.unwrap());
+            let (socket, _) = listener.accept().await.unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let established = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let established_clone = established.clone();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                ..BackoffPolicy::default()
+            },
+            on_established: Some(Arc::new(move |initial: bool| {
+                established_clone.lock().unwrap().push(initial);
+            })),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while established.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not reconnect");
+
+        assert_eq!(*established.lock().unwrap(), vec![true, false]);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_on_reconnect_attempt_called_for_each_attempt() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            // Accept the initial connection, then go away for good
+            let (socket, _) = listener.accept().await.unwrap();
+            drop(listener);
+            drop(socket);
+        });
+
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let attempts_clone = attempts.clone();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            max_reconnection_tries: Some(3),
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                ..BackoffPolicy::default()
+            },
+            on_reconnect_attempt: Some(Arc::new(move |attempt| {
+                attempts_clone.lock().unwrap().push(attempt);
+            })),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not give up reconnecting");
+
+        assert_eq!(*attempts.lock().unwrap(), vec![1, 2, 3]);
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_max_reconnect_duration_closes_on_schedule() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (server_tx, mut server_rx) = tokio::sync::mpsc::unbounded_channel();
+        let connector: SocketConnector = {
+            let attempts = attempts.clone();
+            Arc::new(move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                let server_tx = server_tx.clone();
+                Box::pin(async move {
+                    if attempt > 0 {
+                        // Every reconnect hangs far beyond the overall deadline
+                        sleep(Duration::from_secs(60)).await;
+                    }
+                    let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                    server_tx.send(server_stream).unwrap();
+                    Ok(SocketConnection::new(client_stream))
+                })
+            })
+        };
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            connector: Some(connector),
+            reconnect_timeout_secs: Some(30),
+            max_reconnection_tries: None,
+            max_reconnect_duration_secs: Some(1),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        drop(server_rx.recv().await.unwrap());
+        let start = tokio::time::Instant::now();
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not close at the reconnect deadline");
+
+        let elapsed = start.elapsed();
+        assert!(elapsed < Duration::from_millis(1_500), "{elapsed:?}");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        client.close().await;
+    }
+
+    #[rstest]
+    fn test_socket_config_defaults() {
+        let config = SocketConfig::default();
+
+        assert!(config.url.is_empty());
+        assert!(matches!(config.mode, Mode::Plain));
+        assert_eq!(config.framing, Framing::Delimiter(b"\n".to_vec()));
+        assert!(config.heartbeat.is_none());
+        assert_eq!(config.reconnect_timeout_secs, Some(30));
+        assert!(config.max_reconnection_tries.is_none());
+        assert!(config.reconnect_on_eof);
+    }
+
+    #[rstest]
+    fn test_socket_config_builder() {
+        let config = SocketConfig::builder()
+            .url("127.0.0.1:8080")
+            .suffix(b"\r\n".to_vec())
+            .heartbeat(5, b"ping".to_vec())
+            .max_reconnection_tries(3)
+            .handler_queue_capacity(16)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.url, "127.0.0.1:8080");
+        assert_eq!(config.framing, Framing::Delimiter(b"\r\n".to_vec()));
+        assert_eq!(
+            config.heartbeat,
+            Some((Duration::from_secs(5), b"ping".to_vec()))
+        );
+        assert_eq!(config.max_reconnection_tries, Some(3));
+        assert_eq!(config.handler_queue_capacity, Some(16));
+        assert_eq!(config.reconnect_timeout_secs, Some(30));
+    }
+
+    #[rstest]
+    #[case(SocketConfig::builder(), "`url` must not be empty")]
+    #[case(
+        SocketConfig::builder().url("127.0.0.1:8080").suffix(Vec::new()),
+        "`framing` suffix must not be empty"
+    )]
+    fn test_socket_config_builder_validates(
+        #[case] builder: SocketConfigBuilder,
+        #[case] expected: &str,
+    ) {
+        let err = builder.build().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(String::new(), Framing::Delimiter(b"\n".to_vec()), None, "`url` must not be empty")]
+    #[case(
+        "127.0.0.1:0".to_string(),
+        Framing::Delimiter(Vec::new()),
+        None,
+        "`framing` suffix must not be empty"
+    )]
+    #[case(
+        "127.0.0.1:0".to_string(),
+        Framing::Delimiter(b"\n".to_vec()),
+        Some((Duration::ZERO, b"ping".to_vec())),
+        "`heartbeat` interval must be positive"
+    )]
+    #[tokio::test]
+    async fn test_connect_rejects_invalid_config(
+        #[case] url: String,
+        #[case] framing: Framing,
+        #[case] heartbeat: Option<(Duration, Vec<u8>)>,
+        #[case] expected: &str,
+    ) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            url,
+            framing,
+            heartbeat,
+            ..test_config(0, handler)
+        };
+
+        let Err(err) = SocketClient::connect(config, None, None, None).await else {
+            panic!("invalid config should be rejected");
+        };
+
+        assert!(err.to_string().contains(expected), "{err}");
+    }
+
+    #[rstest]
+    fn test_interleave_families() {
+        let v4 = |port| SocketAddr::from(([127, 0, 0, 1], port));
+        let v6 = |port| SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, port));
+
+        let ordered = interleave_families(vec![v6(1), v6(2), v6(3), v4(4), v4(5)]);
+
+        assert_eq!(ordered, vec![v6(1), v4(4), v6(2), v4(5), v6(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_happy_eyeballs_falls_through_dead_address() {
+        let (port, _listener) = bind_test_server();
+        let (dead_port, dead_listener) = bind_test_server();
+        drop(dead_listener);
+        let dead = SocketAddr::from(([127, 0, 0, 1], dead_port));
+        let live = SocketAddr::from(([127, 0, 0, 1], port));
+
+        // A refused attempt starts the next one without waiting out the delay
+        let stream = tokio::time::timeout(
+            Duration::from_secs(1),
+            connect_happy_eyeballs(
+                vec![dead, live],
+                Duration::from_secs(10),
+                None,
+                TcpOptions::default(),
+            ),
+        )
+        .await
+        .expect("dead address was not skipped")
+        .unwrap();
+
+        assert_eq!(stream.peer_addr().unwrap(), live);
+    }
+
+    #[tokio::test]
+    async fn test_connect_all_addresses_fail() {
+        let (dead_port, dead_listener) = bind_test_server();
+        drop(dead_listener);
+        let dead = SocketAddr::from(([127, 0, 0, 1], dead_port));
+
+        let err = connect_happy_eyeballs(
+            vec![dead, dead],
+            Duration::from_millis(50),
+            None,
+            TcpOptions::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_bind_address_sets_local_addr() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, peer) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+            peer
+        });
+
+        // Any address of 127.0.0.0/8 is local on Linux
+        let bind_ip = std::net::Ipv4Addr::new(127, 0, 0, 2);
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            bind_address: Some(SocketAddr::from((bind_ip, 0))),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        let local_addr = client.local_addr().unwrap();
+        assert_eq!(local_addr.ip(), bind_ip);
+
+        client.close().await;
+        let peer = server_task.await.unwrap();
+        assert_eq!(peer, local_addr);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_raw_fd_reads_socket_option() {
+        use std::os::fd::BorrowedFd;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            tcp_options: TcpOptions {
+                nodelay: true,
+                ..TcpOptions::default()
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let raw_fd = client.raw_fd().expect("no file descriptor");
+        // SAFETY: the client keeps the connection, and so the descriptor, open
+        let fd = unsafe { BorrowedFd::borrow_raw(raw_fd) };
+        let socket = SockRef::from(&fd);
+        assert!(socket.nodelay().unwrap());
+        assert_eq!(socket.peer_addr().unwrap().as_socket(), client.peer_addr());
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[rstest]
+    #[case("127.0.0.1")]
+    #[case("[::1]")]
+    #[tokio::test]
+    async fn test_dscp_sets_ip_tos(#[case] host: &str) {
+        use std::os::fd::BorrowedFd;
+
+        let listener = TcpListener::bind(format!("{host}:0")).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            url: format!("{host}:{port}"),
+            tcp_options: TcpOptions {
+                dscp: Some(46),
+                ..TcpOptions::default()
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let raw_fd = client.raw_fd().expect("no file descriptor");
+        // SAFETY: the client keeps the connection, and so the descriptor, open
+        let fd = unsafe { BorrowedFd::borrow_raw(raw_fd) };
+        let socket = SockRef::from(&fd);
+        let marked = if listener.local_addr().unwrap().is_ipv6() {
+            socket.tclass_v6().unwrap()
+        } else {
+            socket.tos().unwrap()
+        };
+        assert_eq!(marked, 46 << 2);
+
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_dscp_out_of_range_rejected() {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            tcp_options: TcpOptions {
+                dscp: Some(64),
+                ..TcpOptions::default()
+            },
+            ..test_config(0, handler)
+        };
+
+        let Err(err) = SocketClient::connect(config, None, None, None).await else {
+            panic!("out of range DSCP should be rejected");
+        };
+
+        assert!(err.to_string().contains("`tcp_options.dscp`"), "{err}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_zero_linger_resets_on_close() {
+        use std::os::fd::BorrowedFd;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = Vec::new();
+            loop {
+                match socket.read_buf(&mut buf).await {
+                    Ok(0) => return Ok(()),
+                    Ok(_) => {}
+                    Err(e) => return Err(e.kind()),
+                }
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            tcp_options: TcpOptions {
+                linger: Some(Duration::ZERO),
+                ..TcpOptions::default()
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let raw_fd = client.raw_fd().expect("no file descriptor");
+        // SAFETY: the client keeps the connection, and so the descriptor, open
+        let fd = unsafe { BorrowedFd::borrow_raw(raw_fd) };
+        assert_eq!(SockRef::from(&fd).linger().unwrap(), Some(Duration::ZERO));
+
+        client.close().await;
+
+        // The server sees a reset instead of a clean EOF
+        let result = tokio::time::timeout(Duration::from_secs(2), server_task)
+            .await
+            .expect("server did not observe the close")
+            .unwrap();
+        assert_eq!(result, Err(std::io::ErrorKind::ConnectionReset));
+    }
+
+    #[tokio::test]
+    async fn test_bind_address_family_mismatch() {
+        let (port, _listener) = bind_test_server();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            bind_address: Some(SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, 0))),
+            ..test_config(port, handler)
+        };
+
+        let Err(err) = SocketClient::connect(config, None, None, None).await else {
+            panic!("connect should fail without an address of the bind family");
+        };
+
+        assert!(err.to_string().contains("IP family"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_raw_framing_delivers_chunks_verbatim() {
+        let (port, listener) = bind_test_server();
+        let (sent_tx, sent_rx) = tokio::sync::oneshot::channel::<Vec<u8>>();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = [0u8; 64];
+            let n = socket.read(&mut buf).await.unwrap();
+            sent_tx.send(buf[..n].to_vec()).unwrap();
+
+            for chunk in [b"abc\r\n".as_slice(), b"def"] {
+                socket.write_all(chunk).await.unwrap();
+                sleep(Duration::from_millis(100)).await;
+            }
+            sleep(Duration::from_secs(1)).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            framing: Framing::Raw,
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        client.send_bytes(b"hello").await.unwrap();
+        let sent = tokio::time::timeout(Duration::from_secs(2), sent_rx)
+            .await
+            .expect("Timed out waiting for sent bytes")
+            .unwrap();
+        assert_eq!(sent, b"hello");
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for chunks");
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"abc\r\n".to_vec(), b"def".to_vec()]
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_separate_send_and_recv_suffixes() {
+        let (port, listener) = bind_test_server();
+        let (sent_tx, sent_rx) = tokio::sync::oneshot::channel::<Vec<u8>>();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut sent = Vec::new();
+            let mut buf = [0u8; 64];
+            while !sent.ends_with(b"world\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                sent.extend_from_slice(&buf[..n]);
+            }
+            sent_tx.send(sent).unwrap();
+
+            socket.write_all(b"one\ntwo\r\nthree\r\n").await.unwrap();
+            sleep(Duration::from_secs(1)).await;
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            framing: Framing::Delimiter(b"\r\n".to_vec()),
+            send_framing: Some(Framing::Delimiter(b"\n".to_vec())),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        client.send_bytes(b"hello").await.unwrap();
+        client.send_bytes(b"world").await.unwrap();
+        let sent = tokio::time::timeout(Duration::from_secs(2), sent_rx)
+            .await
+            .expect("Timed out waiting for sent bytes")
+            .unwrap();
+        assert_eq!(sent, b"hello\nworld\n");
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"one\ntwo".to_vec(), b"three".to_vec()]
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    fn test_builder_suffix_sets_both_directions() {
+        let config = SocketConfig::builder()
+            .url("127.0.0.1:8080")
+            .send_suffix(b"\n".to_vec())
+            .recv_suffix(b"\r\n".to_vec())
+            .build()
+            .unwrap();
+        assert_eq!(config.send_framing(), &Framing::Delimiter(b"\n".to_vec()));
+        assert_eq!(config.framing, Framing::Delimiter(b"\r\n".to_vec()));
+
+        let config = SocketConfig::builder()
+            .url("127.0.0.1:8080")
+            .send_suffix(b"\n".to_vec())
+            .suffix(b"\r\n".to_vec())
+            .build()
+            .unwrap();
+        assert_eq!(config.send_framing(), &config.framing);
+    }
+
+    #[tokio::test]
+    async fn test_handler_receives_positions_reset_on_reconnect() {
+        type PositionedFrames = Arc<std::sync::Mutex<Vec<(Vec<u8>, FramePosition)>>>;
+
+        struct PositionRecorder(PositionedFrames);
+
+        impl MessageHandler for PositionRecorder {
+            fn handle(&self, _data: &[u8]) {
+                panic!("handler should be called with a position");
+            }
+
+            fn handle_with_position(&self, data: &[u8], position: FramePosition) {
+                self.0.lock().unwrap().push((data.to_vec(), position));
+            }
+        }
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"a\r\nbb\r\n").await.unwrap();
+            sleep(Duration::from_millis(50)).await;
+            socket.write_all(b"ccc\r\n").await.unwrap();
+            sleep(Duration::from_millis(50)).await;
+            drop(socket);
+
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(b"dd\r\ne\r\n").await.unwrap();
+            sleep(Duration::from_secs(1)).await;
+        });
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let config = SocketConfig {
+            handler_receive_position: true,
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                ..BackoffPolicy::default()
+            },
+            ..test_config(port, Arc::new(PositionRecorder(received.clone())))
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 5 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let position = |seq, offset| FramePosition { seq, offset };
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                (b"a".to_vec(), position(0, 0)),
+                (b"bb".to_vec(), position(1, 3)),
+                (b"ccc".to_vec(), position(2, 7)),
+                (b"dd".to_vec(), position(0, 0)),
+                (b"e".to_vec(), position(1, 4)),
+            ]
+        );
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_handler_receives_monotonic_receive_timestamps() {
+        type TimestampedFrames = Arc<std::sync::Mutex<Vec<(Vec<u8>, UnixNanos)>>>;
+
+        struct TimestampRecorder(TimestampedFrames);
+
+        impl MessageHandler for TimestampRecorder {
+            fn handle(&self, _data: &[u8]) {
+                panic!("handler should be called with a timestamp");
+            }
+
+            fn handle_with_ts(&self, data: &[u8], ts_recv: UnixNanos) {
+                self.0.lock().unwrap().push((data.to_vec(), ts_recv));
+            }
+        }
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            socket.write_all(b"a\r\nb\r\n").await.unwrap();
+            sleep(Duration::from_millis(50)).await;
+            socket.write_all(b"c\r\n").await.unwrap();
+            sleep(Duration::from_secs(1)).await;
+        });
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let config = SocketConfig {
+            handler_receive_ts: true,
+            ..test_config(port, Arc::new(TimestampRecorder(received.clone())))
+        };
+        let before = get_atomic_clock_realtime().get_time_ns();
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 3 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let received = received.lock().unwrap().clone();
+        let frames: Vec<&[u8]> = received.iter().map(|(data, _)| data.as_slice()).collect();
+        assert_eq!(frames, vec![b"a".as_slice(), b"b", b"c"]);
+        assert!(received[0].1 > before);
+        assert!(received.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert!(received[2].1 > received[0].1);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_on_backpressure_is_edge_triggered() {
+        const FRAMES: usize = 10;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            for i in 0..FRAMES {
+                socket
+                    .write_all(format!("msg{i}\r\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (inner_handler, received) = create_rust_handler();
+        let handler = move |data: &[u8]| {
+            std::thread::sleep(Duration::from_millis(20));
+            inner_handler.handle(data);
+        };
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        let config = SocketConfig {
+            handler_queue_capacity: Some(FRAMES),
+            handler_queue_high_water: Some(4),
+            handler_queue_low_water: Some(1),
+            on_backpressure: Some(Arc::new(move |depth: usize, active: bool| {
+                events_clone.lock().unwrap().push((depth, active));
+            })),
+            ..test_config(port, Arc::new(handler))
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < FRAMES {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for frames");
+
+        let events = events.lock().unwrap().clone();
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert!(events[0].1 && events[0].0 >= 4);
+        assert!(!events[1].1 && events[1].0 <= 1);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(None, Some(4), None)]
+    #[case(Some(8), Some(16), None)]
+    #[case(Some(8), Some(4), Some(4))]
+    #[case(Some(8), None, Some(1))]
+    fn test_invalid_handler_queue_water_marks_rejected(
+        #[case] capacity: Option<usize>,
+        #[case] high_water: Option<usize>,
+        #[case] low_water: Option<usize>,
+    ) {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            handler_queue_capacity: capacity,
+            handler_queue_high_water: high_water,
+            handler_queue_low_water: low_water,
+            ..test_config(0, handler)
+        };
+
+        assert!(config.validate().is_err());
+    }
+
+    #[rstest]
+    #[case(true, 10)]
+    #[case(false, 0)]
+    #[tokio::test]
+    async fn test_writer_drains_queued_frames_on_shutdown(
+        #[case] drain: bool,
+        #[case] expected: usize,
+    ) {
+        let (writer_tx, writer_rx) = mpsc::channel(WRITER_CHANNEL_CAPACITY);
+        for i in 0..10 {
+            writer_tx
+                .send(format!("{i:03}\r\n").into_bytes())
+                .await
+                .unwrap();
+        }
+
+        let writer = RecordingWriter::default();
+        let WriterTask {
+            handle,
+            shutdown_tx,
+        } = SocketClientInner::spawn_writer_task(
+            writer.clone(),
+            Arc::new(Mutex::new(writer_rx)),
+            Arc::new(Mutex::new(mpsc::unbounded_channel().1)),
+            None,
+            false,
+            true,
+            None,
+        );
+        // The shutdown signal is observed before any queued frame
+        shutdown_tx
+            .send(drain.then_some(Duration::from_secs(2)))
+            .unwrap();
+        handle.await.unwrap();
+
+        let written = writer.writes.lock().unwrap().concat();
+        assert_eq!(written.len(), expected * 5);
+    }
+
+    #[tokio::test]
+    async fn test_close_delivers_queued_frames() {
+        const FRAMES: usize = 50;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut received = Vec::new();
+            socket.read_to_end(&mut received).await.unwrap();
+            received
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        for i in 0..FRAMES {
+            client
+                .send_bytes(format!("order{i:02}").as_bytes())
+                .await
+                .unwrap();
+        }
+        client.close().await;
+
+        let received = tokio::time::timeout(Duration::from_secs(2), server_task)
+            .await
+            .expect("Timed out waiting for server")
+            .unwrap();
+        let expected: Vec<u8> = (0..FRAMES)
+            .flat_map(|i| format!("order{i:02}\r\n").into_bytes())
+            .collect();
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_connect_stream_yields_frames() {
+        const FRAMES: usize = 5;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = [0u8; 1024];
+            while let Ok(n) = socket.read(&mut buf).await {
+                if n == 0 || socket.write_all(&buf[..n]).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let (client, mut stream) =
+            SocketClient::connect_stream(test_config(port, handler), None, None, None)
+                .await
+                .unwrap();
+
+        for i in 0..FRAMES {
+            client
+                .send_bytes(format!("msg{i}").as_bytes())
+                .await
+                .unwrap();
+        }
+
+        let frames: Vec<Bytes> = tokio::time::timeout(
+            Duration::from_secs(2),
+            stream.by_ref().take(FRAMES).collect(),
+        )
+        .await
+        .expect("Timed out waiting for frames");
+
+        let expected: Vec<Bytes> = (0..FRAMES).map(|i| format!("msg{i}").into()).collect();
+        assert_eq!(frames, expected);
+        assert!(received.lock().unwrap().is_empty());
+
+        client.close().await;
+        let next = tokio::time::timeout(Duration::from_secs(2), stream.next())
+            .await
+            .expect("Stream did not end after close");
+        assert_eq!(next, None);
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_connect_stream_bounds_buffered_frames() {
+        const CAPACITY: usize = 4;
+        const FRAMES: usize = 100;
+
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            for i in 0..FRAMES {
+                socket
+                    .write_all(format!("msg{i}\r\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            handler_queue_capacity: Some(CAPACITY),
+            ..test_config(port, handler)
+        };
+        let (client, mut stream) = SocketClient::connect_stream(config, None, None, None)
+            .await
+            .unwrap();
+
+        // Not consuming the stream lets the buffers fill up to their capacity only
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(stream.rx.len(), CAPACITY);
+        assert!(client.handler_queue_depth() <= CAPACITY);
+
+        let frames: Vec<Bytes> = tokio::time::timeout(
+            Duration::from_secs(2),
+            stream.by_ref().take(FRAMES).collect(),
+        )
+        .await
+        .expect("Timed out waiting for frames");
+
+        let expected: Vec<Bytes> = (0..FRAMES).map(|i| format!("msg{i}").into()).collect();
+        assert_eq!(frames, expected);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_connect_stream_rejects_handler_concurrency() {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            handler_queue_capacity: Some(16),
+            handler_concurrency: 4,
+            ..test_config(0, handler)
+        };
+
+        let Err(err) = SocketClient::connect_stream(config, None, None, None).await else {
+            panic!("handler concurrency should be rejected");
+        };
+
+        assert!(err.to_string().contains("`handler_concurrency`"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_sink_forwards_frames() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut received = Vec::new();
+            socket.read_to_end(&mut received).await.unwrap();
+            received
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        let frames =
+            futures::stream::iter(["a", "bc", "def"]).map(|data| Ok(data.as_bytes().to_vec()));
+        frames.forward(client.sink()).await.unwrap();
+        client.close().await;
+
+        let received = tokio::time::timeout(Duration::from_secs(2), server_task)
+            .await
+            .expect("Timed out waiting for server")
+            .unwrap();
+        assert_eq!(received, b"a\r\nbc\r\ndef\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_token_aborts_stalled_connect() {
+        // The server accepts but never completes the TLS handshake
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (_socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let token = CancellationToken::new();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            mode: Mode::Tls,
+            tls_sni: Some("localhost".to_string()),
+            cancellation_token: Some(token.clone()),
+            ..test_config(port, handler)
+        };
+        let cancel_task = task::spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            token.cancel();
+        });
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(2),
+            SocketClient::connect(config, None, None, None),
+        )
+        .await
+        .expect("Connect was not cancelled");
+
+        assert!(matches!(result, Err(SocketError::Cancelled)));
+        cancel_task.await.unwrap();
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_token_closes_client() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (_socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let token = CancellationToken::new();
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            cancellation_token: Some(token.clone()),
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        assert!(client.is_active());
+
+        token.cancel();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not close after cancellation");
+        assert!(matches!(
+            client.send_bytes(b"late").await,
+            Err(SocketError::Closed)
+        ));
+
+        server_task.abort();
+    }
+
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    #[tokio::test]
+    async fn test_flush_writes_buffered_frames(#[case] auto_flush: bool) {
+        let (writer_tx, writer_rx) = mpsc::channel(WRITER_CHANNEL_CAPACITY);
+        let (flush_tx, flush_rx) = mpsc::unbounded_channel();
+        let writer = RecordingWriter::default();
+        let WriterTask {
+            handle,
+            shutdown_tx,
+        } = SocketClientInner::spawn_writer_task(
+            tokio::io::BufWriter::new(writer.clone()),
+            Arc::new(Mutex::new(writer_rx)),
+            Arc::new(Mutex::new(flush_rx)),
+            None,
+            auto_flush,
+            true,
+            None,
+        );
+
+        for i in 0..3 {
+            writer_tx
+                .send(format!("{i:03}\r\n").into_bytes())
+                .await
+                .unwrap();
+        }
+        while writer_tx.capacity() < WRITER_CHANNEL_CAPACITY {
+            sleep(Duration::from_millis(1)).await;
+        }
+        sleep(Duration::from_millis(10)).await;
+        let written_before_flush = writer.writes.lock().unwrap().concat();
+
+        request_flush(&flush_tx).await.unwrap();
+        let written = writer.writes.lock().unwrap().concat();
+
+        if auto_flush {
+            assert_eq!(written_before_flush.len(), 15);
+        } else {
+            assert!(written_before_flush.is_empty());
+        }
+        assert_eq!(written, b"000\r\n001\r\n002\r\n");
+
+        shutdown_tx.send(None).unwrap();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_flush_delivers_sent_frames() {
+        let (port, listener) = bind_test_server();
+        let (received_tx, received_rx) = tokio::sync::oneshot::channel::<Vec<u8>>();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 64];
+            while !received.ends_with(b"cancel\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                received.extend_from_slice(&buf[..n]);
+            }
+            received_tx.send(received).unwrap();
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            auto_flush: false,
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        client.send_bytes(b"order").await.unwrap();
+        client.send_bytes(b"cancel").await.unwrap();
+        client.flush().await.unwrap();
+
+        let received = tokio::time::timeout(Duration::from_secs(1), received_rx)
+            .await
+            .expect("Frames not delivered after flush")
+            .unwrap();
+        assert_eq!(received, b"order\r\ncancel\r\n");
+
+        client.close().await;
+        assert!(matches!(client.flush().await, Err(SocketError::Closed)));
+        server_task.abort();
+    }
+
+    /// Returns a connector over in-memory streams recording each writer, where every
+    /// connection after the first waits for a permit from `gate`.
+    fn gated_connector(
+        writers: &Arc<std::sync::Mutex<Vec<FlakyWriter>>>,
+        gate: &Arc<tokio::sync::Semaphore>,
+    ) -> SocketConnector {
+        let writers = writers.clone();
+        let gate = gate.clone();
+        let server_streams = Arc::new(std::sync::Mutex::new(Vec::new()));
+        Arc::new(move || {
+            let (client_stream, server_stream) = tokio::io::duplex(1_024);
+            server_streams.lock().unwrap().push(server_stream);
+            let writer = FlakyWriter::default();
+            let mut writers = writers.lock().unwrap();
+            let gated = !writers.is_empty();
+            writers.push(writer.clone());
+            let gate = gate.clone();
+            Box::pin(async move {
+                if gated {
+                    gate.acquire().await.unwrap().forget();
+                }
+                Ok(SocketConnection {
+                    reader: Box::new(client_stream),
+                    writer: Box::new(writer),
+                    peer_addr: None,
+                    local_addr: None,
+                    url: None,
+                    alpn_protocol: None,
+                    #[cfg(unix)]
+                    raw_fd: None,
+                })
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_send_bytes_flushed_covers_frame_queued_during_reconnect() {
+        let writers = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let gate = Arc::new(tokio::sync::Semaphore::new(0));
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            connector: Some(gated_connector(&writers, &gate)),
+            reconnect_queue_capacity: Some(8),
+            ..test_config(0, handler)
+        };
+        let client = Arc::new(
+            SocketClient::connect(config, None, None, None)
+                .await
+                .unwrap(),
+        );
+
+        let reconnecting_client = client.clone();
+        let reconnect = task::spawn(async move { reconnecting_client.reconnect_now().await });
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while writers.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Reconnect did not start");
+        assert!(client.is_reconnecting());
+
+        let sending_client = client.clone();
+        let send = task::spawn(async move { sending_client.send_bytes_flushed(b"queued").await });
+        sleep(Duration::from_millis(50)).await;
+        assert!(!send.is_finished());
+
+        gate.add_permits(1);
+        send.await.unwrap().unwrap();
+        // The flush only completes once the queued frame is written on the new connection
+        let writer = writers.lock().unwrap()[1].clone();
+        assert_eq!(*writer.written.lock().unwrap(), b"queued\r\n");
+        reconnect.await.unwrap().unwrap();
+
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_flush_bounded_by_reconnect_timeout() {
+        let writers = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let gate = Arc::new(tokio::sync::Semaphore::new(0));
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            connector: Some(gated_connector(&writers, &gate)),
+            reconnect_timeout_secs: Some(1),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        writers.lock().unwrap()[0]
+            .stall_flush
+            .store(true, Ordering::SeqCst);
+
+        let start = Instant::now();
+        let result = client.send_bytes_flushed(b"stalled").await;
+        assert!(
+            matches!(result, Err(SocketError::Timeout(_))),
+            "Expected a timeout, got {result:?}"
+        );
+        assert!(start.elapsed() < Duration::from_secs(3));
+
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_bytes_flushed_waits_for_server_to_drain() {
+        let (port, listener) = bind_test_server();
+        // Small buffers so the payload cannot sit in the kernel on either side
+        SockRef::from(&listener)
+            .set_recv_buffer_size(16 * 1024)
+            .unwrap();
+        let draining = Arc::new(AtomicBool::new(false));
+        let drained = Arc::new(AtomicUsize::new(0));
+        let (draining_clone, drained_clone) = (draining.clone(), drained.clone());
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            sleep(Duration::from_millis(300)).await;
+            draining_clone.store(true, Ordering::SeqCst);
+            let mut buf = vec![0; 64 * 1024];
+            while let Ok(n @ 1..) = socket.read(&mut buf).await {
+                drained_clone.fetch_add(n, Ordering::SeqCst);
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            tcp_options: TcpOptions {
+                send_buffer_size: Some(16 * 1024),
+                ..TcpOptions::default()
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        let payload = vec![b'x'; 4 * 1024 * 1024];
+
+        client.send_bytes(&payload).await.unwrap();
+        assert!(!draining.load(Ordering::SeqCst));
+
+        client.send_bytes_flushed(&payload).await.unwrap();
+        assert!(draining.load(Ordering::SeqCst));
+        // Only what fits in the socket buffers can still be in flight
+        let sent = 2 * (payload.len() + 2);
+        assert!(drained.load(Ordering::SeqCst) >= sent - 1024 * 1024);
+
+        client.close().await;
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_drop_without_close_sends_fin() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            let mut buf = Vec::new();
+            tokio::time::timeout(Duration::from_secs(2), socket.read_to_end(&mut buf)).await
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        assert!(client.is_active());
+        drop(client);
+
+        let result = server_task.await.unwrap();
+        assert!(
+            matches!(result, Ok(Ok(0))),
+            "Expected the server to observe a FIN, got {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_shutdown_is_idempotent() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
+
+        let (handler, _) = create_rust_handler();
+        let mut inner = SocketClientInner::connect_url(test_config(port, handler))
+            .await
+            .unwrap();
+        assert!(inner.is_alive());
+
+        inner.shutdown(true, None).await;
+        inner.shutdown(true, None).await;
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while inner.is_alive() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Read task still running after shutdown");
+        assert!(inner.writer_task.is_none());
+        assert!(inner.heartbeat_task.is_none());
+        assert!(logs_contain("Already shut down"));
+        assert!(!logs_contain("ERROR"));
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_read_buffer_reused_across_reconnects() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            for i in 0..4 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                // Each connection leaves an unterminated frame behind when it is dropped
+                socket
+                    .write_all(format!("frame{i}\r\npartial{i}").as_bytes())
+                    .await
+                    .unwrap();
+                task::spawn(async move {
+                    let mut buf = Vec::new();
+                    let _ = socket.read_to_end(&mut buf).await;
+                });
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let mut inner = SocketClientInner::connect_url(test_config(port, handler))
+            .await
+            .unwrap();
+        for i in 0..4 {
+            tokio::time::timeout(Duration::from_secs(2), async {
+                while received.lock().unwrap().len() <= i {
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("Timed out waiting for frame");
+            if i < 3 {
+                inner.reconnect().await.unwrap();
+            }
+        }
+
+        let expected: Vec<Vec<u8>> = (0..4).map(|i| format!("frame{i}").into_bytes()).collect();
+        assert_eq!(*received.lock().unwrap(), expected);
+        assert_eq!(inner.read_buffers.allocations(), 1);
+
+        inner.shutdown(false, None).await;
+        server_task.abort();
+    }
+
+    #[rstest]
+    fn test_read_buffer_capacity_below_chunk_size_rejected() {
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            read_buffer_capacity: Some(READ_CHUNK_SIZE - 1),
+            ..test_config(0, handler)
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_count_and_uptime() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(client.reconnect_count(), 0);
+        let initial = client.connected_since().unwrap();
+
+        sleep(Duration::from_millis(20)).await;
+        assert!(client.uptime().unwrap() >= Duration::from_millis(20));
+
+        client.reconnect_now().await.unwrap();
+        client.reconnect_now().await.unwrap();
+
+        assert_eq!(client.reconnect_count(), 2);
+        assert_eq!(client.stats().reconnect_count, 2);
+        assert!(client.connected_since().unwrap() > initial);
+
+        client.close().await;
+        assert_eq!(client.reconnect_count(), 2);
+        assert!(client.uptime().is_none());
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_client_id_attached_to_task_spans() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let mut clients = Vec::new();
+        for client_id in ["alpha", "beta"] {
+            let (handler, _) = create_rust_handler();
+            let config = SocketConfig {
+                client_id: Some(client_id.to_string()),
+                ..test_config(port, handler)
+            };
+            clients.push(
+                SocketClient::connect(config, None, None, None)
+                    .await
+                    .unwrap(),
+            );
+        }
+        for client in &clients {
+            client.close().await;
+        }
+
+        assert!(logs_contain("client=alpha"));
+        assert!(logs_contain("client=beta"));
+        server_task.abort();
+    }
+
+    #[rstest]
+    fn test_client_span_generates_unique_ids() {
+        let mut first = SocketConfig::default();
+        let mut second = SocketConfig::default();
+        let _ = client_span(&mut first);
+        let _ = client_span(&mut second);
+
+        assert!(first.client_id.is_some());
+        assert_ne!(first.client_id, second.client_id);
+
+        let mut named = SocketConfig {
+            client_id: Some("named".to_string()),
+            ..SocketConfig::default()
+        };
+        let _ = client_span(&mut named);
+        assert_eq!(named.client_id.as_deref(), Some("named"));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_reconnect_attempt_spans_carry_attempt_number() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let (socket, _) = listener.accept().await.unwrap();
+            // Drop the listener as well so every reconnect is refused
+            drop(listener);
+            drop(socket);
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            max_reconnection_tries: Some(3),
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !client.is_closed() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Client did not give up");
+
+        for attempt in 1..=3 {
+            assert!(logs_contain(&format!(
+                "reconnect_attempt{{attempt={attempt}}}"
+            )));
+        }
+        assert!(!logs_contain("reconnect_attempt{attempt=4}"));
+        assert!(logs_contain("delay_ms=10"));
+
+        client.close().await;
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_active_after_reconnect() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            // Drop the first connection to force a reconnect
+            drop(listener.accept().await.unwrap());
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                task::spawn(run_echo_server(socket));
+            }
+        });
+
+        let (handler, received) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while client.reconnect_count() == 0 && client.is_active() {
+                sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("Client did not observe the disconnect");
+
+        client
+            .wait_until_active(Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert!(client.is_active());
+
+        client.send_bytes(b"after").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().is_empty() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("No echo after reconnect");
+        assert_eq!(*received.lock().unwrap(), vec![b"after".to_vec()]);
+
+        client.close().await;
+        let result = client.wait_until_active(Duration::from_millis(50)).await;
+        assert!(matches!(result, Err(SocketError::Closed)));
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_active_times_out() {
+        let (port, listener) = bind_test_server();
+        let server_task = task::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            // Drop the listener as well so every reconnect is refused
+            let (socket, _) = listener.accept().await.unwrap();
+            drop(listener);
+            drop(socket);
+        });
+
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            ..test_config(port, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+        server_task.await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while client.is_active() {
+                sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("Client did not observe the disconnect");
+
+        let result = client.wait_until_active(Duration::from_millis(50)).await;
+        assert!(matches!(result, Err(SocketError::Timeout(_))));
+
+        client.close().await;
+    }
+
+    /// Writer whose writes never complete.
+    struct StalledWriter;
+
+    impl AsyncWrite for StalledWriter {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            _buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            std::task::Poll::Pending
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
 
-                        if line == b"close" {
-                            let _ = socket.shutdown().await;
-                            return;
-                        }
+    #[tokio::test]
+    async fn test_writer_drain_bounded_by_timeout() {
+        let (writer_tx, writer_rx) = mpsc::channel(WRITER_CHANNEL_CAPACITY);
+        writer_tx.send(b"queued\r\n".to_vec()).await.unwrap();
 
-                        let mut echo_data = line;
-                        echo_data.extend_from_slice(b"\r\n");
-                        if socket.write_all(&echo_data).await.is_err() {
-                            break;
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Server read error: {e}");
-                    break;
-                }
-            }
+        let WriterTask {
+            handle,
+            shutdown_tx,
+        } = SocketClientInner::spawn_writer_task(
+            StalledWriter,
+            Arc::new(Mutex::new(writer_rx)),
+            Arc::new(Mutex::new(mpsc::unbounded_channel().1)),
+            None,
+            false,
+            true,
+            None,
+        );
+        let start = Instant::now();
+        shutdown_tx.send(Some(Duration::from_millis(100))).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("Drain was not bounded by its timeout")
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    /// Writer whose shutdown never completes.
+    struct StalledShutdownWriter;
+
+    impl AsyncWrite for StalledShutdownWriter {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Pending
         }
     }
 
     #[tokio::test]
-    async fn test_basic_send_receive() {
-        prepare_freethreaded_python();
+    async fn test_shutdown_timeout_bounds_close() {
+        let server_streams = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let connector: SocketConnector = {
+            let server_streams = server_streams.clone();
+            Arc::new(move || {
+                let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                server_streams.lock().unwrap().push(server_stream);
+                Box::pin(async move {
+                    Ok(SocketConnection {
+                        reader: Box::new(client_stream),
+                        writer: Box::new(StalledShutdownWriter),
+                        peer_addr: None,
+                        local_addr: None,
+                        url: None,
+                        alpn_protocol: None,
+                        #[cfg(unix)]
+                        raw_fd: None,
+                    })
+                })
+            })
+        };
 
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            connector: Some(connector),
+            shutdown_timeout_secs: Some(1),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let start = Instant::now();
+        client.close().await;
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed >= Duration::from_millis(900),
+            "Close did not wait for the writer: {elapsed:?}"
+        );
+        assert!(
+            elapsed < Duration::from_secs(3),
+            "Close was not bounded by the shutdown timeout: {elapsed:?}"
+        );
+        assert!(client.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_completion() {
         let (port, listener) = bind_test_server();
         let server_task = task::spawn(async move {
             let (socket, _) = tokio::net::TcpListener::from_std(listener)
@@ -740,59 +11044,219 @@ counter = Counter()
             run_echo_server(socket).await;
         });
 
+        let (handler, _) = create_rust_handler();
+        let client = SocketClient::connect(test_config(port, handler), None, None, None)
+            .await
+            .unwrap();
+
+        assert!(client.shutdown().await.is_ok());
+        assert!(client.is_closed());
+        // Shutting down a closed client completes immediately
+        assert!(client.shutdown().await.is_ok());
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_stalled_teardown() {
+        let server_streams = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let connector: SocketConnector = {
+            let server_streams = server_streams.clone();
+            Arc::new(move || {
+                let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                server_streams.lock().unwrap().push(server_stream);
+                Box::pin(async move {
+                    Ok(SocketConnection {
+                        reader: Box::new(client_stream),
+                        writer: Box::new(StalledShutdownWriter),
+                        peer_addr: None,
+                        local_addr: None,
+                        url: None,
+                        alpn_protocol: None,
+                        #[cfg(unix)]
+                        raw_fd: None,
+                    })
+                })
+            })
+        };
+
+        let (handler, _) = create_rust_handler();
         let config = SocketConfig {
-            url: format!("127.0.0.1:{port}"),
-            mode: Mode::Plain,
-            suffix: b"\r\n".to_vec(),
-            handler: Arc::new(create_handler()),
-            heartbeat: None,
-            reconnect_timeout_secs: None,
-            max_reconnection_tries: Some(1),
+            connector: Some(connector),
+            shutdown_timeout_secs: Some(1),
+            ..test_config(0, handler)
+        };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let result = client.shutdown().await;
+        assert!(
+            matches!(result, Err(SocketError::Timeout(_))),
+            "Expected a timeout, got {result:?}"
+        );
+        assert!(client.is_closed());
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while !client.controller_task.is_finished() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Controller task kept running after the timeout");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_suspends_and_resumes_reconnects() {
+        const COOLDOWN: Duration = Duration::from_millis(500);
+
+        // The initial connect and the fifth reconnect succeed, every other attempt fails
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let server_streams = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let connector: SocketConnector = {
+            let attempts = attempts.clone();
+            let server_streams = server_streams.clone();
+            Arc::new(move || {
+                let attempt = {
+                    let mut attempts = attempts.lock().unwrap();
+                    attempts.push(Instant::now());
+                    attempts.len() - 1
+                };
+                let result = if attempt == 0 || attempt == 5 {
+                    let (client_stream, server_stream) = tokio::io::duplex(1_024);
+                    server_streams.lock().unwrap().push(server_stream);
+                    let (reader, writer) = tokio::io::split(client_stream);
+                    Ok(SocketConnection {
+                        reader: Box::new(reader),
+                        writer: Box::new(writer),
+                        peer_addr: None,
+                        local_addr: None,
+                        url: None,
+                        alpn_protocol: None,
+                        #[cfg(unix)]
+                        raw_fd: None,
+                    })
+                } else {
+                    Err(Error::Io(std::io::Error::from(
+                        std::io::ErrorKind::ConnectionRefused,
+                    )))
+                };
+                Box::pin(async move { result })
+            })
         };
 
+        let (handler, _) = create_rust_handler();
+        let config = SocketConfig {
+            connector: Some(connector),
+            max_reconnection_tries: None,
+            backoff: BackoffPolicy {
+                delay_initial: Duration::from_millis(10),
+                delay_max: Duration::from_millis(10),
+                factor: 1.0,
+                jitter: None,
+            },
+            circuit_breaker: Some(CircuitBreakerPolicy {
+                failure_threshold: 3,
+                window: Duration::from_secs(10),
+                cooldown: COOLDOWN,
+            }),
+            ..test_config(0, handler)
+        };
         let client = SocketClient::connect(config, None, None, None)
             .await
-            .expect("Client connect failed unexpectedly");
+            .unwrap();
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
 
-        client.send_bytes(b"Hello").await.unwrap();
-        client.send_bytes(b"World").await.unwrap();
+        // Dropping the server side ends the first connection
+        server_streams.lock().unwrap().clear();
 
-        // Wait a bit for the server to echo them back
-        sleep(Duration::from_millis(100)).await;
+        let wait_for_attempts = |count: usize| {
+            let attempts = attempts.clone();
+            async move {
+                tokio::time::timeout(Duration::from_secs(5), async {
+                    while attempts.lock().unwrap().len() < count {
+                        sleep(Duration::from_millis(5)).await;
+                    }
+                })
+                .await
+                .expect("Timed out waiting for reconnect attempts");
+            }
+        };
 
-        client.send_bytes(b"close").await.unwrap();
-        server_task.await.unwrap();
-        assert!(!client.is_closed());
+        wait_for_attempts(4).await;
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+        assert_eq!(attempts.lock().unwrap().len(), 4, "Reconnected while open");
+
+        // The failed trial opens the breaker again, the next trial succeeds
+        wait_for_attempts(6).await;
+        client
+            .wait_until_active(Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(client.circuit_state(), CircuitState::Closed);
+
+        let attempts = attempts.lock().unwrap().clone();
+        assert!(attempts[3] - attempts[2] < COOLDOWN);
+        assert!(attempts[4] - attempts[3] >= COOLDOWN);
+        assert!(attempts[5] - attempts[4] >= COOLDOWN);
+
+        client.close().await;
     }
 
     #[tokio::test]
-    async fn test_reconnect_fail_exhausted() {
-        prepare_freethreaded_python();
-
+    async fn test_send_rate_limit_paces_sends() {
         let (port, listener) = bind_test_server();
-        drop(listener); // We drop it immediately -> no server is listening
+        let server_task = task::spawn(async move {
+            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+                .unwrap()
+                .accept()
+                .await
+                .unwrap();
+            run_echo_server(socket).await;
+        });
 
+        // A burst of 2 then one frame every 50ms
+        let quota = Quota::with_period(Duration::from_millis(50))
+            .unwrap()
+            .allow_burst(NonZeroU32::new(2).unwrap());
+        let (handler, received) = create_rust_handler();
         let config = SocketConfig {
-            url: format!("127.0.0.1:{port}"),
-            mode: Mode::Plain,
-            suffix: b"\r\n".to_vec(),
-            handler: Arc::new(create_handler()),
-            heartbeat: None,
-            reconnect_timeout_secs: None,
-            max_reconnection_tries: Some(2),
+            send_rate_limit: Some(quota),
+            ..test_config(port, handler)
         };
+        let client = SocketClient::connect(config, None, None, None)
+            .await
+            .unwrap();
+
+        let start = Instant::now();
+        for i in 0..4 {
+            client.send_bytes(format!("{i}").as_bytes()).await.unwrap();
+        }
+        client.send_many(&[b"4", b"5"]).await.unwrap();
+        let elapsed = start.elapsed();
 
-        let client_res = SocketClient::connect(config, None, None, None).await;
         assert!(
-            client_res.is_err(),
-            "Should fail quickly with no server listening"
+            elapsed >= Duration::from_millis(180),
+            "Sends were not paced: {elapsed:?}"
+        );
+        assert!(
+            elapsed < Duration::from_secs(1),
+            "Sends too slow: {elapsed:?}"
         );
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 6 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Not all frames were echoed");
+
+        client.close().await;
+        server_task.abort();
     }
 
     #[tokio::test]
-    async fn test_user_disconnect() {
-        prepare_freethreaded_python();
-
+    async fn test_try_send_bytes_rate_limited() {
         let (port, listener) = bind_test_server();
         let server_task = task::spawn(async move {
             let (socket, _) = tokio::net::TcpListener::from_std(listener)
@@ -800,158 +11264,132 @@ counter = Counter()
                 .accept()
                 .await
                 .unwrap();
-            let mut buf = [0u8; 1024];
-            let _ = socket.try_read(&mut buf);
-
-            loop {
-                sleep(Duration::from_secs(1)).await;
-            }
+            run_echo_server(socket).await;
         });
 
+        let (handler, _) = create_rust_handler();
         let config = SocketConfig {
-            url: format!("127.0.0.1:{port}"),
-            mode: Mode::Plain,
-            suffix: b"\r\n".to_vec(),
-            handler: Arc::new(create_handler()),
-            heartbeat: None,
-            reconnect_timeout_secs: None,
-            max_reconnection_tries: None,
+            send_rate_limit: Some(Quota::per_second(NonZeroU32::new(1).unwrap())),
+            ..test_config(port, handler)
         };
-
         let client = SocketClient::connect(config, None, None, None)
             .await
             .unwrap();
 
+        client.try_send_bytes(b"first").unwrap();
+        let result = client.try_send_bytes(b"second");
+        assert!(matches!(result, Err(SocketError::RateLimited)));
+
         client.close().await;
-        assert!(client.is_closed());
         server_task.abort();
     }
 
     #[tokio::test]
-    async fn test_heartbeat() {
-        prepare_freethreaded_python();
-
+    async fn test_on_send_transforms_payload() {
         let (port, listener) = bind_test_server();
-        let received = Arc::new(Mutex::new(Vec::new()));
-        let received2 = received.clone();
-
         let server_task = task::spawn(async move {
             let (socket, _) = tokio::net::TcpListener::from_std(listener)
                 .unwrap()
                 .accept()
                 .await
                 .unwrap();
-
-            let mut buf = Vec::new();
-            loop {
-                match socket.try_read_buf(&mut buf) {
-                    Ok(0) => break,
-                    Ok(_) => {
-                        while let Some(idx) = buf.windows(2).position(|w| w == b"\r\n") {
-                            let mut line = buf.drain(..idx + 2).collect::<Vec<u8>>();
-                            line.truncate(line.len() - 2);
-                            received2.lock().await.push(line);
-                        }
-                    }
-                    Err(_) => {
-                        tokio::time::sleep(Duration::from_millis(10)).await;
-                    }
-                }
-            }
+            run_echo_server(socket).await;
         });
 
-        // Heartbeat every 1 second
-        let heartbeat = Some((1, b"ping".to_vec()));
-
+        let (handler, received) = create_rust_handler();
         let config = SocketConfig {
-            url: format!("127.0.0.1:{port}"),
-            mode: Mode::Plain,
-            suffix: b"\r\n".to_vec(),
-            handler: Arc::new(create_handler().into()),
-            heartbeat,
-            reconnect_timeout_secs: None,
-            max_reconnection_tries: None,
+            on_send: Some(Arc::new(|data: &[u8]| {
+                if data == b"reject" {
+                    return Err(std::io::Error::other("rejected"));
+                }
+                Ok(data.to_ascii_uppercase())
+            })),
+            ..test_config(port, handler)
         };
-
         let client = SocketClient::connect(config, None, None, None)
             .await
             .unwrap();
 
-        // Wait ~3 seconds to collect some heartbeats
-        sleep(Duration::from_secs(3)).await;
+        client.send_bytes(b"hello").await.unwrap();
+        client.send_many(&[b"a", b"b"]).await.unwrap();
+        let result = client.send_bytes(b"reject").await;
+        assert!(matches!(result, Err(SocketError::Io(ref e)) if e.to_string() == "rejected"));
 
-        {
-            let lock = received.lock().await;
-            let pings = lock
-                .iter()
-                .filter(|line| line == &&b"ping".to_vec())
-                .count();
-            assert!(
-                pings >= 2,
-                "Expected at least 2 heartbeat pings; got {pings}"
-            );
-        }
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 3 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Not all frames were echoed");
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"HELLO".to_vec(), b"A".to_vec(), b"B".to_vec()]
+        );
 
         client.close().await;
         server_task.abort();
     }
 
     #[tokio::test]
-    async fn test_python_handler_error() {
-        prepare_freethreaded_python();
-
+    async fn test_on_recv_transforms_received_messages() {
         let (port, listener) = bind_test_server();
         let server_task = task::spawn(async move {
-            let (socket, _) = tokio::net::TcpListener::from_std(listener)
+            let (mut socket, _) = tokio::net::TcpListener::from_std(listener)
                 .unwrap()
                 .accept()
                 .await
                 .unwrap();
-            run_echo_server(socket).await;
-        });
-
-        let code_raw = r#"
-def handler(bytes_data):
-    txt = bytes_data.decode()
-    if "ERR" in txt:
-        raise ValueError("Simulated error in handler")
-    return
-"#;
-        let code = CString::new(code_raw).unwrap();
-        let filename = CString::new("test".to_string()).unwrap();
-        let module = CString::new("test".to_string()).unwrap();
-
-        let handler = Python::with_gil(|py| {
-            let pymod = PyModule::from_code(py, &code, &filename, &module).unwrap();
-            let func = pymod.getattr("handler").unwrap();
-            Arc::new(func.into_py(py))
+            socket
+                .write_all(b"olleh\r\nbad\r\ndlrow\r\n")
+                .await
+                .unwrap();
+            loop {
+                sleep(Duration::from_secs(1)).await;
+            }
         });
 
+        let skipped = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let skipped_clone = skipped.clone();
+        let (handler, received) = create_rust_handler();
         let config = SocketConfig {
-            url: format!("127.0.0.1:{port}"),
-            mode: Mode::Plain,
-            suffix: b"\r\n".to_vec(),
-            handler,
-            heartbeat: None,
-            reconnect_timeout_secs: None,
-            max_reconnection_tries: Some(1),
+            on_recv: Some(Arc::new(|data: &[u8]| {
+                if data == b"bad" {
+                    return Err(std::io::Error::other("corrupt"));
+                }
+                Ok(data.iter().rev().copied().collect())
+            })),
+            on_recv_error: Some(Arc::new(move |data: &[u8], e: &std::io::Error| {
+                skipped_clone
+                    .lock()
+                    .unwrap()
+                    .push((data.to_vec(), e.to_string()));
+            })),
+            ..test_config(port, handler)
         };
-
         let client = SocketClient::connect(config, None, None, None)
             .await
-            .expect("Client connect failed unexpectedly");
-
-        client.send_bytes(b"hello").await.unwrap();
-        sleep(Duration::from_millis(100)).await;
+            .unwrap();
 
-        client.send_bytes(b"ERR").await.unwrap();
-        sleep(Duration::from_secs(1)).await;
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while received.lock().unwrap().len() < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for messages");
 
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![b"hello".to_vec(), b"world".to_vec()]
+        );
+        assert_eq!(
+            *skipped.lock().unwrap(),
+            vec![(b"bad".to_vec(), "corrupt".to_string())]
+        );
         assert!(client.is_active());
 
         client.close().await;
-
-        assert!(client.is_closed());
         server_task.abort();
     }
 }
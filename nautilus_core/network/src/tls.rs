@@ -15,6 +15,15 @@
 
 //! Module for wrapping raw socket streams with TLS encryption.
 
+use std::sync::Arc;
+
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+    ClientConfig, DigitallySignedStruct, SignatureScheme,
+};
+use rustls_platform_verifier::Verifier;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_tungstenite::{
     tungstenite::{handshake::client::Request, stream::Mode, Error},
@@ -34,6 +43,196 @@ pub enum Connector {
     Rustls(std::sync::Arc<rustls::ClientConfig>),
 }
 
+/// Client TLS settings layered over the platform certificate verifier.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsClientOptions {
+    /// The optional PEM-encoded certificate chain presented to servers requiring mutual TLS.
+    pub client_cert_pem: Option<Vec<u8>>,
+    /// The optional PEM-encoded private key of `client_cert_pem`.
+    pub client_key_pem: Option<Vec<u8>>,
+    /// The optional PEM-encoded CA certificates trusted in addition to the platform roots.
+    pub root_ca_pem: Option<Vec<u8>>,
+    /// If server certificates are accepted without verification (test environments only).
+    pub danger_accept_invalid_certs: bool,
+    /// The protocols offered with ALPN, in order of preference.
+    pub alpn_protocols: Vec<Vec<u8>>,
+    /// The optional SHA-256 fingerprints of pinned server certificates, one of which the
+    /// leaf certificate must match in addition to passing verification.
+    pub pinned_cert_sha256: Option<Vec<[u8; 32]>>,
+}
+
+impl TlsClientOptions {
+    /// Returns `true` if the options do not change the platform defaults.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Builds a `rustls` client config from the options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client certificate, key or root CA certificates cannot
+    /// be parsed, or if only one of the client certificate and key is set.
+    pub fn build(&self) -> std::io::Result<Arc<ClientConfig>> {
+        let builder = ClientConfig::builder();
+        let provider = builder.crypto_provider().clone();
+        let mut verifier: Arc<dyn ServerCertVerifier> = if self.danger_accept_invalid_certs {
+            tracing::warn!("TLS server certificate verification is disabled");
+            Arc::new(NoCertificateVerification(provider))
+        } else if let Some(root_ca_pem) = &self.root_ca_pem {
+            let roots = parse_certs(root_ca_pem, "root CA certificate")?;
+            let verifier = Verifier::new_with_extra_roots(roots)
+                .map_err(|e| invalid_tls_input(&format!("Invalid root CA certificate: {e}")))?
+                .with_provider(provider);
+            Arc::new(verifier)
+        } else {
+            Arc::new(Verifier::new().with_provider(provider))
+        };
+        if let Some(pins) = &self.pinned_cert_sha256 {
+            verifier = Arc::new(PinnedCertVerifier {
+                inner: verifier,
+                pins: pins.clone(),
+            });
+        }
+        let builder = builder
+            .dangerous()
+            .with_custom_certificate_verifier(verifier);
+        let mut config = match (&self.client_cert_pem, &self.client_key_pem) {
+            (Some(cert_pem), Some(key_pem)) => {
+                let certs = parse_certs(cert_pem, "client certificate")?;
+                let key = PrivateKeyDer::from_pem_slice(key_pem)
+                    .map_err(|e| invalid_tls_input(&format!("Invalid client key: {e}")))?;
+                builder
+                    .with_client_auth_cert(certs, key)
+                    .map_err(|e| invalid_tls_input(&format!("Invalid client certificate: {e}")))?
+            }
+            (None, None) => builder.with_no_client_auth(),
+            _ => {
+                return Err(invalid_tls_input(
+                    "Client certificate and key must be set together",
+                ))
+            }
+        };
+        config.alpn_protocols.clone_from(&self.alpn_protocols);
+        Ok(Arc::new(config))
+    }
+}
+
+fn parse_certs(pem: &[u8], name: &str) -> std::io::Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_slice_iter(pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid_tls_input(&format!("Invalid {name}: {e}")))?;
+    if certs.is_empty() {
+        return Err(invalid_tls_input(&format!("No {name} found in PEM")));
+    }
+    Ok(certs)
+}
+
+/// Verifier which requires the leaf certificate to match a pinned SHA-256 fingerprint
+/// before delegating to the `inner` verifier.
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let digest = ring::digest::digest(&ring::digest::SHA256, end_entity.as_ref());
+        if !self.pins.iter().any(|pin| pin == digest.as_ref()) {
+            let fingerprint: String = digest.as_ref().iter().map(|b| format!("{b:02x}")).collect();
+            return Err(rustls::Error::General(format!(
+                "Server certificate SHA-256 fingerprint {fingerprint} matches no pinned certificate"
+            )));
+        }
+        self.inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Verifier which accepts any server certificate while still checking handshake signatures.
+#[derive(Debug)]
+struct NoCertificateVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn invalid_tls_input(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, msg)
+}
+
 mod encryption {
 
     pub mod rustls {
@@ -113,15 +312,33 @@ where
     MaybeTlsStream<S>: Unpin,
 {
     let domain = domain(request)?;
+    tcp_tls_with_server_name(domain, mode, stream, connector).await
+}
 
+/// Wraps `stream` as [`tcp_tls`] does, but advertises `server_name` during the
+/// TLS handshake in place of the request host.
+///
+/// # Errors
+///
+/// Returns an error if `server_name` is invalid or the TLS handshake fails.
+pub async fn tcp_tls_with_server_name<S>(
+    server_name: String,
+    mode: Mode,
+    stream: S,
+    connector: Option<Connector>,
+) -> Result<MaybeTlsStream<S>, Error>
+where
+    S: 'static + AsyncRead + AsyncWrite + Send + Unpin,
+    MaybeTlsStream<S>: Unpin,
+{
     match connector {
         Some(conn) => match conn {
             Connector::Rustls(conn) => {
-                self::encryption::rustls::wrap_stream(stream, domain, mode, Some(conn)).await
+                self::encryption::rustls::wrap_stream(stream, server_name, mode, Some(conn)).await
             }
             Connector::Plain => self::encryption::plain::wrap_stream(stream, mode).await,
         },
-        None => self::encryption::rustls::wrap_stream(stream, domain, mode, None).await,
+        None => self::encryption::rustls::wrap_stream(stream, server_name, mode, None).await,
     }
 }
 
@@ -133,3 +350,121 @@ fn domain(request: &Request) -> Result<String, Error> {
         None => panic!("No host name"),
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+#[cfg(target_os = "linux")] // Only run network tests on Linux (CI stability)
+mod tests {
+    use nautilus_cryptography::providers::install_cryptographic_provider;
+    use rstest::rstest;
+    use rustls::{
+        server::{ServerConfig, WebPkiClientVerifier},
+        RootCertStore,
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+    use tokio_rustls::TlsAcceptor;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    use super::*;
+
+    const CA_PEM: &[u8] = include_bytes!("../test_data/tls/ca.pem");
+    const SERVER_PEM: &[u8] = include_bytes!("../test_data/tls/server.pem");
+    const SERVER_KEY: &[u8] = include_bytes!("../test_data/tls/server.key");
+    const CLIENT_PEM: &[u8] = include_bytes!("../test_data/tls/client.pem");
+    const CLIENT_KEY: &[u8] = include_bytes!("../test_data/tls/client.key");
+
+    fn test_roots() -> Arc<RootCertStore> {
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_slice_iter(CA_PEM) {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        Arc::new(roots)
+    }
+
+    /// Binds a TLS server which requires clients to present a certificate issued by the test CA.
+    async fn bind_mtls_server() -> (u16, TcpListener, TlsAcceptor) {
+        let verifier = WebPkiClientVerifier::builder(test_roots()).build().unwrap();
+        let certs = CertificateDer::pem_slice_iter(SERVER_PEM)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = PrivateKeyDer::from_pem_slice(SERVER_KEY).unwrap();
+        let config = ServerConfig::builder()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        (port, listener, TlsAcceptor::from(Arc::new(config)))
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    #[tokio::test]
+    async fn test_mtls_client_certificate(#[case] present_cert: bool) {
+        install_cryptographic_provider();
+        let (port, listener, acceptor) = bind_mtls_server().await;
+        let server_task = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut stream = acceptor.accept(socket).await?;
+            let mut buf = [0u8; 5];
+            stream.read_exact(&mut buf).await?;
+            Ok::<_, std::io::Error>(buf)
+        });
+
+        let options = TlsClientOptions {
+            client_cert_pem: present_cert.then(|| CLIENT_PEM.to_vec()),
+            client_key_pem: present_cert.then(|| CLIENT_KEY.to_vec()),
+            root_ca_pem: Some(CA_PEM.to_vec()),
+            ..Default::default()
+        };
+        let connector = Connector::Rustls(options.build().unwrap());
+        let url = format!("127.0.0.1:{port}");
+        let request = url.as_str().into_client_request().unwrap();
+        let stream = TcpStream::connect(&url).await.unwrap();
+
+        // With TLS 1.3 the client finishes its handshake before the server verifies it
+        if let Ok(mut stream) = tcp_tls(&request, Mode::Tls, stream, Some(connector)).await {
+            let _ = stream.write_all(b"hello").await;
+        }
+
+        let result = server_task.await.unwrap();
+        if present_cert {
+            assert_eq!(&result.unwrap(), b"hello");
+        } else {
+            assert!(result.is_err());
+        }
+    }
+
+    #[rstest]
+    #[case(Some(CLIENT_PEM), None)]
+    #[case(None, Some(CLIENT_KEY))]
+    #[case(Some(b"not a certificate".as_slice()), Some(CLIENT_KEY))]
+    fn test_invalid_client_auth_rejected(#[case] cert: Option<&[u8]>, #[case] key: Option<&[u8]>) {
+        install_cryptographic_provider();
+        let options = TlsClientOptions {
+            client_cert_pem: cert.map(<[u8]>::to_vec),
+            client_key_pem: key.map(<[u8]>::to_vec),
+            ..Default::default()
+        };
+
+        assert!(options.build().is_err());
+    }
+
+    #[rstest]
+    fn test_invalid_root_ca_rejected() {
+        install_cryptographic_provider();
+        let options = TlsClientOptions {
+            root_ca_pem: Some(b"not a certificate".to_vec()),
+            ..Default::default()
+        };
+
+        assert!(options.build().is_err());
+    }
+}
@@ -3248,6 +3248,34 @@ class WebSocketClient:
     def send_text(self, data: bytes, keys: list[str] | None = None) -> Awaitable[None]: ...
     def send_pong(self, data: bytes) -> Awaitable[None]: ...
 
+class ProxyScheme(Enum):
+    Socks5 = 0
+    Http = 1
+
+class ProxyConfig:
+    def __init__(
+        self,
+        scheme: ProxyScheme,
+        host: str,
+        port: int,
+        username: str | None = None,
+        password: str | None = None,
+    ) -> None: ...
+
+class QueueOverflowPolicy(Enum):
+    Error = 0
+    DropOldest = 1
+    Block = 2
+
+class HandlerQueuePolicy(Enum):
+    Block = 0
+    Drop = 1
+
+class CircuitState(Enum):
+    Closed = 0
+    Open = 1
+    HalfOpen = 2
+
 class SocketConfig:
     def __init__(
         self,
@@ -3255,11 +3283,106 @@ class SocketConfig:
         ssl: bool,
         suffix: bytes,
         handler: Callable[..., Any],
-        heartbeat: tuple[int, bytes] | None = None,
+        heartbeat: tuple[float, bytes] | None = None,
         reconnect_timeout_secs: int | None = 30,
         max_reconnection_tries: int | None = 3,
+        reconnect_delay_initial_ms: int | None = None,
+        reconnect_delay_max_ms: int | None = None,
+        reconnect_backoff_factor: float | None = None,
+        reconnect_jitter: float | None = None,
+        controller_check_interval_ms: int | None = None,
+        handler_batch_size: int | None = None,
+        max_message_size: int | None = None,
+        on_overflow: Callable[[int], None] | None = None,
+        handler_queue_capacity: int | None = None,
+        handler_queue_overflow: HandlerQueuePolicy | None = None,
+        reconnect_queue_capacity: int | None = None,
+        reconnect_queue_overflow: QueueOverflowPolicy | None = None,
+        max_coalesce_bytes: int | None = None,
+        max_coalesce_frames: int | None = None,
+        deliver_unterminated_on_eof: bool = False,
+        read_idle_timeout_secs: int | None = None,
+        heartbeat_timeout_secs: int | None = None,
+        heartbeat_response: Callable[[bytes], bool] | None = None,
+        heartbeat_always_send: bool = False,
+        tcp_nodelay: bool = True,
+        tcp_keepalive_secs: int | None = None,
+        tcp_recv_buffer_size: int | None = None,
+        tcp_send_buffer_size: int | None = None,
+        connect_timeout_secs: int | None = None,
+        client_cert_pem: bytes | None = None,
+        client_key_pem: bytes | None = None,
+        root_ca_pem: bytes | None = None,
+        danger_accept_invalid_certs: bool = False,
+        tls_sni: str | None = None,
+        proxy: ProxyConfig | None = None,
+        fallback_urls: list[str] | None = None,
+        url_provider: Callable[[], str | tuple[str, bytes | None]] | None = None,
+        on_connect_send: list[bytes] | None = None,
+        send_wait_active_timeout_ms: int | None = None,
+        shutdown_linger_ms: int | None = None,
+        reconnect_on_eof: bool = True,
+        is_fatal_error: Callable[[str], bool] | None = None,
+        on_fatal: Callable[[str], None] | None = None,
+        on_give_up: Callable[[str, int], None] | None = None,
+        max_reconnect_duration_secs: int | None = None,
+        send_suffix: bytes | None = None,
+        handler_receive_ts: bool = False,
+        handler_queue_high_water: int | None = None,
+        handler_queue_low_water: int | None = None,
+        on_backpressure: Callable[[int, bool], None] | None = None,
+        drain_on_close: bool = True,
+        parse_json: bool = False,
+        on_json_error: Callable[[bytes, str], None] | None = None,
+        auto_flush: bool = True,
+        client_id: str | None = None,
+        shutdown_timeout_secs: int | None = None,
+        send_rate_limit: Quota | None = None,
+        on_send: Callable[[bytes], bytes] | None = None,
+        on_recv: Callable[[bytes], bytes] | None = None,
+        on_recv_error: Callable[[bytes, str], None] | None = None,
+        heartbeat_payload: Callable[[], bytes] | None = None,
+        heartbeat_append_suffix: bool = True,
+        on_established: Callable[[bool], None] | None = None,
+        bind_address: tuple[str, int] | None = None,
+        tls_handshake_timeout_secs: int | None = None,
+        alpn_protocols: list[bytes] | None = None,
+        pinned_cert_sha256: list[bytes] | None = None,
+        tcp_dscp: int | None = None,
+        tcp_linger_secs: int | None = None,
+        read_buffer_capacity: int | None = None,
+        handler_yield_every: int | None = None,
+        circuit_breaker: tuple[int, float, float] | None = None,
+        on_reconnect_attempt: Callable[[int], None] | None = None,
+        handler_receive_position: bool = False,
+        handler_concurrency: int = 1,
+        on_reconnect_send: list[bytes] | None = None,
+        max_handler_errors: int | None = None,
+        on_handler_error: Callable[[str], None] | None = None,
     ) -> None: ...
 
+class SocketStats:
+    @property
+    def bytes_sent(self) -> int: ...
+    @property
+    def bytes_received(self) -> int: ...
+    @property
+    def frames_sent(self) -> int: ...
+    @property
+    def frames_received(self) -> int: ...
+    @property
+    def reconnect_count(self) -> int: ...
+    @property
+    def handler_frames_dropped(self) -> int: ...
+    @property
+    def handler_queue_depth(self) -> int: ...
+    @property
+    def handler_queue_max_depth(self) -> int: ...
+    @property
+    def handler_latency_avg_us(self) -> int: ...
+    @property
+    def handler_latency_max_us(self) -> int: ...
+
 class SocketClient:
     @classmethod
     def connect(
@@ -3273,8 +3396,29 @@ class SocketClient:
     def is_reconnecting(self) -> bool: ...
     def is_disconnecting(self) -> bool: ...
     def is_closed(self) -> bool: ...
+    def peer_addr(self) -> str | None: ...
+    def active_url(self) -> str | None: ...
+    def alpn_protocol(self) -> bytes | None: ...
+    def raw_fd(self) -> int | None: ...
+    def last_exit_reason(self) -> str | None: ...
+    def seconds_since_last_recv(self) -> float | None: ...
+    def seconds_since_last_send(self) -> float | None: ...
+    def circuit_state(self) -> CircuitState: ...
+    def reconnect_count(self) -> int: ...
+    def uptime_secs(self) -> float | None: ...
+    def stats(self) -> SocketStats: ...
+    def local_addr(self) -> str | None: ...
+    def reconnect_now(self) -> Awaitable[None]: ...
+    def wait_until_active(self, timeout_secs: float) -> Awaitable[None]: ...
     def close(self) -> Awaitable[None]: ...
     def send(self, data: bytes) -> Awaitable[None]: ...
+    def send_flushed(self, data: bytes) -> Awaitable[None]: ...
+    def send_many(self, frames: list[bytes]) -> Awaitable[None]: ...
+    def flush(self) -> Awaitable[None]: ...
+    def try_send(self, data: bytes) -> None: ...
+    def pause_reading(self) -> None: ...
+    def resume_reading(self) -> None: ...
+    def is_reading_paused(self) -> bool: ...
 
 ###################################################################################################
 # Persistence